@@ -180,19 +180,19 @@ fn bench_concurrency(c: &mut Criterion) {
 
     // 测试不同线程数的性能
     for thread_count in [1, 2, 4, 8].iter() {
+        // 线程池在计时之外创建并复用，只测量并行计算本身
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(*thread_count)
+            .build()
+            .unwrap();
+
         group.bench_with_input(
             BenchmarkId::new("parallel_processing", thread_count),
             thread_count,
-            |b, &thread_count| {
+            |b, _| {
                 b.iter(|| {
                     use rayon::prelude::*;
 
-                    // 设置线程池大小
-                    let pool = rayon::ThreadPoolBuilder::new()
-                        .num_threads(thread_count)
-                        .build()
-                        .unwrap();
-
                     pool.install(|| {
                         let data: Vec<i32> = (0..1000).collect();
                         let result: Vec<_> = data
@@ -212,6 +212,40 @@ fn bench_concurrency(c: &mut Criterion) {
     group.finish();
 }
 
+/// 基准测试：用FFmpeg分析一段真实的测试音
+///
+/// 测试音由自检使用的正弦信号生成；FFmpeg无法运行时跳过。
+fn bench_analyze_file(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analyze_file");
+    // 每次迭代都要启动FFmpeg，减少采样次数
+    group.sample_size(10);
+
+    let temp_dir = TempDir::new().unwrap();
+    // 使用 FLAC 而不是 WAV，确保始终经过FFmpeg（不走 native-wav 路径）
+    let tone = temp_dir.path().join("tone.flac");
+    let config = AnalyzerConfig {
+        show_progress: false,
+        ..Default::default()
+    };
+    let mut analyzer = AudioAnalyzer::new(config).unwrap();
+    if let Err(e) = analyzer
+        .initialize_dependencies()
+        .and_then(|()| analyzer.write_test_tone(&tone))
+    {
+        eprintln!("无法生成测试音，跳过 analyze_file 基准测试: {e}");
+        return;
+    }
+
+    group.bench_function("sine_tone", |b| {
+        b.iter(|| {
+            let result = black_box(analyzer.analyze_file(&tone));
+            black_box(result)
+        })
+    });
+
+    group.finish();
+}
+
 /// 基准测试：Timer性能
 fn bench_timer_operations(c: &mut Criterion) {
     let mut group = c.benchmark_group("timer_operations");
@@ -242,6 +276,7 @@ criterion_group!(
     bench_analyzer_initialization,
     bench_memory_patterns,
     bench_concurrency,
+    bench_analyze_file,
     bench_timer_operations
 );
 
@@ -0,0 +1,52 @@
+//! # 构建脚本
+//!
+//! 在编译时计算当前编译目标嵌入的二进制文件（`assets/binaries/<目标平台>/`）的校验值，
+//! 写入 `$OUT_DIR/embedded_hashes.rs`，运行时不必再对数十MB的嵌入内容求校验值。
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 64位 FNV-1a 校验值，与解压时记录的校验值使用相同的算法
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// 编译目标对应的嵌入二进制文件目录，须与 `src/analyzer.rs` 中的 `#[cfg]` 选择一致
+fn embedded_dir() -> Option<&'static str> {
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    let os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    match (arch.as_str(), os.as_str()) {
+        ("aarch64", "macos") => Some("aarch64-apple-darwin"),
+        _ => None,
+    }
+}
+
+/// 嵌入文件的校验值，当前平台没有嵌入的文件时为 0
+fn embedded_hash(name: &str) -> u64 {
+    let Some(dir) = embedded_dir() else {
+        return 0;
+    };
+    let path = Path::new("assets/binaries").join(dir).join(name);
+    println!("cargo:rerun-if-changed={}", path.display());
+    let bytes = fs::read(&path)
+        .unwrap_or_else(|e| panic!("无法读取嵌入的二进制文件 {}: {e}", path.display()));
+    fnv1a_hash(&bytes)
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let source = format!(
+        "/// 嵌入的FFmpeg的校验值，用于判断固定解压目录中的文件能否复用\n\
+         const FFMPEG_HASH: u64 = {:#018x};\n\
+         /// 嵌入的Python分析器的校验值\n\
+         const ANALYZER_HASH: u64 = {:#018x};\n",
+        embedded_hash("ffmpeg"),
+        embedded_hash("audio-analyzer"),
+    );
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo 未设置 OUT_DIR"));
+    fs::write(out_dir.join("embedded_hashes.rs"), source).expect("无法写出嵌入文件的校验值");
+}
@@ -2,8 +2,11 @@
 //!
 //! 演示如何使用音频分析器库进行基本的音频质量分析
 
-use audio_analyzer_ultimate::{utils::Timer, AnalyzerConfig, AudioAnalyzer, Result};
+use audio_analyzer_ultimate::{
+    utils::Timer, AnalysisSummary, AnalyzerConfig, AudioAnalyzer, Result,
+};
 use std::path::Path;
+use std::time::Duration;
 
 fn main() -> Result<()> {
     println!("音频质量分析器 - 基本使用示例");
@@ -50,18 +53,17 @@ fn custom_config_example() -> Result<()> {
     println!("\n⚙️ 示例2: 自定义配置");
     println!("{}", "-".repeat(40));
 
-    // 创建自定义配置
-    let mut config = AnalyzerConfig {
-        verbose: true,
-        show_progress: true,
-        num_threads: Some(4),
-        supported_extensions: vec!["wav".to_string(), "flac".to_string(), "mp3".to_string()],
-        ..Default::default()
-    };
+    // 创建自定义配置（build 时会自动验证）
+    let mut config = AnalyzerConfig::builder()
+        .verbose(true)
+        .threads(4)
+        .extensions(["wav", "flac", "mp3"])
+        .build()?;
 
-    // 自定义质量阈值
+    // 自定义质量阈值（须保持 lra_excellent_max < lra_acceptable_max）
     config.quality_thresholds.lra_excellent_min = 10.0;
-    config.quality_thresholds.lra_excellent_max = 15.0;
+    config.quality_thresholds.lra_excellent_max = 14.0;
+    config.quality_thresholds.validate()?;
 
     println!("📋 自定义配置:");
     println!("  - 详细输出: {}", config.verbose);
@@ -146,7 +148,7 @@ fn batch_analysis_example() -> Result<()> {
                 println!("📊 处理了 {} 个文件", results.len());
 
                 // 显示统计信息
-                print_batch_summary(&results);
+                print_batch_summary(&results, timer.elapsed());
             }
             Err(e) => {
                 println!("❌ 批量分析失败: {e}");
@@ -179,56 +181,62 @@ fn print_metrics_summary(metrics: &audio_analyzer_ultimate::AudioMetrics) {
         println!("  - 峰值: {peak:.1} dB");
     }
 
-    if let Some(rms_18k) = metrics.rms_db_above_18k {
+    if let Some(rms_18k) = metrics.rms_db_above(18000) {
         println!("  - 18kHz以上RMS: {rms_18k:.1} dB");
     }
 
     println!("  - 处理时间: {} ms", metrics.processing_time_ms);
-    println!(
-        "  - 数据完整性: {}",
-        if metrics.is_complete() {
-            "✅ 完整"
-        } else {
-            "⚠️ 不完整"
-        }
-    );
+    let missing = metrics.missing_metrics();
+    if missing.is_empty() {
+        println!("  - 数据完整性: ✅ 完整");
+    } else {
+        println!("  - 数据完整性: ⚠️ 不完整（缺少 {}）", missing.join(", "));
+    }
 }
 
 /// 打印批量分析结果摘要
-fn print_batch_summary(results: &[audio_analyzer_ultimate::AudioMetrics]) {
+fn print_batch_summary(results: &[audio_analyzer_ultimate::AudioMetrics], elapsed: Duration) {
     if results.is_empty() {
         println!("📊 没有找到可分析的文件");
         return;
     }
 
-    let total_files = results.len();
-    let complete_files = results.iter().filter(|m| m.is_complete()).count();
-    let total_size: u64 = results.iter().map(|m| m.file_size_bytes).sum();
-    let total_time: u64 = results.iter().map(|m| m.processing_time_ms).sum();
+    let summary = AnalysisSummary::from_metrics(results).with_wall_clock(elapsed);
 
     println!("📊 批量分析统计:");
-    println!("  - 总文件数: {total_files}");
+    println!("  - 总文件数: {}", summary.total_files);
     println!(
         "  - 完整分析: {} ({:.1}%)",
-        complete_files,
-        complete_files as f64 / total_files as f64 * 100.0
+        summary.complete_files,
+        summary.complete_percentage()
+    );
+    println!(
+        "  - 总大小: {:.2} MB",
+        summary.total_size_bytes as f64 / 1024.0 / 1024.0
+    );
+    println!(
+        "  - 总处理时间: {:.2} 秒",
+        summary.total_processing_ms as f64 / 1000.0
     );
-    println!("  - 总大小: {:.2} MB", total_size as f64 / 1024.0 / 1024.0);
-    println!("  - 总处理时间: {:.2} 秒", total_time as f64 / 1000.0);
     println!(
         "  - 平均处理时间: {:.0} ms/文件",
-        total_time as f64 / total_files as f64
+        summary.average_processing_ms
     );
+    if let (Some(files), Some(megabytes)) = (summary.files_per_second, summary.megabytes_per_second)
+    {
+        println!("  - 吞吐量: {files:.2} 文件/秒, {megabytes:.2} MB/秒");
+    }
 
     // LRA 统计
-    let lra_values: Vec<f64> = results.iter().filter_map(|m| m.lra).collect();
-
-    if !lra_values.is_empty() {
-        let avg_lra = lra_values.iter().sum::<f64>() / lra_values.len() as f64;
-        let min_lra = lra_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_lra = lra_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    if let Some(lra) = summary.lra {
+        println!(
+            "  - LRA统计: 平均 {:.1} LU, 中位数 {:.1} LU, 范围 {:.1}-{:.1} LU",
+            lra.mean, lra.median, lra.min, lra.max
+        );
+    }
 
-        println!("  - LRA统计: 平均 {avg_lra:.1} LU, 范围 {min_lra:.1}-{max_lra:.1} LU");
+    for (verdict, count) in &summary.verdicts {
+        println!("  - {}: {count}", verdict.description());
     }
 }
 
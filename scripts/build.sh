@@ -12,6 +12,11 @@ YELLOW='\033[1;33m'
 BLUE='\033[0;34m'
 NC='\033[0m' # No Color
 
+# 嵌入的二进制文件按 Rust 目标平台分目录（如 assets/binaries/aarch64-apple-darwin），
+# 默认为当前主机，可通过 TARGET_TRIPLE 环境变量指定
+TARGET_TRIPLE="${TARGET_TRIPLE:-$(rustc -vV 2>/dev/null | sed -n 's/^host: //p')}"
+BINARIES_DIR="assets/binaries/${TARGET_TRIPLE:-unknown}"
+
 # 日志函数
 log_info() {
     echo -e "${BLUE}[INFO]${NC} $1"
@@ -158,13 +163,13 @@ build_python_analyzer_uv() {
     uv run pyinstaller --onefile \
                        --name audio-analyzer \
                        --clean \
-                       --distpath assets/binaries \
+                       --distpath "$BINARIES_DIR" \
                        src/bin/audio_analyzer.py
 
     # 验证构建结果
-    if [ -f "assets/binaries/audio-analyzer" ]; then
+    if [ -f "$BINARIES_DIR/audio-analyzer" ]; then
         log_success "Python 分析器构建成功"
-        chmod +x assets/binaries/audio-analyzer
+        chmod +x "$BINARIES_DIR/audio-analyzer"
     else
         log_error "Python 分析器构建失败"
         exit 1
@@ -192,13 +197,13 @@ build_python_analyzer() {
         pyinstaller --onefile \
                     --name audio-analyzer \
                     --clean \
-                    --distpath assets/binaries \
+                    --distpath "$BINARIES_DIR" \
                     src/bin/audio_analyzer.py
 
         # 验证构建结果
-        if [ -f "assets/binaries/audio-analyzer" ]; then
+        if [ -f "$BINARIES_DIR/audio-analyzer" ]; then
             log_success "Python 分析器构建成功"
-            chmod +x assets/binaries/audio-analyzer
+            chmod +x "$BINARIES_DIR/audio-analyzer"
         else
             log_error "Python 分析器构建失败"
             exit 1
@@ -286,7 +291,7 @@ verify_build() {
     fi
     
     # 检查 Python 分析器
-    if [ -f "assets/binaries/audio-analyzer" ]; then
+    if [ -f "$BINARIES_DIR/audio-analyzer" ]; then
         log_success "Python 分析器存在"
     else
         log_warning "Python 分析器不存在，某些功能可能不可用"
@@ -401,7 +406,7 @@ main() {
     echo "可执行文件位置:"
     echo "  - Rust 主程序: target/release/audio-analyzer"
     if [ "$SKIP_PYTHON" = false ]; then
-        echo "  - Python 分析器: assets/binaries/audio-analyzer"
+        echo "  - Python 分析器: $BINARIES_DIR/audio-analyzer"
     fi
     echo ""
     echo "使用 './target/release/audio-analyzer --help' 查看使用说明"
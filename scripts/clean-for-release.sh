@@ -136,8 +136,8 @@ fi
 define_clean_targets() {
     # 基础清理目标（保留重要的二进制依赖文件）
     BUILD_ARTIFACTS="target/ build/ dist/ releases/"
-    # 只清理 assets/binaries/ 中的构建产物，保留 ffmpeg 等依赖文件
-    BINARY_ARTIFACTS="assets/binaries/audio-analyzer"
+    # 只清理 assets/binaries/<目标平台>/ 中的构建产物，保留 ffmpeg 等依赖文件
+    BINARY_ARTIFACTS="assets/binaries/*/audio-analyzer"
     PYTHON_CACHE="__pycache__/ *.pyc *.pyo *.pyd .pytest_cache/ .coverage htmlcov/"
     SYSTEM_TEMP=".DS_Store .DS_Store? ._* .Spotlight-V100 .Trashes ehthumbs.db Thumbs.db"
     EDITOR_TEMP="*.swp *.swo *~ .vscode/.ropeproject .idea/"
@@ -15,6 +15,11 @@ PURPLE='\033[0;35m'
 CYAN='\033[0;36m'
 NC='\033[0m' # No Color
 
+# 嵌入的二进制文件按 Rust 目标平台分目录（如 assets/binaries/aarch64-apple-darwin），
+# 默认为当前主机，可通过 TARGET_TRIPLE 环境变量指定
+TARGET_TRIPLE="${TARGET_TRIPLE:-$(rustc -vV 2>/dev/null | sed -n 's/^host: //p')}"
+BINARIES_DIR="assets/binaries/${TARGET_TRIPLE:-unknown}"
+
 # 日志函数
 log_info() {
     echo -e "${BLUE}[INFO]${NC} $1"
@@ -218,7 +223,7 @@ build_analyzer() {
     fi
     
     # 确保输出目录存在
-    mkdir -p assets/binaries
+    mkdir -p "$BINARIES_DIR"
     
     start_timer
     
@@ -228,7 +233,7 @@ build_analyzer() {
         --onefile \
         --name audio-analyzer \
         --clean \
-        --distpath assets/binaries \
+        --distpath "$BINARIES_DIR" \
         --workpath build \
         --specpath . \
         src/bin/audio_analyzer.py
@@ -236,12 +241,12 @@ build_analyzer() {
     end_timer
     
     # 验证构建结果
-    if [ -f "assets/binaries/audio-analyzer" ]; then
-        chmod +x assets/binaries/audio-analyzer
-        BINARY_SIZE=$(du -h assets/binaries/audio-analyzer | cut -f1)
+    if [ -f "$BINARIES_DIR/audio-analyzer" ]; then
+        chmod +x "$BINARIES_DIR/audio-analyzer"
+        BINARY_SIZE=$(du -h "$BINARIES_DIR/audio-analyzer" | cut -f1)
         log_success "Python 分析器构建成功"
         log_info "可执行文件大小: $BINARY_SIZE"
-        log_info "可执行文件路径: assets/binaries/audio-analyzer"
+        log_info "可执行文件路径: $BINARIES_DIR/audio-analyzer"
     else
         log_error "Python 分析器构建失败"
         exit 1
@@ -290,11 +295,11 @@ health_check() {
     fi
     
     # 检查 Python 分析器
-    if [ -f "assets/binaries/audio-analyzer" ]; then
+    if [ -f "$BINARIES_DIR/audio-analyzer" ]; then
         log_success "Python 分析器存在"
         
         # 测试 Python 分析器
-        if ./assets/binaries/audio-analyzer --help > /dev/null 2>&1; then
+        if "./$BINARIES_DIR/audio-analyzer" --help > /dev/null 2>&1; then
             log_success "Python 分析器运行正常"
         else
             log_warning "Python 分析器运行异常"
@@ -343,8 +348,8 @@ show_performance_stats() {
         log_info "Rust 主程序大小: $RUST_SIZE"
     fi
     
-    if [ -f "assets/binaries/audio-analyzer" ]; then
-        PYTHON_SIZE=$(du -h assets/binaries/audio-analyzer | cut -f1)
+    if [ -f "$BINARIES_DIR/audio-analyzer" ]; then
+        PYTHON_SIZE=$(du -h "$BINARIES_DIR/audio-analyzer" | cut -f1)
         log_info "Python 分析器大小: $PYTHON_SIZE"
     fi
 }
@@ -435,7 +440,7 @@ main() {
     echo ""
     echo "📁 构建产物:"
     echo "  - Rust 主程序: target/release/audio-analyzer"
-    echo "  - Python 分析器: assets/binaries/audio-analyzer"
+    echo "  - Python 分析器: $BINARIES_DIR/audio-analyzer"
     echo ""
     echo "🚀 使用方法:"
     echo "  ./target/release/audio-analyzer --help"
@@ -2,21 +2,38 @@
 //!
 //! 提供音频文件分析的核心功能，包括FFmpeg集成、并行处理和数据提取。
 
-use crate::config::AnalyzerConfig;
+use crate::cache::AnalysisCache;
+use crate::compare::MetricsDiff;
+use crate::config::{AnalyzerConfig, ConfigOverrides, ProfileMatcher, ProfileRule};
 use crate::error::{AnalyzerError, Result};
-use crate::types::{AudioMetrics, AudioStats};
-use crate::utils::{fs_utils, process_utils, Timer};
+use crate::fingerprint;
+use crate::messages;
+use crate::progress::{LogObserver, ProgressObserver};
+use crate::types::{
+    highpass_field_name, AnalysisProgress, AnalysisReport, AnalysisWindow, AudioMetrics,
+    AudioStats, ChannelStats, Ebur128Summary, EnvironmentReport, FormatInfo, NotAudioReason,
+    SpectralFlatness, SILENT_BAND_DB,
+};
+use crate::utils::process_utils::{self, Semaphore};
+use crate::utils::{fs_utils, stats, Timer};
 
 use lazy_static::lazy_static;
+use log::{debug, info, warn};
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::OsStr;
 use std::fs::{self, File};
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::thread;
+use std::time::Duration;
 use tempfile::TempDir;
 
 // 预编译的正则表达式，用于解析FFmpeg输出
@@ -27,13 +44,64 @@ lazy_static! {
 
     /// EBU R128 汇总 LRA 提取正则表达式
     static ref EBUR128_SUMMARY_LRA_REGEX: Regex =
-        Regex::new(r"(?m)^LRA:\s*([0-9.-]+)\s*LU\s*$").unwrap();
+        Regex::new(r"(?m)^\s*LRA:\s*([0-9.-]+)\s*LU\s*$").unwrap();
 
-    /// 基础统计信息提取正则表达式
+    /// EBU R128 综合响度提取正则表达式（逐帧输出与汇总均匹配）
+    static ref EBUR128_I_REGEX: Regex =
+        Regex::new(r"I:\s*([-\d.]+)\s*LUFS").unwrap();
+
+    /// EBU R128 汇总综合响度提取正则表达式
+    static ref EBUR128_SUMMARY_I_REGEX: Regex =
+        Regex::new(r"(?m)^\s*I:\s*([-\d.]+)\s*LUFS\s*$").unwrap();
+
+    /// EBU R128 汇总真峰值提取正则表达式（允许正值，用于捕获样本间峰值）
+    static ref EBUR128_SUMMARY_TRUE_PEAK_REGEX: Regex =
+        Regex::new(r"(?m)^\s*Peak:\s*([-+]?[\d.]+)\s*dBFS\s*$").unwrap();
+
+    /// EBU R128 逐帧真峰值提取正则表达式（每个声道一个值）
+    static ref EBUR128_TPK_REGEX: Regex =
+        Regex::new(r"(?:^|\s)TPK:\s*((?:\S+\s+)+?)dBFS").unwrap();
+
+    /// astats Overall 块中峰值与RMS提取正则表达式（作用于去掉日志前缀的滤镜输出）
     static ref ASTATS_OVERALL_REGEX: Regex = Regex::new(
-        r"(?m)^\[Parsed_astats_0 @ [^\]]+\] Overall\s*\n(?:[^\n]*\n)*?[^\n]*Peak level dB:\s*([-\d.]+)\s*\n(?:[^\n]*\n)*?[^\n]*RMS level dB:\s*([-\d.]+)"
+        r"(?m)^Overall\s*\n(?:[^\n]*\n)*?[^\n]*Peak level dB:\s*([-\d.]+)\s*\n(?:[^\n]*\n)*?[^\n]*RMS level dB:\s*([-\d.]+)"
     ).unwrap();
 
+    /// astats Overall 块起始行
+    static ref ASTATS_OVERALL_HEADER_REGEX: Regex = Regex::new(r"(?m)^Overall\s*$").unwrap();
+
+    /// astats 声道块起始行
+    static ref ASTATS_CHANNEL_HEADER_REGEX: Regex =
+        Regex::new(r"^Channel:\s*(\d+)\s*$").unwrap();
+
+    /// 直流偏移提取正则表达式
+    static ref ASTATS_DC_OFFSET_REGEX: Regex =
+        Regex::new(r"(?m)^DC offset:\s*([-+]?[\d.]+(?:e[-+]?\d+)?)").unwrap();
+
+    /// 波峰因数提取正则表达式（astats 输出为线性比值）
+    static ref ASTATS_CREST_FACTOR_REGEX: Regex =
+        Regex::new(r"(?m)^Crest factor:\s*([\d.]+)").unwrap();
+
+    /// 峰值采样数提取正则表达式（旧版FFmpeg输出为浮点数）
+    static ref ASTATS_PEAK_COUNT_REGEX: Regex =
+        Regex::new(r"(?m)^Peak count:\s*([\d.]+)").unwrap();
+
+    /// 采样数提取正则表达式
+    static ref ASTATS_SAMPLE_COUNT_REGEX: Regex =
+        Regex::new(r"(?m)^Number of samples:\s*(\d+)").unwrap();
+
+    /// 短时RMS最大值提取正则表达式（按 astats 的测量窗口计算）
+    static ref ASTATS_RMS_PEAK_REGEX: Regex =
+        Regex::new(r"(?m)^RMS peak dB:\s*(-inf|[-\d.]+)").unwrap();
+
+    /// 短时RMS最小值提取正则表达式（含完全静音的窗口时为 `-inf`）
+    static ref ASTATS_RMS_TROUGH_REGEX: Regex =
+        Regex::new(r"(?m)^RMS trough dB:\s*(-inf|[-\d.]+)").unwrap();
+
+    /// 平顶系数提取正则表达式
+    static ref ASTATS_FLAT_FACTOR_REGEX: Regex =
+        Regex::new(r"(?m)^Flat factor:\s*(-inf|[-\d.]+)").unwrap();
+
     /// 简单峰值提取正则表达式
     static ref SIMPLE_PEAK_REGEX: Regex =
         Regex::new(r"Peak level dB:\s*([-\d.]+)").unwrap();
@@ -42,43 +110,208 @@ lazy_static! {
     static ref SIMPLE_RMS_REGEX: Regex =
         Regex::new(r"RMS level dB:\s*([-\d.]+)").unwrap();
 
-    /// 高通滤波后的RMS提取正则表达式
+    /// 高通滤波后 astats Overall 块中RMS提取正则表达式（静音频段为 `-inf`）
     static ref HIGHPASS_ASTATS_REGEX: Regex = Regex::new(
-        r"(?m)^\[Parsed_astats_1 @ [^\]]+\] Overall\s*\n(?:[^\n]*\n)*?[^\n]*RMS level dB:\s*([-\d.]+)"
+        r"(?m)^Overall\s*\n(?:[^\n]*\n)*?[^\n]*RMS level dB:\s*(-inf|[-\d.]+)"
+    ).unwrap();
+
+    /// 输入文件音频流信息行提取正则表达式
+    static ref AUDIO_STREAM_REGEX: Regex =
+        Regex::new(r"(?m)^\s*Stream #0:\d+\S*: Audio: (.+)$").unwrap();
+
+    /// 输入文件中作为封面的图片流提取正则表达式
+    static ref ATTACHED_PICTURE_REGEX: Regex =
+        Regex::new(r"(?m)^\s*Stream #0:\d+\S*: Video: .*\(attached pic\)").unwrap();
+
+    /// 音频流码率提取正则表达式（如 `320 kb/s`）
+    static ref BITRATE_REGEX: Regex = Regex::new(r"^(\d+) kb/s").unwrap();
+
+    /// 容器平均码率提取正则表达式（`Duration` 行中的 `bitrate: 256 kb/s`）
+    static ref CONTAINER_BITRATE_REGEX: Regex =
+        Regex::new(r"Duration:.*\bbitrate:\s*(\d+) kb/s").unwrap();
+
+    /// 编码描述中括号内的内容（解码器名称、编码规格与编码标签）
+    static ref CODEC_DETAIL_REGEX: Regex = Regex::new(r"\(([^()]+)\)").unwrap();
+
+    /// 采样率提取正则表达式
+    static ref SAMPLE_RATE_REGEX: Regex = Regex::new(r"^(\d+) Hz$").unwrap();
+
+    /// 显式位深度提取正则表达式（如 `s32 (24 bit)`）
+    static ref BIT_DEPTH_REGEX: Regex = Regex::new(r"\((\d+) bit\)").unwrap();
+
+    /// FFmpeg 组件日志行的前缀（如 `[flac @ 0x55d1] `），捕获其后的消息
+    ///
+    /// `Input #0` 头部、流信息与元数据标签等行没有这一前缀。
+    static ref COMPONENT_LOG_LINE_REGEX: Regex =
+        Regex::new(r"^\[[^\]]+ @ 0x[0-9a-fA-F]+\]\s*(.*)$").unwrap();
+
+    /// 解码器报告文件损坏或截断的消息
+    static ref DECODE_WARNING_REGEX: Regex = Regex::new(
+        r"(?i)invalid data found|truncat|error while decoding|header missing|corrupt|partial file|invalid residual|invalid frame|overread|lost sync|packet too small|incomplete frame"
     ).unwrap();
+
+    /// 高通滤波后RMS行提取正则表达式（静音频段为 `-inf`）
+    static ref HIGHPASS_RMS_REGEX: Regex =
+        Regex::new(r"RMS level dB:\s*(-inf|[-\d.]+)").unwrap();
+
+    /// astats 中 `名称: 数值` 形式的行
+    static ref ASTATS_FIELD_REGEX: Regex =
+        Regex::new(r"^([A-Za-z][^:]*?):\s*(\S+)\s*$").unwrap();
+
+    /// EBU R128 逐帧输出中的时间与短期响度（`t: 1.2 ... S: -18.3`，无信号时为 `-inf`）
+    static ref EBUR128_FRAME_SHORT_TERM_REGEX: Regex =
+        Regex::new(r"\bt:\s*([\d.]+)\s.*?(?:^|\s)S:\s*(-inf|-?[\d.]+)").unwrap();
+
+    /// 输入文件时长提取正则表达式（如 `Duration: 00:03:12.00`）
+    static ref DURATION_REGEX: Regex =
+        Regex::new(r"Duration:\s*(\d+):(\d{2}):(\d{2}(?:\.\d+)?)").unwrap();
+
+    /// silencedetect 静音起点提取正则表达式
+    static ref SILENCE_START_REGEX: Regex =
+        Regex::new(r"silence_start:\s*(-?[\d.]+)").unwrap();
+
+    /// aspectralstats 逐帧频谱平坦度（`ametadata` 打印的 `lavfi.aspectralstats.<声道>.flatness=<值>`）
+    static ref SPECTRAL_FLATNESS_REGEX: Regex =
+        Regex::new(r"lavfi\.aspectralstats\.\d+\.flatness=(\S+)").unwrap();
+
+    /// astats 本底噪声提取正则表达式（整段静音时为 `-inf`）
+    static ref ASTATS_NOISE_FLOOR_REGEX: Regex =
+        Regex::new(r"(?m)^Noise floor dB:\s*(-inf|[-\d.]+)").unwrap();
+
+    /// silencedetect 静音终点与时长提取正则表达式
+    static ref SILENCE_END_REGEX: Regex =
+        Regex::new(r"silence_end:\s*(-?[\d.]+)\s*\|\s*silence_duration:\s*([\d.]+)").unwrap();
 }
 
-/// 嵌入的二进制依赖文件
-const FFMPEG_BYTES: &[u8] = include_bytes!("../assets/binaries/ffmpeg");
-const ANALYZER_BYTES: &[u8] = include_bytes!("../assets/binaries/audio-analyzer");
+/// 整体统计分支的 astats 实例名，用于在合并的stderr中区分各分支输出
+const OVERALL_ASTATS_INSTANCE: &str = "astats@overall";
+
+/// 相位分支的 astats 实例名
+const PHASE_ASTATS_INSTANCE: &str = "astats@phase";
+
+/// 频谱平坦度分析中估计本底噪声的 astats 实例名
+const NOISE_FLOOR_ASTATS_INSTANCE: &str = "astats@noisefloor";
+
+/// 频谱平坦度分析中打印逐帧元数据的 ametadata 实例名
+const FLATNESS_METADATA_INSTANCE: &str = "ametadata@flatness";
+
+/// 静音检测分支的 silencedetect 实例名
+const SILENCE_DETECT_INSTANCE: &str = "silencedetect@silence";
+
+/// 低于该电平 (dB) 的音频视为静音
+const SILENCE_NOISE_DB: f64 = -60.0;
+
+/// 计入静音的最短持续时间（秒），过滤乐句间的短暂停顿
+const SILENCE_MIN_DURATION_SECS: f64 = 0.5;
+
+/// 流式分析时每批文件数相对于线程数的倍数
+const STREAMING_BATCH_FACTOR: usize = 4;
+
+/// 校验外部FFmpeg时 `-version` 的最长等待时间
+const FFMPEG_VALIDATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// FFmpeg 暂时性失败后第一次重试前的等待时间，之后每次加倍
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// 计算声学指纹时读取的最长音频时长（秒），与 `fpcalc` 的默认值相同
+const FINGERPRINT_DURATION_SECS: u32 = 120;
+
+/// 每个文件最多保留的解码警告条数
+const MAX_DECODE_WARNINGS: usize = 20;
+
+/// 自检测试音的频率 (Hz)
+const SELF_TEST_FREQUENCY_HZ: u32 = 1000;
+
+/// 自检测试音的时长（秒），LRA 至少需要数秒的短期响度样本
+const SELF_TEST_DURATION_SECS: u32 = 5;
+
+/// 嵌入的二进制依赖文件，按编译目标从 `assets/binaries/<目标平台>/` 中选择
+///
+/// 目前只提供 macOS ARM64 版本；其他平台没有内置的二进制文件，需要使用外部FFmpeg。
+#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+const FFMPEG_BYTES: Option<&[u8]> = Some(include_bytes!(
+    "../assets/binaries/aarch64-apple-darwin/ffmpeg"
+));
+#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+const ANALYZER_BYTES: Option<&[u8]> = Some(include_bytes!(
+    "../assets/binaries/aarch64-apple-darwin/audio-analyzer"
+));
+#[cfg(not(all(target_arch = "aarch64", target_os = "macos")))]
+const FFMPEG_BYTES: Option<&[u8]> = None;
+#[cfg(not(all(target_arch = "aarch64", target_os = "macos")))]
+const ANALYZER_BYTES: Option<&[u8]> = None;
+
+// 嵌入内容的校验值（`FFMPEG_HASH`、`ANALYZER_HASH`）由构建脚本在编译时计算
+include!(concat!(env!("OUT_DIR"), "/embedded_hashes.rs"));
+
+/// 当前平台（如 `macos/aarch64`）
+fn current_platform() -> String {
+    format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH)
+}
 
 /// 音频分析器主结构
+///
+/// `AudioAnalyzer` 是 `Send + Sync` 的：初始化依赖项之后可以放进 `Arc`，
+/// 在多个线程或异步任务中同时调用 [`AudioAnalyzer::analyze_file`]。
+/// 解压出的依赖项所在的临时目录只在 `AudioAnalyzer` 被释放时删除。
 pub struct AudioAnalyzer {
     /// 配置信息
     config: AnalyzerConfig,
+    /// 编译后的 `profile_rules`
+    profiles: ProfileMatcher,
     /// 依赖项句柄
     dependencies: Option<DependencyHandle>,
+    /// 批量分析进度观察者
+    progress_observer: Option<Arc<dyn ProgressObserver>>,
+    /// FFmpeg并发数限制（未配置 `max_concurrent_ffmpeg` 时为 `None`）
+    ffmpeg_limiter: Option<Semaphore>,
 }
 
 /// 依赖项管理句柄
 struct DependencyHandle {
     /// FFmpeg 可执行文件路径
     ffmpeg_path: PathBuf,
-    /// Python 分析器可执行文件路径
-    analyzer_path: PathBuf,
-    /// 临时目录（保持引用以防止被删除）
-    _temp_dir: TempDir,
+    /// 解压目录
+    extraction_dir: PathBuf,
+    /// 解压目录是否为可复用的固定目录
+    reusable: bool,
+    /// Python 分析器可执行文件路径，首次使用时解压（解压失败时为 `None`）
+    analyzer_path: OnceLock<Option<PathBuf>>,
+    /// FFmpeg 是否已被发现缺少 `chromaprint` 复用器（之后不再尝试计算指纹）
+    chromaprint_missing: AtomicBool,
+    /// 临时目录（保持引用以防止被删除；使用固定解压目录时为 `None`）
+    _temp_dir: Option<TempDir>,
 }
 
 impl AudioAnalyzer {
     /// 创建新的音频分析器实例
+    ///
+    /// `config.locale` 只影响此分析器产生的提示与错误信息，不改变进程的界面语言。
     pub fn new(config: AnalyzerConfig) -> Result<Self> {
-        // 验证配置
-        config.validate()?;
+        // 验证配置，提示使用该分析器的语言
+        let profiles = messages::with_locale(config.locale, || -> Result<ProfileMatcher> {
+            config.validate()?;
+            if let Some(message) = config.unknown_extensions_warning() {
+                warn!("{}: {message}", messages::text("警告", "Warning"));
+            }
+            config.profile_matcher()
+        })?;
+
+        // 默认沿用命令行的进度输出
+        let progress_observer: Option<Arc<dyn ProgressObserver>> = if config.show_progress {
+            Some(Arc::new(LogObserver))
+        } else {
+            None
+        };
+
+        let ffmpeg_limiter = config.max_concurrent_ffmpeg.map(Semaphore::new);
 
         Ok(Self {
             config,
+            profiles,
             dependencies: None,
+            progress_observer,
+            ffmpeg_limiter,
         })
     }
 
@@ -89,48 +322,211 @@ impl AudioAnalyzer {
 
     /// 初始化依赖项（解压嵌入的二进制文件）
     ///
-    /// 性能优化：使用并行解压和优化的I/O操作
+    /// 配置了外部FFmpeg（`ffmpeg.binary_path` 或 `AUDIO_ANALYZER_FFMPEG` 环境变量）时，
+    /// 不再解压嵌入的FFmpeg，而是通过 `ffmpeg -version` 校验该路径可以执行。
+    /// 嵌入的Python分析器推迟到 [`AudioAnalyzer::get_analyzer_path`] 首次调用时解压。
     pub fn initialize_dependencies(&mut self) -> Result<()> {
-        if self.dependencies.is_some() {
-            return Ok(()); // 已经初始化过了
+        messages::with_locale(self.config.locale, || {
+            if self.dependencies.is_some() {
+                return Ok(()); // 已经初始化过了
+            }
+
+            let timer = Timer::new("依赖项初始化");
+
+            // 使用配置的固定解压目录，否则创建临时目录
+            let (temp_dir, extraction_dir) = match &self.config.extraction_dir {
+                Some(dir) => {
+                    fs_utils::ensure_dir_exists(dir).map_err(|e| {
+                        AnalyzerError::DependencyError(crate::tr!(
+                            "创建解压目录失败: {}（{}）",
+                            "Failed to create the extraction directory: {} ({})",
+                            dir.display(),
+                            e
+                        ))
+                    })?;
+                    (None, dir.clone())
+                }
+                None => {
+                    let temp_dir = tempfile::Builder::new()
+                        .prefix("audio_analyzer_")
+                        .tempdir()
+                        .map_err(|e| {
+                            AnalyzerError::DependencyError(crate::tr!(
+                                "创建临时目录失败: {e}",
+                                "Failed to create a temporary directory: {e}"
+                            ))
+                        })?;
+                    let path = temp_dir.path().to_path_buf();
+                    (Some(temp_dir), path)
+                }
+            };
+
+            debug!(
+                "{}",
+                messages::text("正在初始化依赖项...", "Initializing dependencies...")
+            );
+
+            let external_ffmpeg = self.config.ffmpeg.resolve_binary_path();
+            let ffmpeg_path = external_ffmpeg
+                .clone()
+                .unwrap_or_else(|| extraction_dir.join("ffmpeg"));
+
+            // Python分析器只在首次调用 `get_analyzer_path` 时解压
+            let reusable = temp_dir.is_none();
+            match (&external_ffmpeg, FFMPEG_BYTES) {
+                (Some(path), _) => self.validate_external_ffmpeg(path)?,
+                (None, Some(bytes)) => {
+                    self.extract_embedded(bytes, FFMPEG_HASH, &ffmpeg_path, "FFmpeg", reusable)?
+                }
+                (None, None) => {
+                    return Err(AnalyzerError::DependencyError(crate::tr!(
+                    "当前平台 ({}) 没有内置的FFmpeg，请通过配置项 ffmpeg.binary_path 或环境变量 {} 指定外部FFmpeg",
+                    "No embedded FFmpeg for this platform ({}); set ffmpeg.binary_path in the config or the {} environment variable to an external FFmpeg",
+                    current_platform(),
+                    crate::config::FFMPEG_PATH_ENV
+                )));
+                }
+            }
+
+            self.dependencies = Some(DependencyHandle {
+                ffmpeg_path,
+                extraction_dir,
+                reusable,
+                analyzer_path: OnceLock::new(),
+                chromaprint_missing: AtomicBool::new(false),
+                _temp_dir: temp_dir,
+            });
+
+            timer.log_elapsed();
+
+            Ok(())
+        })
+    }
+
+    /// 解压嵌入的二进制文件；`reusable` 为 `true` 时使用固定目录的复用逻辑
+    fn extract_embedded(
+        &self,
+        bytes: &[u8],
+        hash: u64,
+        path: &Path,
+        name: &str,
+        reusable: bool,
+    ) -> Result<()> {
+        if reusable {
+            self.extract_binary_reusable(bytes, hash, path, name)
+        } else {
+            self.extract_binary_optimized(bytes, path, name)
         }
+    }
 
-        let timer = Timer::new("依赖项初始化");
+    /// 解压二进制文件到固定目录，已有的完整文件直接复用
+    ///
+    /// 解压完成后在旁边写入 `<文件名>.hash`，记录嵌入内容的校验值（`hash`，编译时计算）
+    /// 以及解压出的文件的长度与修改时间；只有这些都与记录一致时才跳过解压，因此旧版本留下的文件、
+    /// 之后被截断或改写过的文件都会被重新解压，复用时也不必读取整个文件。
+    /// 二进制文件先写入临时文件再重命名，多个进程同时解压时不会读到写了一半的文件。
+    fn extract_binary_reusable(
+        &self,
+        bytes: &[u8],
+        hash: u64,
+        path: &Path,
+        name: &str,
+    ) -> Result<()> {
+        let stamp_path = hash_stamp_path(path);
 
-        // 创建临时目录
-        let temp_dir = tempfile::Builder::new()
-            .prefix("audio_analyzer_")
-            .tempdir()
-            .map_err(|e| AnalyzerError::DependencyError(format!("创建临时目录失败: {e}")))?;
+        let is_current = fs::metadata(path)
+            .ok()
+            .filter(|m| m.len() == bytes.len() as u64)
+            .and_then(|m| extraction_stamp(hash, &m))
+            .is_some_and(|expected| {
+                fs::read_to_string(&stamp_path).is_ok_and(|stamp| stamp.trim() == expected)
+            });
+        if is_current {
+            debug!(
+                "{}",
+                crate::tr!(
+                    "复用已解压的 {}: {}",
+                    "Reusing extracted {}: {}",
+                    name,
+                    path.display()
+                )
+            );
+            return Ok(());
+        }
 
-        if self.config.verbose {
-            println!("正在初始化依赖项...");
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let temp = tempfile::NamedTempFile::new_in(dir)?;
+        self.extract_binary_optimized(bytes, temp.path(), name)?;
+        // 之后的运行会直接复用，重命名前确保内容已落盘
+        temp.as_file().sync_all()?;
+        let file = temp.persist(path).map_err(|e| AnalyzerError::Io(e.error))?;
+        // 文件系统不提供修改时间时不写记录，下次运行重新解压
+        match extraction_stamp(hash, &file.metadata()?) {
+            Some(stamp) => fs_utils::write_atomic(&stamp_path, stamp.as_bytes())?,
+            None => {
+                let _ = fs::remove_file(&stamp_path);
+            }
         }
 
-        // 并行解压二进制文件以提高性能
-        let ffmpeg_path = temp_dir.path().join("ffmpeg");
-        let analyzer_path = temp_dir.path().join("audio_analyzer");
+        Ok(())
+    }
 
-        let (ffmpeg_result, analyzer_result) = rayon::join(
-            || self.extract_binary_optimized(FFMPEG_BYTES, &ffmpeg_path, "FFmpeg"),
-            || self.extract_binary_optimized(ANALYZER_BYTES, &analyzer_path, "Python分析器"),
+    /// 校验外部FFmpeg可执行文件
+    fn validate_external_ffmpeg(&self, path: &Path) -> Result<()> {
+        query_ffmpeg_version(path).map_err(|e| {
+            AnalyzerError::DependencyError(crate::tr!(
+                "外部FFmpeg不可用: {}（{}）",
+                "External FFmpeg is not usable: {} ({})",
+                path.display(),
+                e
+            ))
+        })?;
+
+        debug!(
+            "{}",
+            crate::tr!(
+                "使用外部 FFmpeg: {}",
+                "Using external FFmpeg: {}",
+                path.display()
+            )
         );
 
-        // 检查结果
-        ffmpeg_result?;
-        analyzer_result?;
+        Ok(())
+    }
 
-        self.dependencies = Some(DependencyHandle {
-            ffmpeg_path,
-            analyzer_path,
-            _temp_dir: temp_dir,
-        });
+    /// 所用FFmpeg的版本信息（`ffmpeg -version` 输出的第一行）
+    ///
+    /// 需要先调用 [`AudioAnalyzer::initialize_dependencies`]。
+    /// 同时可以作为简单的健康检查：确认解压出的二进制文件在当前平台上确实可以执行。
+    pub fn ffmpeg_version(&self) -> Result<String> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        if self.config.verbose {
-            timer.print_elapsed();
-        }
+            query_ffmpeg_version(&dependencies.ffmpeg_path)
+        })
+    }
 
-        Ok(())
+    /// 汇总运行环境信息：库版本、FFmpeg 版本、CPU 与线程数、Python分析模块是否可用
+    ///
+    /// 依赖项未初始化时 FFmpeg 与Python分析模块的信息为空，原因记录在 `ffmpeg_error` 中。
+    pub fn environment_report(&self) -> EnvironmentReport {
+        let (ffmpeg_version, ffmpeg_error) = match self.ffmpeg_version() {
+            Ok(version) => (Some(version), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        EnvironmentReport {
+            crate_version: crate::VERSION.to_string(),
+            platform: current_platform(),
+            ffmpeg_path: self
+                .dependencies
+                .as_ref()
+                .map(|deps| deps.ffmpeg_path.clone()),
+            ffmpeg_version,
+            ffmpeg_error,
+            cpu_count: num_cpus::get(),
+            thread_count: self.config.effective_thread_count(),
+            python_analyzer_available: self.get_analyzer_path().is_some(),
+        }
     }
 
     /// 解压二进制文件到指定路径（保留用于兼容性）
@@ -147,9 +543,10 @@ impl AudioAnalyzer {
             fs::set_permissions(path, perms)?;
         }
 
-        if self.config.verbose {
-            println!("已解压 {}: {}", name, path.display());
-        }
+        debug!(
+            "{}",
+            crate::tr!("已解压 {}: {}", "Extracted {}: {}", name, path.display())
+        );
 
         Ok(())
     }
@@ -160,6 +557,9 @@ impl AudioAnalyzer {
     /// - 使用缓冲写入减少系统调用
     /// - 预分配文件大小
     /// - 批量设置权限
+    ///
+    /// 写入后校验文件的长度，不一致时返回 [`AnalyzerError::DependencyError`]，
+    /// 避免之后执行一个不完整的文件。写入失败会直接返回错误，因此不再读回整个文件比较内容。
     fn extract_binary_optimized(&self, bytes: &[u8], path: &Path, name: &str) -> Result<()> {
         use std::io::BufWriter;
 
@@ -171,6 +571,9 @@ impl AudioAnalyzer {
         let mut writer = BufWriter::with_capacity(64 * 1024, file); // 64KB 缓冲区
         std::io::Write::write_all(&mut writer, bytes)?;
         writer.flush()?;
+        drop(writer);
+
+        verify_extracted(path, bytes.len() as u64, name)?;
 
         // 设置可执行权限 (Unix系统)
         #[cfg(unix)]
@@ -180,342 +583,3782 @@ impl AudioAnalyzer {
             fs::set_permissions(path, perms)?;
         }
 
-        if self.config.verbose {
-            println!("已解压 {}: {}", name, path.display());
-        }
+        debug!(
+            "{}",
+            crate::tr!("已解压 {}: {}", "Extracted {}: {}", name, path.display())
+        );
 
         Ok(())
     }
 
     /// 分析单个音频文件
+    ///
+    /// 只需要 `&self`，分析过程不修改分析器状态，因此同一个分析器可以在多个线程间共享；
+    /// 配置了 `max_concurrent_ffmpeg` 时，所有调用方共同受该并发数限制。
+    ///
+    /// 扩展名不在 `supported_extensions` 中时直接返回
+    /// [`AnalyzerError::UnsupportedFormat`]，不会调用FFmpeg。只有封面图片、没有音频流，
+    /// 或时长短于 `min_audio_duration_secs` 的文件返回 [`AnalyzerError::NotAudio`]。
+    ///
+    /// 启用 `native-wav` 特性时，WAV 文件的电平统计由 [`crate::native`] 直接计算，
+    /// 响度、高频等其余指标仍由FFmpeg得到；原生解码失败时整体回退到FFmpeg。
+    /// 启用 `fingerprint` 时另外运行一次FFmpeg计算声学指纹，因超时只得到部分指标的文件不计算指纹。
+    ///
+    /// 结果中的 `quality_verdict` 按配置的质量阈值计算。
+    ///
+    /// 不应用 `profile_rules`；批量分析时按规则选择预设见 [`AudioAnalyzer::profile_matcher`]。
     pub fn analyze_file(&self, file_path: &Path) -> Result<AudioMetrics> {
-        let dependencies = self
-            .dependencies
-            .as_ref()
-            .ok_or_else(|| AnalyzerError::DependencyError("依赖项未初始化".to_string()))?;
+        self.analyze_file_with(file_path, &ConfigOverrides::default())
+    }
 
-        let timer = Timer::new("文件分析");
-        let file_size = fs_utils::get_file_size(file_path)?;
+    /// 按覆盖的配置项分析单个音频文件
+    ///
+    /// `overrides` 中的质量阈值与高通截止频率只用于这一次分析，不修改分析器的共享配置，
+    /// 因此可以在多个线程中对不同文件使用不同的设置。覆盖项无效时返回
+    /// [`AnalyzerError::ConfigError`]，不会调用FFmpeg。其余行为与 [`AudioAnalyzer::analyze_file`] 相同。
+    pub fn analyze_file_with(
+        &self,
+        file_path: &Path,
+        overrides: &ConfigOverrides,
+    ) -> Result<AudioMetrics> {
+        messages::with_locale(self.config.locale, || {
+            overrides.validate()?;
+            let mut metrics = self.analyze_levels(file_path, overrides)?;
+            self.check_really_audio(&metrics)?;
+            if self.config.fingerprint && !metrics.partial {
+                let timer = Timer::new("声学指纹");
+                metrics.fingerprint = self.fingerprint_if_available(file_path)?;
+                metrics.processing_time_ms += timer.elapsed().as_millis() as u64;
+            }
+            if self.config.spectral_flatness && !metrics.partial {
+                let timer = Timer::new("频谱平坦度");
+                if let Some(flatness) = self.extract_spectral_flatness(file_path)? {
+                    metrics.spectral_flatness = Some(flatness.flatness);
+                    metrics.noise_floor_db = flatness.noise_floor_db;
+                }
+                metrics.processing_time_ms += timer.elapsed().as_millis() as u64;
+            }
+            metrics.quality_verdict = Some(metrics.verdict(overrides.thresholds(&self.config)));
+            Ok(metrics)
+        })
+    }
 
-        // 并行执行多个分析任务
-        let (lra_result, (stats_result, (rms_16k_result, (rms_18k_result, rms_20k_result)))) =
-            rayon::join(
-                || self.extract_lra_ebur128(file_path, &dependencies.ffmpeg_path),
-                || {
-                    rayon::join(
-                        || self.extract_audio_stats(file_path, &dependencies.ffmpeg_path),
-                        || {
-                            rayon::join(
-                                || {
-                                    self.extract_highpass_rms(
-                                        file_path,
-                                        16000,
-                                        &dependencies.ffmpeg_path,
-                                    )
-                                },
-                                || {
-                                    rayon::join(
-                                        || {
-                                            self.extract_highpass_rms(
-                                                file_path,
-                                                18000,
-                                                &dependencies.ffmpeg_path,
-                                            )
-                                        },
-                                        || {
-                                            self.extract_highpass_rms(
-                                                file_path,
-                                                20000,
-                                                &dependencies.ffmpeg_path,
-                                            )
-                                        },
-                                    )
-                                },
-                            )
-                        },
-                    )
-                },
-            );
+    /// 分析单个文件的响度、电平与频谱指标（不含声学指纹）
+    fn analyze_levels(
+        &self,
+        file_path: &Path,
+        overrides: &ConfigOverrides,
+    ) -> Result<AudioMetrics> {
+        if !fs_utils::is_supported_audio_file(file_path, &self.config.supported_extensions) {
+            return Err(AnalyzerError::UnsupportedFormat {
+                path: file_path.display().to_string(),
+                extension: file_path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_string()),
+            });
+        }
 
-        let processing_time_ms = timer.elapsed().as_millis() as u64;
+        let window = self.config.ffmpeg.analysis_window();
 
-        let mut metrics = AudioMetrics::new(file_path.to_string_lossy().to_string(), file_size);
+        let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        // 设置分析结果
-        metrics.lra = lra_result.ok();
-        if let Ok(stats) = stats_result {
-            metrics.peak_amplitude_db = stats.peak_db;
-            metrics.overall_rms_db = stats.rms_db;
+        let file_size = self.checked_file_size(file_path)?;
+        #[cfg(feature = "native-wav")]
+        let native_stats = self.native_level_stats(file_path);
+        #[cfg(not(feature = "native-wav"))]
+        let native_stats = None;
+
+        // 作为输入选项传给FFmpeg：-ss 直接定位，-t 限制读取的长度，不解码片段以外的部分
+        let mut window_args = Vec::new();
+        if let Some(start) = self.config.ffmpeg.analyze_start_secs {
+            window_args.extend(["-ss".to_string(), start.to_string()]);
         }
-        metrics.rms_db_above_16k = rms_16k_result.ok();
-        metrics.rms_db_above_18k = rms_18k_result.ok();
-        metrics.rms_db_above_20k = rms_20k_result.ok();
-        metrics.processing_time_ms = processing_time_ms;
+        if let Some(duration) = self.config.ffmpeg.analyze_duration_secs {
+            window_args.extend(["-t".to_string(), duration.to_string()]);
+        }
+        let input: Vec<&OsStr> = window_args
+            .iter()
+            .map(OsStr::new)
+            .chain([OsStr::new("-i"), file_path.as_os_str()])
+            .collect();
 
-        Ok(metrics)
+        let mut metrics = AudioMetrics::from_path(file_path, file_size);
+        metrics.analysis_window = window;
+        self.run_analysis(
+            &input,
+            &dependencies.ffmpeg_path,
+            self.stream_index(),
+            metrics,
+            overrides,
+            native_stats,
+        )
     }
 
-    /// 批量分析音频文件
-    pub fn analyze_files(&self, file_paths: &[PathBuf]) -> Result<Vec<AudioMetrics>> {
-        if file_paths.is_empty() {
-            return Ok(Vec::new());
+    /// 用原生解码计算 WAV 文件的电平统计，不适用或解码失败时为 `None`（改用FFmpeg的 `astats`）
+    ///
+    /// 原生解码总是读取整个文件的唯一音频流，因此只分析片段或指定了其他音频流时不使用；
+    /// 需要 astats 的原始输出（`capture_raw_astats`、`keep_raw_output`）时同样不使用。
+    #[cfg(feature = "native-wav")]
+    fn native_level_stats(&self, file_path: &Path) -> Option<AudioStats> {
+        let applicable = self.config.ffmpeg.analysis_window().is_none()
+            && self.stream_index() == 0
+            && !self.config.capture_raw_astats
+            && !self.config.keep_raw_output
+            && crate::native::is_native_wav(file_path);
+        if !applicable {
+            return None;
         }
 
-        let total_files = file_paths.len();
-        let processed_count = Arc::new(AtomicUsize::new(0));
+        match crate::native::wav_level_stats(file_path) {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                debug!(
+                    "{}",
+                    crate::tr!(
+                        "原生 WAV 解码失败，改用FFmpeg: {e}",
+                        "Native WAV decoding failed, falling back to FFmpeg: {e}"
+                    )
+                );
+                None
+            }
+        }
+    }
 
-        if self.config.verbose {
-            println!("开始并行分析 {total_files} 个文件...");
+    /// 时长短于 `min_audio_duration_secs` 的结果返回 [`AnalyzerError::NotAudio`]
+    fn check_really_audio(&self, metrics: &AudioMetrics) -> Result<()> {
+        let reason = self
+            .config
+            .min_audio_duration_secs
+            .and_then(|min_duration| metrics.not_audio_reason(min_duration));
+        match reason {
+            Some(reason) => Err(AnalyzerError::NotAudio {
+                path: metrics.file_path.clone(),
+                reason,
+            }),
+            None => Ok(()),
         }
+    }
 
-        let timer = Timer::new("批量分析");
+    /// 获取文件大小，空文件或小于 `min_file_size_bytes` 的文件返回 [`AnalyzerError::FileTooSmall`]
+    fn checked_file_size(&self, file_path: &Path) -> Result<u64> {
+        let file_size = fs_utils::get_file_size(file_path)?;
+        let min_size = self.config.min_file_size_bytes.unwrap_or(0).max(1);
+        if file_size < min_size {
+            return Err(AnalyzerError::FileTooSmall {
+                path: file_path.display().to_string(),
+                size_bytes: file_size,
+            });
+        }
+        Ok(file_size)
+    }
 
-        let results: Vec<AudioMetrics> = file_paths
-            .par_iter()
-            .filter_map(|path| {
-                let count = processed_count.fetch_add(1, Ordering::SeqCst) + 1;
-
-                if self.config.show_progress {
-                    println!(
-                        "[{}/{}] 正在处理: {}",
-                        count,
-                        total_files,
-                        fs_utils::get_display_name(path)
-                    );
-                }
+    /// 分析两个文件并对比各项指标（差值为 B - A）
+    ///
+    /// 两个文件都通过 [`AudioAnalyzer::analyze_file`] 分析，任一文件失败时返回该错误。
+    pub fn compare(&self, a: &Path, b: &Path) -> Result<MetricsDiff> {
+        messages::with_locale(self.config.locale, || {
+            let metrics_a = self.analyze_file(a)?;
+            let metrics_b = self.analyze_file(b)?;
+            Ok(MetricsDiff::new(
+                metrics_a,
+                metrics_b,
+                &self.config.quality_thresholds,
+            ))
+        })
+    }
 
-                match self.analyze_file(path) {
-                    Ok(metrics) => Some(metrics),
-                    Err(e) => {
-                        eprintln!("处理失败: {}\n └─> 错误详情: {}", path.display(), e);
-                        None
-                    }
-                }
-            })
-            .collect();
+    /// 对FFmpeg输入执行完整分析，将结果填入 `metrics`
+    ///
+    /// `input` 为放在 `-filter_complex` 之前的输入参数（如 `-i <文件>`），
+    /// `stream_index` 为要分析的音频流序号（从 0 开始），`overrides` 为本次分析覆盖的配置项。
+    /// 给出 `level_stats`（原生解码的电平统计）时用它代替整体 `astats` 的结果。
+    fn run_analysis(
+        &self,
+        input: &[&OsStr],
+        ffmpeg_path: &Path,
+        stream_index: usize,
+        mut metrics: AudioMetrics,
+        overrides: &ConfigOverrides,
+        level_stats: Option<AudioStats>,
+    ) -> Result<AudioMetrics> {
+        let timer = Timer::new("文件分析");
+        let highpass_frequencies = overrides.highpass_frequencies(&self.config);
 
-        if self.config.verbose {
-            timer.print_elapsed();
-            println!("成功处理 {}/{} 个文件", results.len(), total_files);
-        }
+        // 单次FFmpeg调用完成全部分析，音频只解码一次
+        let command = self.build_analysis_command(
+            input,
+            ffmpeg_path,
+            stream_index,
+            highpass_frequencies,
+            level_stats.is_none(),
+        );
+        // 超时时仍解析已输出的部分，只丢失尚未输出的指标
+        let (stderr, timeout_error) = match self.run_ffmpeg(command) {
+            Ok(stderr) => (stderr, None),
+            Err(error) if process_utils::is_timeout_error(&error) => {
+                let AnalyzerError::FfmpegError {
+                    stderr: Some(stderr),
+                    ..
+                } = &error
+                else {
+                    return Err(error);
+                };
+                (stderr.clone(), Some(error))
+            }
+            // 只有封面图片的文件没有可分析的音频流，FFmpeg因此失败
+            Err(AnalyzerError::FfmpegError {
+                stderr: Some(stderr),
+                ..
+            }) if is_attached_picture_only(&stderr) => {
+                return Err(AnalyzerError::NotAudio {
+                    path: metrics.file_path,
+                    reason: NotAudioReason::AttachedPictureOnly,
+                });
+            }
+            Err(error) => return Err(error),
+        };
 
-        Ok(results)
-    }
+        let format_info = parse_format_info(&stderr, stream_index);
+        let ebur128_result = parse_ebur128_output(&stderr);
+        let stats_result = match level_stats {
+            Some(stats) => Ok(stats),
+            None => parse_audio_stats(&filter_log_section(&stderr, OVERALL_ASTATS_INSTANCE)),
+        };
 
-    /// 分析目录中的所有音频文件
-    pub fn analyze_directory<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<AudioMetrics>> {
-        let audio_files = fs_utils::scan_audio_files(dir_path, &self.config.supported_extensions)?;
+        let processing_time_ms = timer.elapsed().as_millis() as u64;
 
-        if audio_files.is_empty() {
-            return Err(AnalyzerError::Other(
-                "在指定目录中未找到支持的音频文件".to_string(),
-            ));
+        // 设置分析结果
+        if let Ok(info) = format_info {
+            metrics.set_format_info(info);
+        }
+        if let Ok(summary) = ebur128_result {
+            metrics.lra = summary.lra;
+            metrics.integrated_lufs = summary.integrated_lufs;
+            metrics.true_peak_dbtp = summary.true_peak_dbtp;
+        }
+        if self.config.capture_raw_astats {
+            metrics.raw_astats = Some(parse_all_astats(&filter_log_section(
+                &stderr,
+                OVERALL_ASTATS_INSTANCE,
+            )));
+        }
+        if let Ok(stats) = stats_result {
+            metrics.clipped_samples =
+                stats.estimate_clipped_samples(overrides.thresholds(&self.config).peak_clipping_db);
+            metrics.clipping_ratio = metrics
+                .clipped_samples
+                .zip(stats.sample_count.filter(|&count| count > 0))
+                .map(|(clipped, total)| clipped as f64 / total as f64);
+            metrics.peak_amplitude_db = stats.peak_db;
+            metrics.overall_rms_db = stats.rms_db;
+            metrics.rms_peak_db = stats.rms_peak_db;
+            metrics.rms_trough_db = stats.rms_trough_db;
+            metrics.rms_range_db = stats.rms_range_db();
+            metrics.dc_offset = stats.dc_offset;
+            metrics.crest_factor_db = stats.crest_factor_db;
+            metrics.channel_stats = stats.per_channel;
+        }
+        for &frequency in highpass_frequencies {
+            let section = filter_log_section(&stderr, &highpass_astats_instance(frequency));
+            if let Ok(rms) = parse_highpass_rms(&section) {
+                metrics.highpass_rms.insert(frequency, rms);
+            }
+        }
+        if metrics.channels == Some(2) {
+            metrics.phase_correlation =
+                parse_phase_correlation(&filter_log_section(&stderr, PHASE_ASTATS_INSTANCE));
+        }
+        metrics.duration_secs = parse_duration(&stderr);
+        // 只分析片段时，静音比例相对于片段长度计算
+        let analyzed_duration = metrics.duration_secs.map(|duration| {
+            metrics
+                .analysis_window
+                .map_or(duration, |window| window.analyzed_length(duration))
+        });
+        metrics.silent_fraction = analyzed_duration.and_then(|duration| {
+            parse_silent_fraction(
+                &filter_log_section(&stderr, SILENCE_DETECT_INSTANCE),
+                duration,
+            )
+        });
+        metrics.decode_warnings = parse_decode_warnings(&stderr);
+        if self.config.keep_raw_output {
+            metrics.raw_output = Some(self.raw_output_sections(&stderr, highpass_frequencies));
         }
+        metrics.processing_time_ms = processing_time_ms;
 
-        if self.config.verbose {
-            println!("找到 {} 个音频文件", audio_files.len());
+        if let Some(error) = timeout_error {
+            // 超时前没有得到任何指标时仍按超时失败处理
+            if !metrics.has_any_measurement() {
+                return Err(error);
+            }
+            metrics.partial = true;
         }
 
-        self.analyze_files(&audio_files)
+        Ok(metrics)
     }
 
-    /// 使用 EBU R128 标准提取 LRA (Loudness Range)
-    ///
-    /// LRA (Loudness Range) 是衡量音频动态范围的重要指标，单位为LU (Loudness Units)。
-    /// 该方法通过FFmpeg的ebur128滤镜来计算音频的响度范围。
-    ///
-    /// # 参数
-    /// * `file_path` - 音频文件路径
-    /// * `ffmpeg_path` - FFmpeg可执行文件路径
-    ///
-    /// # 返回值
-    /// * `Ok(f64)` - 成功时返回LRA值（单位：LU）
-    /// * `Err(AnalyzerError)` - 失败时返回错误信息
-    ///
-    /// # EBU R128标准说明
-    /// EBU R128是欧洲广播联盟制定的音频响度标准，用于确保不同音频内容的响度一致性。
-    /// LRA值通常在以下范围内：
-    /// - 0-3 LU: 严重压缩，动态范围极低
-    /// - 3-6 LU: 低动态范围，可能过度压缩
-    /// - 8-12 LU: 理想的动态范围
-    /// - >20 LU: 动态范围过高，可能需要压缩处理
-    fn extract_lra_ebur128(&self, file_path: &Path, ffmpeg_path: &Path) -> Result<f64> {
-        let mut command = Command::new(ffmpeg_path);
-        command
-            .arg("-i")
-            .arg(file_path)
-            .arg("-filter_complex")
-            .arg("ebur128")
-            .arg("-f")
-            .arg("null")
-            .arg("-");
-
-        if self.config.ffmpeg.hide_banner {
-            command.arg("-hide_banner");
-        }
-        command.arg("-loglevel").arg(&self.config.ffmpeg.log_level);
+    /// 按指标拆分FFmpeg原始输出，键的含义见 [`AudioMetrics::raw_output`]
+    fn raw_output_sections(
+        &self,
+        stderr: &str,
+        highpass_frequencies: &[u32],
+    ) -> BTreeMap<String, String> {
+        let mut sections = vec![
+            (
+                "format".to_string(),
+                input_section(stderr).trim().to_string(),
+            ),
+            ("ebur128".to_string(), ebur128_log(stderr)),
+            (
+                "astats".to_string(),
+                filter_log_section(stderr, OVERALL_ASTATS_INSTANCE),
+            ),
+            (
+                "phaseCorrelation".to_string(),
+                filter_log_section(stderr, PHASE_ASTATS_INSTANCE),
+            ),
+            (
+                "silentFraction".to_string(),
+                filter_log_section(stderr, SILENCE_DETECT_INSTANCE),
+            ),
+        ];
+        sections.extend(highpass_frequencies.iter().map(|&frequency| {
+            (
+                highpass_field_name(frequency),
+                filter_log_section(stderr, &highpass_astats_instance(frequency)),
+            )
+        }));
 
-        let stderr = process_utils::run_command_capture_stderr(command)?;
+        sections
+            .into_iter()
+            .map(|(key, text)| (key, self.cap_raw_output(&text)))
+            .collect()
+    }
 
-        // 首先尝试匹配汇总的LRA值
-        if let Some(caps) = EBUR128_SUMMARY_LRA_REGEX.captures(&stderr) {
-            if let Some(lra_str) = caps.get(1) {
-                if let Ok(lra_value) = lra_str.as_str().parse::<f64>() {
-                    return Ok(lra_value);
-                }
+    /// 按 `raw_output_max_chars` 截断原始输出
+    fn cap_raw_output(&self, text: &str) -> String {
+        match self.config.raw_output_max_chars {
+            Some(max_chars) if text.chars().count() > max_chars => {
+                let mut capped: String = text.chars().take(max_chars).collect();
+                capped.push_str("…（已截断）");
+                capped
             }
+            _ => text.to_string(),
         }
+    }
 
-        // 如果没有找到汇总值，尝试提取所有LRA值并取最后一个
-        let lra_values: Vec<f64> = EBUR128_LRA_REGEX
-            .captures_iter(&stderr)
-            .filter_map(|caps| caps.get(1))
-            .filter_map(|m| m.as_str().parse::<f64>().ok())
-            .collect();
-
-        if let Some(&last_lra) = lra_values.last() {
-            Ok(last_lra)
-        } else {
-            Err(AnalyzerError::ParseError {
-                message: "无法从EBU R128输出中解析LRA值".to_string(),
-                raw_data: Some(stderr.chars().take(500).collect()),
-            })
+    /// 按 `raw_output_max_chars` 截断解析错误附带的原始输出
+    fn cap_parse_error(&self, error: AnalyzerError) -> AnalyzerError {
+        match error {
+            AnalyzerError::ParseError {
+                message,
+                raw_data: Some(raw),
+                source,
+            } => AnalyzerError::ParseError {
+                message,
+                raw_data: Some(self.cap_raw_output(&raw)),
+                source,
+            },
+            other => other,
         }
     }
 
-    /// 提取音频统计信息（峰值和RMS）
-    ///
-    /// 使用FFmpeg的astats滤镜提取音频的基本统计信息，包括峰值电平和RMS电平。
-    /// 这些指标用于评估音频的整体响度和是否存在削波等问题。
+    /// 自检：用FFmpeg的 `lavfi` 正弦波信号源合成测试音，走完整的分析流程，
+    /// 并确认各项指标落在预期范围内
     ///
-    /// # 参数
-    /// * `file_path` - 音频文件路径
-    /// * `ffmpeg_path` - FFmpeg可执行文件路径
-    ///
-    /// # 返回值
-    /// * `Ok(AudioStats)` - 成功时返回音频统计信息
-    /// * `Err(AnalyzerError)` - 失败时返回错误信息
-    ///
-    /// # 统计指标说明
-    /// - **峰值电平 (Peak Level)**: 音频信号的最大振幅，单位为dB
-    ///   - 接近0dB表示可能存在削波风险
-    ///   - 低于-6dB通常被认为是安全的
-    /// - **RMS电平 (RMS Level)**: 音频信号的有效值，反映平均响度
-    ///   - 比峰值电平更能反映人耳感知的响度
-    fn extract_audio_stats(&self, file_path: &Path, ffmpeg_path: &Path) -> Result<AudioStats> {
-        let mut command = Command::new(ffmpeg_path);
-        command
-            .arg("-i")
-            .arg(file_path)
-            .arg("-filter:a")
-            .arg("astats=metadata=1")
-            .arg("-map")
-            .arg("0:a")
-            .arg("-f")
-            .arg("null")
-            .arg("-");
+    /// 用于在新机器上确认FFmpeg可以执行、输出可以解析、超时与退出码处理正常，
+    /// 无需准备真实的音频文件。需要先调用 [`AudioAnalyzer::initialize_dependencies`]。
+    pub fn self_test(&self) -> Result<()> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        if self.config.ffmpeg.hide_banner {
-            command.arg("-hide_banner");
-        }
-        command.arg("-loglevel").arg(&self.config.ffmpeg.log_level);
+            let source = self_test_source();
+            let input = [
+                OsStr::new("-f"),
+                OsStr::new("lavfi"),
+                OsStr::new("-i"),
+                OsStr::new(&source),
+            ];
+            let metrics = self.run_analysis(
+                &input,
+                &dependencies.ffmpeg_path,
+                // 测试音只有一个音频流，不受 `stream_index` 设置影响
+                0,
+                AudioMetrics::new(format!("lavfi:{source}"), 0),
+                &ConfigOverrides::default(),
+                None,
+            )?;
 
-        let stderr = process_utils::run_command_capture_stderr(command)?;
+            check_self_test_metrics(&metrics)
+        })
+    }
 
-        // 尝试使用复杂正则表达式匹配
-        if let Some(caps) = ASTATS_OVERALL_REGEX.captures(&stderr) {
-            let peak_db = caps.get(1).and_then(|m| m.as_str().parse::<f64>().ok());
-            let rms_db = caps.get(2).and_then(|m| m.as_str().parse::<f64>().ok());
-            return Ok(AudioStats { peak_db, rms_db });
-        }
+    /// 将自检使用的正弦测试音写入 `path`，格式由扩展名决定（如 `.flac`、`.wav`）
+    ///
+    /// 用于基准测试与演示，无需准备真实的音频文件；已存在的文件会被覆盖。
+    /// 需要先调用 [`AudioAnalyzer::initialize_dependencies`]。
+    pub fn write_test_tone(&self, path: &Path) -> Result<()> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        // 回退到简单正则表达式
-        let peak_db = SIMPLE_PEAK_REGEX
-            .captures(&stderr)
-            .and_then(|caps| caps.get(1))
-            .and_then(|m| m.as_str().parse::<f64>().ok());
+            let mut command = ffmpeg_command(&dependencies.ffmpeg_path);
+            command
+                .arg("-hide_banner")
+                .arg("-loglevel")
+                .arg("error")
+                .arg("-f")
+                .arg("lavfi")
+                .arg("-i")
+                .arg(self_test_source())
+                .arg("-y")
+                .arg(path);
+            self.run_ffmpeg(command).map(drop)
+        })
+    }
 
-        let rms_db = SIMPLE_RMS_REGEX
-            .captures(&stderr)
-            .and_then(|caps| caps.get(1))
-            .and_then(|m| m.as_str().parse::<f64>().ok());
+    /// 单独提取音频文件的格式信息（编码、采样率、声道数、位深度）
+    ///
+    /// `analyze_file` 已从同一次FFmpeg调用中读取格式信息，
+    /// 此方法只用于不需要完整分析的场景。
+    pub fn extract_format_info(&self, file_path: &Path) -> Result<FormatInfo> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        if peak_db.is_some() || rms_db.is_some() {
-            Ok(AudioStats { peak_db, rms_db })
-        } else {
-            Err(AnalyzerError::ParseError {
-                message: "无法从astats输出中解析峰值/RMS".to_string(),
-                raw_data: Some(stderr.trim().to_string()),
-            })
-        }
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "info");
+            command.arg("-t").arg("0").arg("-f").arg("null").arg("-");
+
+            let stderr = self.run_ffmpeg(command)?;
+            parse_format_info(&stderr, self.stream_index()).map_err(|e| self.cap_parse_error(e))
+        })
     }
 
-    /// 提取高通滤波后的RMS值
-    fn extract_highpass_rms(
+    /// 提取短期响度随时间的变化，按 `window_secs` 秒的窗口降采样
+    ///
+    /// 解析 `ebur128` 逐帧输出的短期响度 (S)，同一窗口内的帧在能量域取平均，
+    /// 返回 `(窗口起始秒数, 短期响度 LUFS)` 列表。无信号的帧不参与平均，
+    /// 整个窗口都无信号时跳过该窗口。`window_secs` 必须为正数。
+    pub fn extract_loudness_timeseries(
         &self,
         file_path: &Path,
-        frequency: u32,
-        ffmpeg_path: &Path,
-    ) -> Result<f64> {
-        let mut command = Command::new(ffmpeg_path);
-        let filter_str = format!("highpass=f={frequency},astats=metadata=1");
-
-        command
-            .arg("-i")
-            .arg(file_path)
-            .arg("-filter:a")
-            .arg(&filter_str)
-            .arg("-map")
-            .arg("0:a")
-            .arg("-f")
-            .arg("null")
-            .arg("-");
+        window_secs: f64,
+    ) -> Result<Vec<(f64, f64)>> {
+        messages::with_locale(self.config.locale, || {
+            if !(window_secs.is_finite() && window_secs > 0.0) {
+                return Err(AnalyzerError::ConfigError(crate::tr!(
+                    "响度时间序列的窗口长度必须为正数: {window_secs}",
+                    "The loudness time series window must be positive: {window_secs}"
+                )));
+            }
 
-        if self.config.ffmpeg.hide_banner {
-            command.arg("-hide_banner");
-        }
-        command.arg("-loglevel").arg(&self.config.ffmpeg.log_level);
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        let stderr = process_utils::run_command_capture_stderr(command)?;
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "info");
+            command
+                .arg("-af")
+                .arg(ebur128_filter(
+                    None,
+                    self.config.ffmpeg.ebur128_args.as_deref(),
+                ))
+                .arg("-f")
+                .arg("null")
+                .arg("-");
 
-        // 尝试使用高通滤波专用正则表达式
-        if let Some(caps) = HIGHPASS_ASTATS_REGEX.captures(&stderr) {
-            if let Some(rms_str) = caps.get(1) {
-                if let Ok(rms_value) = rms_str.as_str().parse::<f64>() {
-                    return Ok(rms_value);
-                }
-            }
-        }
+            let stderr = self.run_ffmpeg(command)?;
+            Ok(parse_loudness_timeseries(&stderr, window_secs))
+        })
+    }
 
-        // 回退到简单RMS正则表达式
-        let rms_values: Vec<f64> = SIMPLE_RMS_REGEX
-            .captures_iter(&stderr)
-            .filter_map(|caps| caps.get(1))
-            .filter_map(|m| m.as_str().parse::<f64>().ok())
-            .collect();
+    /// 单独提取整体 astats Overall 块中的全部数值
+    ///
+    /// 键为FFmpeg输出的原始名称（如 `RMS trough dB`、`Noise floor dB`、`Flat factor`），
+    /// 便于使用本库尚未单独建模的指标。找不到 Overall 块时返回解析错误。
+    pub fn extract_all_astats(&self, file_path: &Path) -> Result<BTreeMap<String, f64>> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
 
-        if let Some(&last_rms) = rms_values.last() {
-            Ok(last_rms)
-        } else {
-            // 如果没有找到任何RMS值，返回一个默认的低值
-            Ok(-144.0)
-        }
-    }
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "info");
+            command
+                .arg("-af")
+                .arg(format!("{OVERALL_ASTATS_INSTANCE}=metadata=1"))
+                .arg("-f")
+                .arg("null")
+                .arg("-");
+
+            let stderr = self.run_ffmpeg(command)?;
+            let section = filter_log_section(&stderr, OVERALL_ASTATS_INSTANCE);
+            if !ASTATS_OVERALL_HEADER_REGEX.is_match(&section) {
+                return Err(self.cap_parse_error(AnalyzerError::ParseError {
+                    message: messages::text(
+                        "未找到 astats Overall 统计块",
+                        "No astats Overall statistics block found",
+                    )
+                    .to_string(),
+                    raw_data: Some(stderr.trim().to_string()),
+                    source: None,
+                }));
+            }
+            Ok(parse_all_astats(&section))
+        })
+    }
+
+    /// 单独提取立体声文件的相位相关系数 (-1.0-1.0)
+    ///
+    /// `analyze_file` 已在同一次FFmpeg调用中完成相位分析，
+    /// 此方法只用于不需要完整分析的场景。非立体声文件返回 `Ok(None)`。
+    pub fn extract_phase_correlation(&self, file_path: &Path) -> Result<Option<f64>> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
+
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "info");
+            command
+                .arg("-af")
+                .arg(phase_filter())
+                .arg("-f")
+                .arg("null")
+                .arg("-");
+
+            let stderr = self.run_ffmpeg(command)?;
+            let format_info = parse_format_info(&stderr, self.stream_index())
+                .map_err(|e| self.cap_parse_error(e))?;
+            if format_info.channels != Some(2) {
+                return Ok(None);
+            }
+            parse_phase_correlation(&filter_log_section(&stderr, PHASE_ASTATS_INSTANCE))
+                .map(Some)
+                .ok_or_else(|| {
+                    self.cap_parse_error(AnalyzerError::ParseError {
+                        message: messages::text(
+                            "无法从astats输出中计算相位相关系数",
+                            "Could not compute the phase correlation from the astats output",
+                        )
+                        .to_string(),
+                        raw_data: Some(stderr.trim().to_string()),
+                        source: None,
+                    })
+                })
+        })
+    }
+
+    /// 计算音频文件的声学指纹，返回十六进制字符串（见 [`crate::fingerprint`]）
+    ///
+    /// 使用FFmpeg的 `chromaprint` 复用器，只读取开头 120 秒，不受分析片段设置影响，
+    /// 以便同一曲目的不同版本得到可比较的指纹。FFmpeg未启用 chromaprint 时返回FFmpeg错误
+    /// （批量分析时改为记录警告并省略指纹），没有得到任何指纹数据时返回解析错误。
+    pub fn extract_fingerprint(&self, file_path: &Path) -> Result<String> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
+
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "error");
+            command
+                .arg("-t")
+                .arg(FINGERPRINT_DURATION_SECS.to_string())
+                .arg("-vn")
+                .arg("-f")
+                .arg("chromaprint")
+                .arg("-fp_format")
+                .arg("raw")
+                .arg("-");
+
+            let (stdout, stderr) = self.run_ffmpeg_with(
+                command,
+                process_utils::run_command_capture_raw_output_timeout,
+            )?;
+            let fingerprint =
+                fingerprint::parse_raw(&stdout).map_err(|e| self.cap_parse_error(e))?;
+            if fingerprint.is_empty() {
+                return Err(self.cap_parse_error(AnalyzerError::ParseError {
+                    message: messages::text(
+                        "chromaprint 没有输出任何指纹数据",
+                        "chromaprint produced no fingerprint data",
+                    )
+                    .to_string(),
+                    raw_data: Some(stderr.trim().to_string()),
+                    source: None,
+                }));
+            }
+            Ok(fingerprint::encode(&fingerprint))
+        })
+    }
+
+    /// 计算声学指纹；FFmpeg 没有 `chromaprint` 复用器时返回 `Ok(None)`
+    ///
+    /// 第一次发现缺少复用器时记录一条警告，之后的文件不再调用FFmpeg。
+    fn fingerprint_if_available(&self, file_path: &Path) -> Result<Option<String>> {
+        let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
+        if dependencies.chromaprint_missing.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        match self.extract_fingerprint(file_path) {
+            Ok(fingerprint) => Ok(Some(fingerprint)),
+            Err(e) if is_missing_muxer(&e, "chromaprint") => {
+                if !dependencies
+                    .chromaprint_missing
+                    .swap(true, Ordering::Relaxed)
+                {
+                    warn!(
+                        "{}",
+                        messages::text(
+                            "警告: FFmpeg 未启用 chromaprint，跳过声学指纹",
+                            "Warning: FFmpeg was built without chromaprint; skipping acoustic fingerprints"
+                        )
+                    );
+                }
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 计算频谱平坦度与本底噪声
+    ///
+    /// `aspectralstats` 逐窗口计算各声道的频谱平坦度（几何平均与算术平均之比），
+    /// 这里取全部窗口与声道的平均值；本底噪声取自同一次运行中的 astats。
+    /// FFmpeg 没有 `aspectralstats` 滤镜时返回 `Ok(None)`，没有得到任何平坦度数值时返回解析错误。
+    pub fn extract_spectral_flatness(&self, file_path: &Path) -> Result<Option<SpectralFlatness>> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
+
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "info");
+            command
+                .arg("-af")
+                .arg(spectral_flatness_filter())
+                .arg("-f")
+                .arg("null")
+                .arg("-");
+
+            let stderr = match self.run_ffmpeg(command) {
+                Ok(stderr) => stderr,
+                Err(e) if is_missing_filter(&e, "aspectralstats") => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            let flatness =
+                parse_spectral_flatness(&filter_log_section(&stderr, FLATNESS_METADATA_INSTANCE))
+                    .ok_or_else(|| {
+                    self.cap_parse_error(AnalyzerError::ParseError {
+                        message: messages::text(
+                            "无法从 aspectralstats 输出中解析频谱平坦度",
+                            "Could not parse the spectral flatness from the aspectralstats output",
+                        )
+                        .to_string(),
+                        raw_data: Some(stderr.trim().to_string()),
+                        source: None,
+                    })
+                })?;
+            let noise_floor_db = capture_f64(
+                &ASTATS_NOISE_FLOOR_REGEX,
+                overall_block(&filter_log_section(&stderr, NOISE_FLOOR_ASTATS_INSTANCE)),
+            )
+            .filter(|db| db.is_finite());
+
+            Ok(Some(SpectralFlatness {
+                flatness,
+                noise_floor_db,
+            }))
+        })
+    }
+
+    /// 单独检测音频文件中静音部分所占的比例 (0.0-1.0)
+    ///
+    /// `analyze_file` 已在同一次FFmpeg调用中完成静音检测，
+    /// 此方法只用于不需要完整分析的场景。无法确定文件时长时返回解析错误。
+    pub fn extract_silence(&self, file_path: &Path) -> Result<f64> {
+        messages::with_locale(self.config.locale, || {
+            let dependencies = self.dependencies.as_ref().ok_or_else(not_initialized)?;
+
+            let mut command = self.file_command(&dependencies.ffmpeg_path, file_path, "info");
+            command
+                .arg("-af")
+                .arg(silence_detect_filter())
+                .arg("-f")
+                .arg("null")
+                .arg("-");
+
+            let stderr = self.run_ffmpeg(command)?;
+            let section = filter_log_section(&stderr, SILENCE_DETECT_INSTANCE);
+            parse_duration(&stderr)
+                .and_then(|duration| parse_silent_fraction(&section, duration))
+                .ok_or_else(|| {
+                    self.cap_parse_error(AnalyzerError::ParseError {
+                        message: messages::text(
+                            "无法确定文件时长，不能计算静音比例",
+                            "Could not determine the file duration to compute the silence ratio",
+                        )
+                        .to_string(),
+                        raw_data: Some(stderr.trim().to_string()),
+                        source: None,
+                    })
+                })
+        })
+    }
+
+    /// 批量分析音频文件
+    ///
+    /// 失败的文件以 `warn` 级别记录日志并被跳过；需要获取失败原因时请使用
+    /// [`AudioAnalyzer::analyze_files_detailed`]。
+    pub fn analyze_files(&self, file_paths: &[PathBuf]) -> Result<Vec<AudioMetrics>> {
+        messages::with_locale(self.config.locale, || {
+            let report = self.analyze_files_detailed(file_paths)?;
+            log_batch_outcome(&report);
+            Ok(report.succeeded)
+        })
+    }
+
+    /// 可取消的批量分析
+    ///
+    /// 每个文件开始分析前检查 `cancel` 标志，一旦置为 `true`，剩余文件将被跳过，
+    /// 已完成的分析结果仍会返回。正在运行的FFmpeg子进程不会被强制终止，
+    /// 而是完成当前文件后再停止（如需限制单个文件的耗时，请配置 `ffmpeg.timeout_seconds`）。
+    pub fn analyze_files_cancellable(
+        &self,
+        file_paths: &[PathBuf],
+        cancel: Arc<AtomicBool>,
+    ) -> Result<Vec<AudioMetrics>> {
+        messages::with_locale(self.config.locale, || {
+            let report = self.run_batch(file_paths, Some(&cancel), None)?;
+            log_batch_outcome(&report);
+            Ok(report.succeeded)
+        })
+    }
+
+    /// 批量分析音频文件，并返回每个失败文件的错误
+    ///
+    /// 在按 `num_threads` 配置创建的独立线程池中并行执行，不占用全局Rayon线程池。
+    /// 成功与失败列表均保持输入顺序，与线程调度和各文件的完成先后无关，
+    /// 因此同一批文件多次运行生成的报告行顺序相同。
+    pub fn analyze_files_detailed(&self, file_paths: &[PathBuf]) -> Result<AnalysisReport> {
+        messages::with_locale(self.config.locale, || {
+            self.run_batch(file_paths, None, None)
+        })
+    }
+
+    /// 批量分析的公共实现，`cancel` 置位后跳过尚未开始的文件
+    ///
+    /// 每个文件分析成功后立即在分析线程中调用 `on_done`（按完成顺序）。
+    pub(crate) fn run_batch(
+        &self,
+        file_paths: &[PathBuf],
+        cancel: Option<&AtomicBool>,
+        on_done: Option<&(dyn Fn(&AudioMetrics) + Sync)>,
+    ) -> Result<AnalysisReport> {
+        if file_paths.is_empty() {
+            return Ok(AnalysisReport::default());
+        }
+
+        let total_files = file_paths.len();
+        let counter = BatchCounter::new(total_files);
+
+        debug!(
+            "{}",
+            crate::tr!(
+                "开始并行分析 {total_files} 个文件...",
+                "Analyzing {total_files} files in parallel..."
+            )
+        );
+
+        let timer = Timer::new("批量分析");
+        let pool = self.build_thread_pool()?;
+        let cache = self.load_cache();
+
+        let results: Vec<Option<Result<AudioMetrics>>> = pool.install(|| {
+            file_paths
+                .par_iter()
+                .map(|path| {
+                    if cancel.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                        return None;
+                    }
+                    Some(self.analyze_batch_item(path, cache.as_ref(), &counter, on_done))
+                })
+                .collect()
+        });
+
+        save_cache(cache.as_ref());
+
+        // 索引并行迭代器的 collect 按输入位置放置结果，与完成顺序无关，
+        // 因此报告中的各列表始终与 `file_paths` 的顺序一致
+        let mut report = AnalysisReport::default();
+        for (path, result) in file_paths.iter().zip(results) {
+            match result {
+                Some(Ok(metrics)) => report.succeeded.push(metrics),
+                Some(Err(AnalyzerError::FileTooSmall { .. })) => {
+                    report.undersized.push(path.clone())
+                }
+                Some(Err(AnalyzerError::NotAudio { reason, .. })) => {
+                    report.not_audio.push((path.clone(), reason))
+                }
+                Some(Err(e)) => report.failed.push((path.clone(), e)),
+                None => report.skipped.push(path.clone()),
+            }
+        }
+        report.elapsed = timer.elapsed();
+
+        timer.log_elapsed();
+        debug!(
+            "{}",
+            crate::tr!(
+                "成功处理 {}/{} 个文件",
+                "Processed {}/{} files successfully",
+                report.succeeded.len(),
+                total_files
+            )
+        );
+
+        Ok(report)
+    }
+
+    /// 在后台并行分析文件，每个文件完成后立即通过通道发送 `(路径, 结果)`
+    ///
+    /// 适合需要逐个显示进度和处理失败的前端：调用立即返回，结果按完成顺序到达，
+    /// 所有文件处理完并写回分析缓存后通道关闭。与 [`AudioAnalyzer::analyze_files_detailed`]
+    /// 一样在独立线程池中执行，并同样通知进度观察者。
+    ///
+    /// 丢弃接收端后不再开始新的文件，已经开始的分析照常结束。
+    pub fn analyze_files_channel(
+        self: Arc<Self>,
+        file_paths: Vec<PathBuf>,
+    ) -> Result<mpsc::Receiver<(PathBuf, Result<AudioMetrics>)>> {
+        let pool = self.build_thread_pool()?;
+        let (sender, receiver) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("audio-analyzer-channel".to_string())
+            .spawn(move || {
+                messages::set_thread_locale(self.config.locale);
+                let cache = self.load_cache();
+                let counter = BatchCounter::new(file_paths.len());
+                let disconnected = AtomicBool::new(false);
+
+                pool.install(|| {
+                    file_paths
+                        .into_par_iter()
+                        .for_each_with(sender.clone(), |sender, path| {
+                            if disconnected.load(Ordering::SeqCst) {
+                                return;
+                            }
+                            let result =
+                                self.analyze_batch_item(&path, cache.as_ref(), &counter, None);
+                            if sender.send((path, result)).is_err() {
+                                disconnected.store(true, Ordering::SeqCst);
+                            }
+                        });
+                });
+
+                save_cache(cache.as_ref());
+                // 保留一个发送端到缓存写回之后，接收端看到通道关闭时缓存已经保存
+                drop(sender);
+            })?;
+
+        Ok(receiver)
+    }
+
+    /// 分析批量中的一个文件，并在开始与结束时通知进度观察者
+    ///
+    /// 分析成功后先调用 `on_done`，再通知观察者。
+    fn analyze_batch_item(
+        &self,
+        path: &Path,
+        cache: Option<&AnalysisCache>,
+        counter: &BatchCounter,
+        on_done: Option<&(dyn Fn(&AudioMetrics) + Sync)>,
+    ) -> Result<AudioMetrics> {
+        let current_file = counter.started.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut progress = AnalysisProgress {
+            current_file,
+            total_files: counter.total_files,
+            current_path: path.display().to_string(),
+            completed_files: counter.completed.load(Ordering::SeqCst),
+        };
+
+        if let Some(observer) = &self.progress_observer {
+            observer.on_file_start(&progress);
+        }
+
+        let result = self.analyze_file_cached(path, cache);
+        if let (Some(on_done), Ok(metrics)) = (on_done, &result) {
+            on_done(metrics);
+        }
+        progress.completed_files = counter.completed.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if let Some(observer) = &self.progress_observer {
+            match &result {
+                Ok(metrics) => observer.on_file_done(&progress, metrics),
+                Err(e) => observer.on_file_error(&progress, path, e),
+            }
+        }
+
+        result
+    }
+
+    /// 分析目录中的音频文件，以迭代器形式逐个返回结果
+    ///
+    /// 目录扫描是惰性的，每次只取出一批文件（线程数的若干倍）并行分析，
+    /// 因此内存占用与目录大小无关，适合超大曲库边分析边写出结果。
+    /// 结果顺序与扫描顺序一致；此接口不调用进度观察者（文件总数未知）。
+    /// 分析缓存在迭代器被丢弃时保存，提前停止遍历也不会丢失已分析文件的缓存。
+    pub fn analyze_directory_streaming<'a, P: AsRef<Path>>(
+        &'a self,
+        dir_path: P,
+    ) -> Result<impl Iterator<Item = Result<AudioMetrics>> + 'a> {
+        let pool = self.build_thread_pool()?;
+        let batch_size = self.config.effective_thread_count() * STREAMING_BATCH_FACTOR;
+        let cache = CacheSaver {
+            cache: self.load_cache(),
+            locale: self.config.locale,
+        };
+        let mut files = fs_utils::audio_files_iter_with_options(
+            dir_path,
+            &self.config.supported_extensions,
+            self.config.scan_options()?,
+        );
+        let mut pending: VecDeque<Result<AudioMetrics>> = VecDeque::new();
+
+        let locale = self.config.locale;
+        Ok(std::iter::from_fn(move || {
+            messages::with_locale(locale, || {
+                if pending.is_empty() {
+                    let batch: Vec<Result<PathBuf>> = files.by_ref().take(batch_size).collect();
+                    if batch.is_empty() {
+                        return None;
+                    }
+
+                    pending = pool.install(|| {
+                        batch
+                            .into_par_iter()
+                            .map(|path| {
+                                path.and_then(|path| self.analyze_file_cached(&path, cache.get()))
+                            })
+                            .collect::<Vec<_>>()
+                            .into()
+                    });
+                }
+                pending.pop_front()
+            })
+        })
+        .fuse())
+    }
+
+    /// 分析单个文件，命中缓存时直接返回缓存结果，否则分析后写入缓存
+    ///
+    /// 文件匹配 `profile_rules` 时按该预设的阈值分析。
+    fn analyze_file_cached(
+        &self,
+        path: &Path,
+        cache: Option<&AnalysisCache>,
+    ) -> Result<AudioMetrics> {
+        let overrides = self.profiles.overrides_for(path);
+        if let Some(mut cached) = cache.and_then(|cache| cache.get(path)) {
+            self.check_really_audio(&cached)?;
+            // 质量阈值不影响缓存的测量值，判定按当前阈值重新计算
+            cached.quality_verdict = Some(cached.verdict(overrides.thresholds(&self.config)));
+            return Ok(cached);
+        }
+
+        let result = self.analyze_file_with(path, &overrides);
+        // 超时得到的部分结果不缓存，下次（例如放宽超时后）重新分析
+        if let (Some(cache), Ok(metrics)) = (cache, &result) {
+            if !metrics.partial {
+                cache.insert(path, metrics);
+            }
+        }
+        result
+    }
+
+    /// 按配置加载分析缓存（未配置 `cache_path` 时为 `None`）
+    ///
+    /// 无法生成设置指纹时不使用缓存，只打印警告。
+    fn load_cache(&self) -> Option<AnalysisCache> {
+        let path = self.config.cache_path.as_ref()?;
+        match self.cache_fingerprint() {
+            Ok(fingerprint) => Some(AnalysisCache::load(path, &fingerprint)),
+            Err(e) => {
+                warn!(
+                    "{}",
+                    crate::tr!(
+                        "警告: 生成分析缓存指纹失败，本次不使用缓存: {e}",
+                        "Warning: failed to fingerprint the analysis settings, cache disabled: {e}"
+                    )
+                );
+                None
+            }
+        }
+    }
+
+    /// 分析设置指纹，影响分析结果的设置变化时缓存整体失效
+    fn cache_fingerprint(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&CacheFingerprint {
+            version: crate::VERSION,
+            highpass_frequencies: &self.config.highpass_frequencies,
+            profile_rules: &self.config.profile_rules,
+            capture_raw_astats: self.config.capture_raw_astats,
+            keep_raw_output: self.config.keep_raw_output,
+            raw_output_max_chars: self.config.raw_output_max_chars,
+            fingerprint: self.config.fingerprint,
+            spectral_flatness: self.config.spectral_flatness,
+            stream_index: self.stream_index(),
+            peak_clipping_db: self.config.quality_thresholds.peak_clipping_db,
+            native_wav: cfg!(feature = "native-wav"),
+            analysis_window: self.config.ffmpeg.analysis_window(),
+            extra_input_args: &self.config.ffmpeg.extra_input_args,
+            extra_output_args: &self.config.ffmpeg.extra_output_args,
+            ebur128_args: self.config.ffmpeg.ebur128_args.as_deref(),
+        })
+    }
+
+    /// 按配置的线程数创建分析用线程池
+    fn build_thread_pool(&self) -> Result<ThreadPool> {
+        let locale = self.config.locale;
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.config.effective_thread_count())
+            .thread_name(|index| format!("audio-analyzer-{index}"))
+            // 工作线程中的进度与错误信息使用分析器的语言
+            .start_handler(move |_| messages::set_thread_locale(locale))
+            .build()?;
+        Ok(pool)
+    }
+
+    /// 分析目录中的所有音频文件
+    ///
+    /// 目录中没有支持的音频文件时返回空列表；其中无法读取的条目警告后跳过，
+    /// 只有目录本身无法读取时才返回错误。
+    pub fn analyze_directory<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<AudioMetrics>> {
+        messages::with_locale(self.config.locale, || {
+            let audio_files = self.scan_directory(dir_path)?;
+            self.analyze_files(&audio_files)
+        })
+    }
+
+    /// 可取消地分析目录中的所有音频文件
+    ///
+    /// 取消行为见 [`AudioAnalyzer::analyze_files_cancellable`]。
+    pub fn analyze_directory_cancellable<P: AsRef<Path>>(
+        &self,
+        dir_path: P,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<Vec<AudioMetrics>> {
+        messages::with_locale(self.config.locale, || {
+            let audio_files = self.scan_directory(dir_path)?;
+            self.analyze_files_cancellable(&audio_files, cancel)
+        })
+    }
+
+    /// 扫描目录中支持的音频文件（可能为空）
+    ///
+    /// 目录中无法读取的条目逐个警告后跳过，不会中止整个分析；只有输入目录本身无法读取时返回错误。
+    pub(crate) fn scan_directory<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<PathBuf>> {
+        messages::with_locale(self.config.locale, || {
+            let dir_path = dir_path.as_ref();
+            let mut scan = self.scan_report(dir_path)?;
+            if let Some(index) = scan
+                .skipped_errors
+                .iter()
+                .position(|(path, _)| path == dir_path)
+            {
+                return Err(scan.skipped_errors.swap_remove(index).1.into());
+            }
+
+            for (path, error) in &scan.skipped_errors {
+                warn!(
+                    "{}",
+                    crate::tr!(
+                        "警告: 跳过无法读取的条目 {}: {error}",
+                        "Warning: skipping unreadable entry {}: {error}",
+                        path.display()
+                    )
+                );
+            }
+            info!(
+                "{}",
+                crate::tr!(
+                    "找到 {} 个音频文件，跳过 {} 个不支持的文件、{} 个无法读取的条目",
+                    "Found {} audio files, skipped {} unsupported files and {} unreadable entries",
+                    scan.matched.len(),
+                    scan.skipped_unsupported,
+                    scan.skipped_errors.len()
+                )
+            );
+
+            Ok(scan.matched)
+        })
+    }
+
+    /// 构建单次分析所用的FFmpeg命令
+    ///
+    /// 通过 `asplit` 将音频拆分为多个分支，分别接入 `ebur128`、整体 `astats`
+    /// 以及各频率的 `highpass,astats`，所有分支输出到 `null` 复用器。
+    /// 每个 astats 实例都带有 `@实例名` 标签，使其日志行带有可区分的前缀。
+    /// `overall_astats` 为 `false` 时不运行整体 `astats`。
+    fn build_analysis_command(
+        &self,
+        input: &[&OsStr],
+        ffmpeg_path: &Path,
+        stream_index: usize,
+        highpass_frequencies: &[u32],
+        overall_astats: bool,
+    ) -> Command {
+        let mut command = ffmpeg_command(ffmpeg_path);
+
+        if self.config.ffmpeg.hide_banner {
+            command.arg("-hide_banner");
+        }
+        command.arg("-loglevel").arg(&self.config.ffmpeg.log_level);
+
+        let (filter_graph, output_count) = build_filter_graph(
+            highpass_frequencies,
+            stream_index,
+            self.config.ffmpeg.ebur128_args.as_deref(),
+            overall_astats,
+        );
+        command
+            .args(&self.config.ffmpeg.extra_input_args)
+            .args(input)
+            .arg("-filter_complex")
+            .arg(filter_graph);
+
+        // 输出选项只作用于紧随其后的输出，因此每个输出都要附加一次
+        for index in 0..output_count {
+            command
+                .arg("-map")
+                .arg(format!("[out{index}]"))
+                .args(&self.config.ffmpeg.extra_output_args)
+                .arg("-f")
+                .arg("null")
+                .arg("-");
+        }
+
+        command
+    }
+
+    /// 构建读取单个文件的FFmpeg命令，调用方再追加滤镜与输出
+    ///
+    /// 依次为 `-hide_banner -loglevel <log_level>`、`extra_input_args`、`-i <文件>`、
+    /// 选择音频流的 `-map 0:a:<n>` 与 `extra_output_args`。
+    fn file_command(&self, ffmpeg_path: &Path, file_path: &Path, log_level: &str) -> Command {
+        let mut command = ffmpeg_command(ffmpeg_path);
+        command
+            .arg("-hide_banner")
+            .arg("-loglevel")
+            .arg(log_level)
+            .args(&self.config.ffmpeg.extra_input_args)
+            .arg("-i")
+            .arg(file_path)
+            .arg("-map")
+            .arg(audio_stream_specifier(self.stream_index()))
+            .args(&self.config.ffmpeg.extra_output_args);
+        command
+    }
+
+    /// 执行FFmpeg命令并捕获stderr，应用配置中的超时时间
+    ///
+    /// 配置了 `max_concurrent_ffmpeg` 时先等待空闲名额，进程结束后归还。
+    /// 暂时性错误（见 [`process_utils::is_transient_error`]）最多重试 `max_retries` 次，
+    /// 等待重试期间不占用FFmpeg名额。
+    fn run_ffmpeg(&self, command: Command) -> Result<String> {
+        self.run_ffmpeg_with(command, process_utils::run_command_capture_stderr_timeout)
+    }
+
+    /// 与 [`AudioAnalyzer::run_ffmpeg`] 相同，但由 `run` 执行命令并决定捕获哪些输出
+    fn run_ffmpeg_with<T>(
+        &self,
+        command: Command,
+        run: impl Fn(Command, Option<Duration>) -> Result<T>,
+    ) -> Result<T> {
+        let timeout = self.config.ffmpeg.timeout_seconds.map(Duration::from_secs);
+        let mut retries = 0;
+
+        loop {
+            let result = {
+                let mut attempt = process_utils::clone_command(&command);
+                if self.config.low_priority {
+                    process_utils::set_low_priority(&mut attempt);
+                }
+                let _permit = self.ffmpeg_limiter.as_ref().map(Semaphore::acquire);
+                run(attempt, timeout)
+            };
+
+            match result {
+                Err(e)
+                    if retries < self.config.max_retries
+                        && process_utils::is_transient_error(&e) =>
+                {
+                    let delay = RETRY_BACKOFF * 2u32.pow(u32::from(retries));
+                    retries += 1;
+                    debug!(
+                        "{}",
+                        crate::tr!(
+                            "FFmpeg 执行失败（{}），{} 毫秒后第 {}/{} 次重试",
+                            "FFmpeg failed ({}), retry {2}/{3} in {1} ms",
+                            e,
+                            delay.as_millis(),
+                            retries,
+                            self.config.max_retries
+                        )
+                    );
+                    std::thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 设置批量分析的进度观察者，传入 `None` 关闭进度回调
+    ///
+    /// 默认在 `show_progress` 启用时使用 [`LogObserver`]。
+    pub fn set_progress_observer(&mut self, observer: Option<Arc<dyn ProgressObserver>>) {
+        self.progress_observer = observer;
+    }
+
+    /// 要分析的音频流序号（从 0 开始），未配置 `stream_index` 时为第一个音频流
+    fn stream_index(&self) -> usize {
+        self.config.stream_index.unwrap_or(0)
+    }
 
     /// 获取配置的引用
     pub fn config(&self) -> &AnalyzerConfig {
         &self.config
     }
 
+    /// 按 `profile_rules` 选择质量阈值的匹配器，汇总与排序时传给 [`crate::AnalysisSummary`] 的方法
+    pub fn profile_matcher(&self) -> &ProfileMatcher {
+        &self.profiles
+    }
+
     /// 检查依赖项是否已初始化
     pub fn is_initialized(&self) -> bool {
         self.dependencies.is_some()
     }
 
     /// 获取Python分析器路径（如果已初始化）
+    ///
+    /// 嵌入的Python分析器在第一次调用时才解压，只使用内置CSV报告的场景不会产生解压开销。
+    /// 解压失败时打印警告并返回 `None`，之后的调用不再重试；
+    /// 当前平台没有内置的Python分析器时同样返回 `None`。
     pub fn get_analyzer_path(&self) -> Option<&std::path::Path> {
-        self.dependencies
-            .as_ref()
-            .map(|deps| deps.analyzer_path.as_path())
+        let deps = self.dependencies.as_ref()?;
+        deps.analyzer_path
+            .get_or_init(|| {
+                let Some(bytes) = ANALYZER_BYTES else {
+                    debug!(
+                        "{}",
+                        crate::tr!(
+                            "当前平台 ({}) 没有内置的Python分析器",
+                            "No bundled Python analyzer for this platform ({})",
+                            current_platform()
+                        )
+                    );
+                    return None;
+                };
+                let path = deps.extraction_dir.join("audio_analyzer");
+                self.extract_embedded(bytes, ANALYZER_HASH, &path, "Python分析器", deps.reusable)
+                    .map_err(|e| {
+                        warn!(
+                            "{}",
+                            crate::tr!(
+                                "警告: 解压Python分析器失败: {e}",
+                                "Warning: failed to extract the Python analyzer: {e}"
+                            )
+                        )
+                    })
+                    .ok()
+                    .map(|()| path)
+            })
+            .as_deref()
+    }
+}
+
+/// 高通分支的 astats 实例名
+fn highpass_astats_instance(frequency: u32) -> String {
+    format!("astats@hp{frequency}")
+}
+
+/// 静音检测滤镜（带实例名）
+fn silence_detect_filter() -> String {
+    format!("{SILENCE_DETECT_INSTANCE}=noise={SILENCE_NOISE_DB}dB:d={SILENCE_MIN_DURATION_SECS}")
+}
+
+/// 自检测试音的 `lavfi` 信号源
+fn self_test_source() -> String {
+    format!(
+        "sine=frequency={SELF_TEST_FREQUENCY_HZ}:sample_rate=48000:duration={SELF_TEST_DURATION_SECS}"
+    )
+}
+
+/// 频谱平坦度滤镜链（带实例名）
+///
+/// astats 估计本底噪声后交给 `aspectralstats`，再由 `ametadata` 把逐帧结果打印到日志。
+/// 不使用 `aspectralstats` 的 `measure` 选项，以兼容 5.x 版本。
+fn spectral_flatness_filter() -> String {
+    format!("{NOISE_FLOOR_ASTATS_INSTANCE},aspectralstats,{FLATNESS_METADATA_INSTANCE}=mode=print")
+}
+
+/// 相位分析滤镜链（带实例名）
+///
+/// 先统一为立体声，再用 `pan` 展开为 左、右、中 (L+R)/2、侧 (L-R)/2 四个声道交给 astats。
+/// 由于 E[M²] - E[S²] = E[LR]，相关系数可由这四个声道的RMS算出。
+/// 单声道输入会被复制为两个相同的声道，不会使滤镜图出错，其结果由调用方丢弃。
+fn phase_filter() -> String {
+    format!(
+        "aformat=channel_layouts=stereo,\
+         pan=quad|c0=c0|c1=c1|c2=0.5*c0+0.5*c1|c3=0.5*c0-0.5*c1,\
+         {PHASE_ASTATS_INSTANCE}=metadata=1"
+    )
+}
+
+/// 构建合并分析的滤镜图，返回滤镜图字符串和输出分支数量
+///
+/// 分支顺序：`ebur128`、整体 `astats`、`silencedetect`、相位 `astats`、各频率的高通 `astats`。
+/// `ebur128_args` 为配置的附加 `ebur128` 选项（已经过验证）；
+/// `overall_astats` 为 `false` 时省去整体 `astats` 分支（电平统计已由原生解码得到）。
+fn build_filter_graph(
+    highpass_frequencies: &[u32],
+    stream_index: usize,
+    ebur128_args: Option<&str>,
+    overall_astats: bool,
+) -> (String, usize) {
+    let mut branches = vec![ebur128_filter(Some("peak=true"), ebur128_args)];
+    if overall_astats {
+        branches.push(format!("{OVERALL_ASTATS_INSTANCE}=metadata=1"));
+    }
+    branches.push(silence_detect_filter());
+    branches.push(phase_filter());
+    branches.extend(highpass_frequencies.iter().map(|&frequency| {
+        format!(
+            "highpass=f={frequency},{}=metadata=1",
+            highpass_astats_instance(frequency)
+        )
+    }));
+    let output_count = branches.len();
+
+    let split_labels: String = (0..output_count).map(|i| format!("[in{i}]")).collect();
+    let mut chains = vec![format!(
+        "[{}]asplit={output_count}{split_labels}",
+        audio_stream_specifier(stream_index)
+    )];
+    chains.extend(
+        branches
+            .iter()
+            .enumerate()
+            .map(|(index, branch)| format!("[in{index}]{branch}[out{index}]")),
+    );
+
+    (chains.join(";"), output_count)
+}
+
+/// 由内置选项与配置的附加选项组成 `ebur128` 滤镜字符串
+fn ebur128_filter(builtin: Option<&str>, extra: Option<&str>) -> String {
+    let options: Vec<&str> = builtin.into_iter().chain(extra).collect();
+    if options.is_empty() {
+        "ebur128".to_string()
+    } else {
+        format!("ebur128={}", options.join(":"))
+    }
+}
+
+/// 第 `stream_index` 个音频流（从 0 开始）的FFmpeg流说明符，如 `0:a:1`
+fn audio_stream_specifier(stream_index: usize) -> String {
+    format!("0:a:{stream_index}")
+}
+
+/// 创建FFmpeg命令，强制使用 C 区域设置
+///
+/// 部分区域设置（如德语、法语）下FFmpeg会以逗号作为小数点输出 `-3,2`，
+/// 导致所有数值解析静默失败，因此所有FFmpeg调用都必须经过此函数创建。
+fn ffmpeg_command(ffmpeg_path: &Path) -> Command {
+    let mut command = Command::new(ffmpeg_path);
+    command.env("LC_ALL", "C").env("LANG", "C");
+    command
+}
+
+/// 校验自检测试音的分析结果
+///
+/// 幅度为 1/8 的正弦波：峰值约 -18.1 dBFS，RMS 约 -21.1 dB；稳态信号的 LRA 接近 0。
+fn check_self_test_metrics(metrics: &AudioMetrics) -> Result<()> {
+    let checks = [
+        ("peakAmplitudeDb", metrics.peak_amplitude_db, -19.0..=-17.0),
+        ("overallRmsDb", metrics.overall_rms_db, -22.5..=-19.5),
+        ("lra", metrics.lra, 0.0..=1.0),
+    ];
+
+    for (name, value, expected) in checks {
+        match value {
+            Some(value) if expected.contains(&value) => {}
+            Some(value) => {
+                return Err(AnalyzerError::Other(crate::tr!(
+                    "自检失败: {name} = {value}，超出预期范围 {:?}",
+                    "Self-test failed: {name} = {value} is outside the expected range {:?}",
+                    expected
+                )))
+            }
+            None => {
+                return Err(AnalyzerError::Other(crate::tr!(
+                    "自检失败: 未能从FFmpeg输出中解析 {name}",
+                    "Self-test failed: could not parse {name} from the FFmpeg output"
+                )))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 运行 `ffmpeg -version` 并返回输出的第一行
+fn query_ffmpeg_version(ffmpeg_path: &Path) -> Result<String> {
+    let mut command = ffmpeg_command(ffmpeg_path);
+    command.arg("-version");
+
+    let (stdout, _) =
+        process_utils::run_command_capture_output_timeout(command, Some(FFMPEG_VALIDATE_TIMEOUT))?;
+    stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| AnalyzerError::ParseError {
+            message: messages::text(
+                "FFmpeg -version 没有输出版本信息",
+                "FFmpeg -version printed no version information",
+            )
+            .to_string(),
+            raw_data: None,
+            source: None,
+        })
+}
+
+/// 批量分析中已开始与已完成的文件数，用于生成进度信息
+struct BatchCounter {
+    total_files: usize,
+    started: AtomicUsize,
+    completed: AtomicUsize,
+}
+
+impl BatchCounter {
+    fn new(total_files: usize) -> Self {
+        Self {
+            total_files,
+            started: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// 影响分析结果的设置，序列化后作为缓存指纹
+///
+/// 新增影响分析结果的设置时需同步加入此结构体。
+#[derive(Serialize)]
+struct CacheFingerprint<'a> {
+    version: &'a str,
+    highpass_frequencies: &'a [u32],
+    profile_rules: &'a [ProfileRule],
+    capture_raw_astats: bool,
+    keep_raw_output: bool,
+    raw_output_max_chars: Option<usize>,
+    fingerprint: bool,
+    spectral_flatness: bool,
+    stream_index: usize,
+    peak_clipping_db: f64,
+    native_wav: bool,
+    analysis_window: Option<AnalysisWindow>,
+    extra_input_args: &'a [String],
+    extra_output_args: &'a [String],
+    ebur128_args: Option<&'a str>,
+}
+
+/// 尚未调用 [`AudioAnalyzer::initialize_dependencies`] 时返回的错误
+fn not_initialized() -> AnalyzerError {
+    AnalyzerError::DependencyError(
+        messages::text("依赖项未初始化", "dependencies are not initialized").to_string(),
+    )
+}
+
+/// 记录批量分析中失败与被跳过的文件：失败的以 `warn` 级别记录，跳过的只记录数量
+fn log_batch_outcome(report: &AnalysisReport) {
+    for (path, error) in &report.failed {
+        warn!(
+            "{}",
+            crate::tr!(
+                "处理失败: {}\n └─> 错误详情: {}",
+                "Failed: {}\n └─> Error: {}",
+                path.display(),
+                error
+            )
+        );
+    }
+    if !report.skipped.is_empty() {
+        debug!(
+            "{}",
+            crate::tr!(
+                "分析已取消，跳过 {} 个文件",
+                "Analysis cancelled, skipped {} files",
+                report.skipped.len()
+            )
+        );
+    }
+    if !report.undersized.is_empty() {
+        debug!(
+            "{}",
+            crate::tr!(
+                "跳过 {} 个空文件或过小的文件",
+                "Skipped {} empty or undersized files",
+                report.undersized.len()
+            )
+        );
+    }
+    if !report.not_audio.is_empty() {
+        debug!(
+            "{}",
+            crate::tr!(
+                "跳过 {} 个不是音频的文件",
+                "Skipped {} files that are not really audio",
+                report.not_audio.len()
+            )
+        );
+    }
+}
+
+/// 保存分析缓存，失败时只打印警告而不影响分析结果
+fn save_cache(cache: Option<&AnalysisCache>) {
+    if let Some(cache) = cache {
+        if let Err(e) = cache.save() {
+            warn!(
+                "{}",
+                crate::tr!(
+                    "警告: 保存分析缓存失败: {e}",
+                    "Warning: failed to save the analysis cache: {e}"
+                )
+            );
+        }
+    }
+}
+
+/// 流式分析持有的分析缓存，被丢弃时保存
+struct CacheSaver {
+    cache: Option<AnalysisCache>,
+    locale: Option<messages::Locale>,
+}
+
+impl CacheSaver {
+    fn get(&self) -> Option<&AnalysisCache> {
+        self.cache.as_ref()
+    }
+}
+
+impl Drop for CacheSaver {
+    fn drop(&mut self) {
+        messages::with_locale(self.locale, || save_cache(self.cache.as_ref()));
+    }
+}
+
+/// 从合并的stderr中提取指定滤镜实例的日志行
+///
+/// FFmpeg 为每行日志添加 `[实例名 @ 0x...] ` 前缀，这里只保留匹配实例的行并去掉前缀。
+fn filter_log_section(stderr: &str, instance: &str) -> String {
+    let prefix = format!("[{instance} @ ");
+
+    stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix(prefix.as_str()))
+        .filter_map(|rest| {
+            rest.split_once(']')
+                .map(|(_, message)| message.trim_start())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 解析输入文件的音频流信息
+///
+/// 只读取 `Input #0` 部分（输出部分同样带有 `Stream #0:0` 行），
+/// 多条音频流时以第一条为准，并记录音频流数量。
+/// 只有一条音频流且其描述中没有码率（多见于 VBR）时，以容器的平均码率代替。
+fn parse_format_info(stderr: &str, stream_index: usize) -> Result<FormatInfo> {
+    let input_section = input_section(stderr);
+
+    let streams: Vec<&str> = AUDIO_STREAM_REGEX
+        .captures_iter(input_section)
+        .filter_map(|caps| caps.get(1))
+        .map(|m| m.as_str())
+        .collect();
+
+    if streams.is_empty() {
+        return Err(AnalyzerError::ParseError {
+            message: messages::text("未找到音频流信息", "No audio stream information found")
+                .to_string(),
+            raw_data: Some(stderr.trim().to_string()),
+            source: None,
+        });
+    }
+    let Some(stream) = streams.get(stream_index) else {
+        return Err(AnalyzerError::ParseError {
+            message: crate::tr!(
+                "音频流 {} 不存在（文件共有 {} 个音频流）",
+                "Audio stream {} does not exist (the file has {} audio streams)",
+                audio_stream_specifier(stream_index),
+                streams.len()
+            ),
+            raw_data: Some(stderr.trim().to_string()),
+            source: None,
+        });
+    };
+
+    let mut info = parse_audio_stream(stream);
+    info.audio_stream_count = streams.len() as u32;
+    if info.bitrate_kbps.is_none() && streams.len() == 1 {
+        info.bitrate_kbps = CONTAINER_BITRATE_REGEX
+            .captures(input_section)
+            .and_then(|caps| caps[1].parse().ok());
+    }
+    Ok(info)
+}
+
+/// 输入文件是否没有音频流、只有作为封面的图片
+fn is_attached_picture_only(stderr: &str) -> bool {
+    let input_section = input_section(stderr);
+    !AUDIO_STREAM_REGEX.is_match(input_section) && ATTACHED_PICTURE_REGEX.is_match(input_section)
+}
+
+/// stderr 中 `Input #0` 部分（输入文件信息），不含输出部分
+fn input_section(stderr: &str) -> &str {
+    let section = stderr
+        .split_once("Input #0")
+        .map(|(_, rest)| rest)
+        .unwrap_or(stderr);
+    ["Output #", "Stream mapping:"]
+        .iter()
+        .filter_map(|marker| section.find(marker))
+        .min()
+        .map_or(section, |end| &section[..end])
+}
+
+/// 解析输入文件的时长（秒），时长未知（如 `N/A`）时返回 `None`
+fn parse_duration(stderr: &str) -> Option<f64> {
+    let caps = DURATION_REGEX.captures(stderr)?;
+    let field = |index: usize| caps.get(index)?.as_str().parse::<f64>().ok();
+    Some(field(1)? * 3600.0 + field(2)? * 60.0 + field(3)?)
+}
+
+/// 根据 silencedetect 输出计算静音部分占总时长 `duration` 的比例
+///
+/// 较旧的FFmpeg不会为持续到文件末尾的静音输出 `silence_end`，
+/// 此时未闭合的静音按持续到文件末尾计算。
+fn parse_silent_fraction(section: &str, duration: f64) -> Option<f64> {
+    if duration <= 0.0 {
+        return None;
+    }
+
+    let mut silent_secs = 0.0;
+    let mut open_start = None;
+    for line in section.lines() {
+        if let Some(start) = capture_f64(&SILENCE_START_REGEX, line) {
+            open_start = Some(start.max(0.0));
+        } else if let Some(caps) = SILENCE_END_REGEX.captures(line) {
+            if let Some(length) = caps.get(2).and_then(|m| m.as_str().parse::<f64>().ok()) {
+                silent_secs += length;
+            }
+            open_start = None;
+        }
+    }
+    if let Some(start) = open_start {
+        silent_secs += (duration - start).max(0.0);
+    }
+
+    Some((silent_secs / duration).clamp(0.0, 1.0))
+}
+
+/// 解析单条音频流描述，如 `flac, 96000 Hz, stereo, s32 (24 bit)`
+fn parse_audio_stream(description: &str) -> FormatInfo {
+    let fields: Vec<&str> = description.split(", ").map(str::trim).collect();
+
+    let codec = fields
+        .first()
+        .and_then(|field| field.split_whitespace().next())
+        .map(str::to_string);
+    let codec_profile = fields.first().and_then(|field| parse_codec_profile(field));
+    let bitrate_kbps = fields
+        .iter()
+        .find_map(|field| BITRATE_REGEX.captures(field))
+        .and_then(|caps| caps[1].parse().ok());
+
+    // 采样率之后依次为声道布局和采样格式
+    let rate_index = fields.iter().position(|f| SAMPLE_RATE_REGEX.is_match(f));
+    let sample_rate_hz = rate_index
+        .and_then(|i| SAMPLE_RATE_REGEX.captures(fields[i]))
+        .and_then(|caps| caps[1].parse().ok());
+    let channels = rate_index
+        .and_then(|i| fields.get(i + 1))
+        .and_then(|layout| parse_channel_layout(layout));
+    let bit_depth = rate_index
+        .and_then(|i| fields.get(i + 2))
+        .and_then(|sample_fmt| parse_bit_depth(sample_fmt, codec.as_deref()));
+
+    FormatInfo {
+        codec,
+        sample_rate_hz,
+        channels,
+        bit_depth,
+        audio_stream_count: 1,
+        bitrate_kbps,
+        codec_profile,
+        duration_secs: None,
+    }
+}
+
+/// 从编码描述（如 `aac (HE-AAC) (mp4a / 0x6134706D)`）中取出编码规格
+///
+/// FFmpeg 在编码名称之后依次用括号给出解码器名称（如 `mp3float`，全为小写）、
+/// 编码规格和编码标签（含 ` / `），其中只有编码规格含大写字母。
+fn parse_codec_profile(codec_field: &str) -> Option<String> {
+    CODEC_DETAIL_REGEX
+        .captures_iter(codec_field)
+        .filter_map(|caps| caps.get(1))
+        .map(|m| m.as_str())
+        .find(|detail| !detail.contains(" / ") && detail.chars().any(|c| c.is_ascii_uppercase()))
+        .map(str::to_string)
+}
+
+/// 将声道布局转换为声道数（`stereo`、`5.1(side)`、`6 channels` 等）
+fn parse_channel_layout(layout: &str) -> Option<u32> {
+    let name = layout.split('(').next().unwrap_or(layout).trim();
+    match name {
+        "mono" => Some(1),
+        "stereo" | "downmix" => Some(2),
+        "quad" => Some(4),
+        "hexagonal" => Some(6),
+        "octagonal" => Some(8),
+        _ => {
+            if let Some(count) = name.strip_suffix(" channels") {
+                return count.trim().parse().ok();
+            }
+            // 形如 5.1、7.1 的布局：主声道数 + LFE声道数
+            let (main, lfe) = name.split_once('.')?;
+            Some(main.parse::<u32>().ok()? + lfe.parse::<u32>().ok()?)
+        }
+    }
+}
+
+/// 根据采样格式推断位深度，浮点格式（有损编码常见）返回 `None`
+fn parse_bit_depth(sample_fmt: &str, codec: Option<&str>) -> Option<u32> {
+    if let Some(caps) = BIT_DEPTH_REGEX.captures(sample_fmt) {
+        return caps[1].parse().ok();
+    }
+
+    // PCM 编码名称中包含位深度，如 pcm_s24le
+    if let Some(bits) = codec
+        .and_then(|c| c.strip_prefix("pcm_"))
+        .map(|c| c.trim_start_matches(['s', 'u', 'f']))
+        .map(|c| c.trim_end_matches(|ch: char| ch.is_ascii_alphabetic()))
+        .filter(|_| !sample_fmt.starts_with("flt") && !sample_fmt.starts_with("dbl"))
+        .and_then(|bits| bits.parse().ok())
+    {
+        return Some(bits);
+    }
+
+    match sample_fmt.split_whitespace().next()?.trim_end_matches('p') {
+        "u8" => Some(8),
+        "s16" => Some(16),
+        "s32" => Some(32),
+        "s64" => Some(64),
+        _ => None,
+    }
+}
+
+/// 从合并的stderr中提取 ebur128 滤镜的日志
+///
+/// 与 [`filter_log_section`] 不同，保留 ebur128 日志行之后不带前缀的续行（`Summary:` 汇总块）。
+fn ebur128_log(stderr: &str) -> String {
+    let mut in_ebur128 = false;
+    stderr
+        .lines()
+        .filter(|line| {
+            if line.starts_with('[') {
+                in_ebur128 = line.starts_with("[Parsed_ebur128");
+            }
+            in_ebur128
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 解析 ebur128 滤镜的 stderr 输出
+///
+/// 优先使用结尾 `Summary:` 块中的汇总值；汇总块缺失时（例如进程被提前终止），
+/// 回退到最后一帧的逐帧输出值。
+fn parse_ebur128_output(stderr: &str) -> Result<Ebur128Summary> {
+    let summary = Ebur128Summary {
+        integrated_lufs: parse_summary_or_last(stderr, &EBUR128_SUMMARY_I_REGEX, &EBUR128_I_REGEX),
+        lra: parse_summary_or_last(stderr, &EBUR128_SUMMARY_LRA_REGEX, &EBUR128_LRA_REGEX),
+        true_peak_dbtp: parse_true_peak(stderr),
+    };
+
+    if summary.has_data() {
+        Ok(summary)
+    } else {
+        Err(AnalyzerError::ParseError {
+            message: messages::text(
+                "无法从EBU R128输出中解析LRA/综合响度",
+                "Could not parse LRA/integrated loudness from the EBU R128 output",
+            )
+            .to_string(),
+            raw_data: Some(stderr.trim().to_string()),
+            source: None,
+        })
+    }
+}
+
+/// 优先匹配汇总正则，失败时取逐帧正则的最后一个匹配值
+fn parse_summary_or_last(stderr: &str, summary_regex: &Regex, frame_regex: &Regex) -> Option<f64> {
+    let summary_value = summary_regex
+        .captures(stderr)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse::<f64>().ok());
+
+    summary_value.or_else(|| {
+        frame_regex
+            .captures_iter(stderr)
+            .filter_map(|caps| caps.get(1))
+            .filter_map(|m| m.as_str().parse::<f64>().ok())
+            .last()
+    })
+}
+
+/// 解析真峰值：优先使用汇总值，否则取最后一帧各声道 TPK 的最大值
+fn parse_true_peak(stderr: &str) -> Option<f64> {
+    let summary_value = EBUR128_SUMMARY_TRUE_PEAK_REGEX
+        .captures(stderr)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse::<f64>().ok());
+
+    summary_value.or_else(|| {
+        EBUR128_TPK_REGEX
+            .captures_iter(stderr)
+            .filter_map(|caps| caps.get(1))
+            .last()
+            .and_then(|m| {
+                m.as_str()
+                    .split_whitespace()
+                    .filter_map(|v| v.parse::<f64>().ok())
+                    .reduce(f64::max)
+            })
+    })
+}
+
+/// 固定解压目录中记录二进制文件校验值的文件路径
+fn hash_stamp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".hash");
+    path.with_file_name(name)
+}
+
+/// 校验解压出的二进制文件的长度与嵌入内容一致
+fn verify_extracted(path: &Path, expected_len: u64, name: &str) -> Result<()> {
+    let actual_len = fs::metadata(path)?.len();
+    if actual_len != expected_len {
+        return Err(AnalyzerError::DependencyError(crate::tr!(
+            "解压的{name}不完整: {}（{actual_len} 字节，应为 {expected_len} 字节），请检查磁盘空间后重试",
+            "Extracted {name} is incomplete: {} ({actual_len} bytes, expected {expected_len}); check free disk space and retry",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// 固定解压目录中记录的内容：嵌入内容的校验值、解压出的文件的长度与修改时间（纳秒）
+///
+/// 文件系统不提供修改时间时返回 `None`。
+fn extraction_stamp(hash: u64, metadata: &fs::Metadata) -> Option<String> {
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some(format!(
+        "{hash:016x} {} {}",
+        metadata.len(),
+        modified.as_nanos()
+    ))
+}
+
+/// 解析整体 astats 分支的统计信息（峰值、RMS、直流偏移和波峰因数）
+///
+/// astats 滤镜提供音频的基本统计信息，包括峰值电平和RMS电平。
+/// 这些指标用于评估音频的整体响度和是否存在削波等问题。
+///
+/// # 统计指标说明
+/// - **峰值电平 (Peak Level)**: 音频信号的最大振幅，单位为dB
+///   - 接近0dB表示可能存在削波风险
+///   - 低于-6dB通常被认为是安全的
+/// - **RMS电平 (RMS Level)**: 音频信号的有效值，反映平均响度
+///   - 比峰值电平更能反映人耳感知的响度
+/// - **直流偏移 (DC Offset)**: 信号的平均值，明显偏离0属于母带缺陷
+/// - **波峰因数 (Crest Factor)**: 峰值与RMS之比，单位为dB
+///   - astats 未输出时按 峰值dB − RMS dB 计算
+/// - **短时RMS最大/最小值 (RMS peak/trough)**: 逐窗口RMS的范围，反映短期动态
+///   - 只从 Overall 块读取；回退到简单正则表达式时为 `None`，`-inf` 同样记为 `None`
+fn parse_audio_stats(section: &str) -> Result<AudioStats> {
+    // 尝试使用复杂正则表达式匹配 Overall 块，否则回退到简单正则表达式
+    let (peak_db, rms_db, rms_peak_db, rms_trough_db) =
+        if let Some(caps) = ASTATS_OVERALL_REGEX.captures(section) {
+            let overall = overall_block(section);
+            (
+                caps.get(1).and_then(|m| m.as_str().parse::<f64>().ok()),
+                caps.get(2).and_then(|m| m.as_str().parse::<f64>().ok()),
+                capture_f64(&ASTATS_RMS_PEAK_REGEX, overall).filter(|db| db.is_finite()),
+                capture_f64(&ASTATS_RMS_TROUGH_REGEX, overall).filter(|db| db.is_finite()),
+            )
+        } else {
+            (
+                capture_f64(&SIMPLE_PEAK_REGEX, section),
+                capture_f64(&SIMPLE_RMS_REGEX, section),
+                None,
+                None,
+            )
+        };
+
+    if peak_db.is_none() && rms_db.is_none() {
+        return Err(AnalyzerError::ParseError {
+            message: messages::text(
+                "无法从astats输出中解析峰值/RMS",
+                "Could not parse peak/RMS from the astats output",
+            )
+            .to_string(),
+            raw_data: Some(section.trim().to_string()),
+            source: None,
+        });
+    }
+
+    let overall = overall_block(section);
+    let dc_offset = capture_f64(&ASTATS_DC_OFFSET_REGEX, overall);
+    let crest_factor_db = capture_f64(&ASTATS_CREST_FACTOR_REGEX, overall)
+        .filter(|ratio| *ratio > 0.0)
+        .map(|ratio| 20.0 * ratio.log10())
+        .or_else(|| Some(peak_db? - rms_db?));
+
+    Ok(AudioStats {
+        peak_db,
+        rms_db,
+        rms_peak_db,
+        rms_trough_db,
+        dc_offset,
+        crest_factor_db,
+        peak_count: capture_f64(&ASTATS_PEAK_COUNT_REGEX, overall),
+        sample_count: ASTATS_SAMPLE_COUNT_REGEX
+            .captures(overall)
+            .and_then(|caps| caps[1].parse().ok()),
+        flat_factor_db: capture_f64(&ASTATS_FLAT_FACTOR_REGEX, overall),
+        per_channel: parse_channel_stats(section),
+    })
+}
+
+/// 由相位分支的 astats 输出计算左右声道的相位相关系数
+///
+/// 声道依次为 左、右、中、侧（见 [`phase_filter`]）；任一侧声道为静音时无法计算。
+fn parse_phase_correlation(section: &str) -> Option<f64> {
+    let channels = parse_channel_stats(section);
+    let [left, right, mid, side] = channels.as_slice() else {
+        return None;
+    };
+    let energy = |stats: &ChannelStats| stats.rms_db.map(|db| 10f64.powf(db / 10.0));
+    let (left, right) = (energy(left)?, energy(right)?);
+    if left <= 0.0 || right <= 0.0 {
+        return None;
+    }
+    let correlation = (energy(mid)? - energy(side)?) / (left * right).sqrt();
+    Some(correlation.clamp(-1.0, 1.0))
+}
+
+/// 解析 astats 输出中 `Channel: N` 开头的各声道统计块
+fn parse_channel_stats(section: &str) -> Vec<ChannelStats> {
+    let mut channels = Vec::new();
+    let mut current: Option<ChannelStats> = None;
+
+    for line in section.lines().map(str::trim) {
+        if let Some(caps) = ASTATS_CHANNEL_HEADER_REGEX.captures(line) {
+            channels.extend(current.take());
+            current = caps[1].parse().ok().map(|channel| ChannelStats {
+                channel,
+                peak_db: None,
+                rms_db: None,
+            });
+        } else if ASTATS_OVERALL_HEADER_REGEX.is_match(line) {
+            channels.extend(current.take());
+        } else if let Some(stats) = current.as_mut() {
+            if let Some(value) = line.strip_prefix("Peak level dB:") {
+                stats.peak_db = value.trim().parse().ok();
+            } else if let Some(value) = line.strip_prefix("RMS level dB:") {
+                stats.rms_db = value.trim().parse().ok();
+            }
+        }
+    }
+    channels.extend(current);
+
+    channels
+}
+
+/// 取 astats 输出中 `Overall` 行之后的部分，没有 Overall 块时返回全部内容
+fn overall_block(section: &str) -> &str {
+    ASTATS_OVERALL_HEADER_REGEX
+        .find_iter(section)
+        .last()
+        .map_or(section, |m| &section[m.end()..])
+}
+
+/// 解析 ebur128 逐帧输出的短期响度，并按窗口在能量域取平均
+fn parse_loudness_timeseries(stderr: &str, window_secs: f64) -> Vec<(f64, f64)> {
+    // 窗口序号 -> (能量之和, 帧数)
+    let mut windows: BTreeMap<u64, (f64, usize)> = BTreeMap::new();
+
+    for caps in stderr
+        .lines()
+        .filter_map(|line| EBUR128_FRAME_SHORT_TERM_REGEX.captures(line))
+    {
+        let (Ok(time), Ok(short_term)) = (caps[1].parse::<f64>(), caps[2].parse::<f64>()) else {
+            continue;
+        };
+        if !short_term.is_finite() {
+            continue;
+        }
+
+        let window = windows
+            .entry((time / window_secs).floor() as u64)
+            .or_default();
+        window.0 += 10f64.powf(short_term / 10.0);
+        window.1 += 1;
+    }
+
+    windows
+        .into_iter()
+        .map(|(index, (energy, frames))| {
+            (
+                index as f64 * window_secs,
+                10.0 * (energy / frames as f64).log10(),
+            )
+        })
+        .collect()
+}
+
+/// 收集 astats Overall 块中所有可解析为有限数值的字段
+fn parse_all_astats(section: &str) -> BTreeMap<String, f64> {
+    overall_block(section)
+        .lines()
+        .filter_map(|line| ASTATS_FIELD_REGEX.captures(line.trim()))
+        .filter_map(|caps| {
+            let value = caps[2].parse::<f64>().ok().filter(|v| v.is_finite())?;
+            Some((caps[1].to_string(), value))
+        })
+        .collect()
+}
+
+/// 提取正则表达式第一个捕获组的数值
+fn capture_f64(regex: &Regex, text: &str) -> Option<f64> {
+    regex
+        .captures(text)
+        .and_then(|caps| caps.get(1))
+        .and_then(|m| m.as_str().parse::<f64>().ok())
+}
+
+/// 逐帧频谱平坦度的平均值，没有有效数值时返回 `None`
+///
+/// 完全静音的窗口平坦度为 NaN，不参与平均。
+fn parse_spectral_flatness(section: &str) -> Option<f64> {
+    let values: Vec<f64> = SPECTRAL_FLATNESS_REGEX
+        .captures_iter(section)
+        .filter_map(|caps| caps[1].parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+        .collect();
+    stats::mean(&values)
+}
+
+/// FFmpeg 是否因为缺少 `filter` 滤镜而失败（旧版本或精简编译）
+fn is_missing_filter(error: &AnalyzerError, filter: &str) -> bool {
+    let AnalyzerError::FfmpegError {
+        stderr: Some(stderr),
+        ..
+    } = error
+    else {
+        return false;
+    };
+    stderr
+        .lines()
+        .any(|line| line.contains("No such filter") && line.contains(filter))
+}
+
+/// FFmpeg 是否因为缺少 `muxer` 输出格式而失败（未启用相应的外部库）
+fn is_missing_muxer(error: &AnalyzerError, muxer: &str) -> bool {
+    let AnalyzerError::FfmpegError {
+        stderr: Some(stderr),
+        ..
+    } = error
+    else {
+        return false;
+    };
+    stderr
+        .lines()
+        .any(|line| line.contains("output format") && line.contains(&format!("'{muxer}'")))
+}
+
+/// 从FFmpeg输出中收集解码损坏/截断警告
+///
+/// 只检查解码器等组件的日志行（`[组件 @ 0x…]` 前缀之后的消息），
+/// 输入头部中的文件路径与元数据标签（如标题中的 "corrupt"）不会被误判。
+/// 损坏的数据包往往连续报告相同的消息，因此对日志行去重，
+/// 并最多保留 [`MAX_DECODE_WARNINGS`] 条。
+fn parse_decode_warnings(stderr: &str) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+    for line in stderr.lines().filter(|line| {
+        COMPONENT_LOG_LINE_REGEX
+            .captures(line.trim())
+            .and_then(|caps| caps.get(1))
+            .is_some_and(|message| DECODE_WARNING_REGEX.is_match(message.as_str()))
+    }) {
+        let message = line.trim().to_string();
+        if !warnings.contains(&message) {
+            warnings.push(message);
+            if warnings.len() >= MAX_DECODE_WARNINGS {
+                break;
+            }
+        }
+    }
+    warnings
+}
+
+/// 解析高通滤波分支的RMS值
+///
+/// ffmpeg 对完全静音的频段输出 `-inf`，此时返回 [`SILENT_BAND_DB`]；
+/// 找不到RMS值时返回解析错误，而不是伪装成静音频段。
+fn parse_highpass_rms(section: &str) -> Result<f64> {
+    // 优先匹配 Overall 块，否则回退到最后一个RMS行
+    let rms_str = HIGHPASS_ASTATS_REGEX
+        .captures(section)
+        .or_else(|| HIGHPASS_RMS_REGEX.captures_iter(section).last())
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str());
+
+    match rms_str {
+        Some("-inf") => Ok(SILENT_BAND_DB),
+        Some(value) => value.parse::<f64>().map_err(|e| AnalyzerError::ParseError {
+            message: crate::tr!(
+                "无效的高通RMS值: {value}",
+                "Invalid high-pass RMS value: {value}"
+            ),
+            raw_data: Some(section.trim().to_string()),
+            source: Some(Box::new(e)),
+        }),
+        None => Err(AnalyzerError::ParseError {
+            message: messages::text(
+                "无法从高通滤波输出中解析RMS",
+                "Could not parse RMS from the high-pass filter output",
+            )
+            .to_string(),
+            raw_data: Some(section.trim().to_string()),
+            source: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quality::QualityVerdict;
+    use crate::types::{QualityProfile, QualityThresholds};
+    use std::ffi::OsStr;
+
+    const EBUR128_STDERR: &str = "\
+[Parsed_ebur128_0 @ 0x600000c0c000] t: 0.4       TARGET:-23 LUFS    M: -18.2 S:-120.7     I: -18.2 LUFS       LRA:   0.0 LU  FTPK: -1.2 -1.5 dBFS  TPK: -1.2 -1.5 dBFS
+[Parsed_ebur128_0 @ 0x600000c0c000] t: 2.9       TARGET:-23 LUFS    M: -16.1 S: -16.4     I: -16.6 LUFS       LRA:   1.2 LU  FTPK: -0.9  0.2 dBFS  TPK: -0.4  0.2 dBFS
+[Parsed_ebur128_0 @ 0x600000c0c000] Summary:
+
+  Integrated loudness:
+    I:         -16.5 LUFS
+    Threshold: -26.8 LUFS
+
+  Loudness range:
+    LRA:         6.0 LU
+    Threshold: -36.9 LUFS
+    LRA low:   -20.4 LUFS
+    LRA high:  -14.4 LUFS
+
+  True peak:
+    Peak:        0.3 dBFS
+";
+
+    #[test]
+    fn test_parse_ebur128_summary() {
+        let summary = parse_ebur128_output(EBUR128_STDERR).unwrap();
+        assert_eq!(summary.integrated_lufs, Some(-16.5));
+        assert_eq!(summary.lra, Some(6.0));
+        assert_eq!(summary.true_peak_dbtp, Some(0.3));
+    }
+
+    #[test]
+    fn test_parse_ebur128_without_summary() {
+        let truncated = EBUR128_STDERR.split("Summary:").next().unwrap();
+        let summary = parse_ebur128_output(truncated).unwrap();
+        assert_eq!(summary.integrated_lufs, Some(-16.6));
+        assert_eq!(summary.lra, Some(1.2));
+        assert_eq!(summary.true_peak_dbtp, Some(0.2));
+    }
+
+    const ASTATS_STDERR: &str = "\
+[astats@overall @ 0x600000c0c100] Channel: 1
+[astats@overall @ 0x600000c0c100] DC offset: 0.250000
+[astats@overall @ 0x600000c0c100] Peak level dB: -1.500000
+[astats@overall @ 0x600000c0c100] RMS level dB: -17.000000
+[astats@overall @ 0x600000c0c100] Crest factor: 5.956621
+[astats@overall @ 0x600000c0c100] Channel: 2
+[astats@overall @ 0x600000c0c100] Peak level dB: -1.200000
+[astats@overall @ 0x600000c0c100] RMS level dB: -16.600000
+[astats@overall @ 0x600000c0c100] Overall
+[astats@overall @ 0x600000c0c100] DC offset: -0.000012
+[astats@overall @ 0x600000c0c100] Peak level dB: -1.200000
+[astats@overall @ 0x600000c0c100] RMS level dB: -16.800000
+[astats@overall @ 0x600000c0c100] RMS peak dB: -9.500000
+[astats@overall @ 0x600000c0c100] RMS trough dB: -41.250000
+[astats@overall @ 0x600000c0c100] Flat factor: 0.000000
+[astats@overall @ 0x600000c0c100] Peak count: 2.000000
+[astats@overall @ 0x600000c0c100] Number of samples: 480000
+[astats@hp16000 @ 0x600000c0c200] Overall
+[astats@hp16000 @ 0x600000c0c200] RMS level dB: -62.500000
+[astats@hp18000 @ 0x600000c0c300] Overall
+[astats@hp18000 @ 0x600000c0c300] RMS level dB: -75.250000
+";
+
+    #[test]
+    fn test_ebur128_log() {
+        let stderr = format!("Input #0, flac\n{EBUR128_STDERR}[out#0/null @ 0x2] size=N/A\n");
+        let log = ebur128_log(&stderr);
+        assert!(log.starts_with("[Parsed_ebur128_0 @"));
+        // 保留汇总块中不带前缀的续行
+        assert!(log.ends_with("    Peak:        0.3 dBFS"));
+        assert!(!log.contains("Input #0") && !log.contains("out#0"));
+
+        let error = parse_ebur128_output("Input #0, flac\n").unwrap_err();
+        assert!(matches!(
+            error,
+            AnalyzerError::ParseError { raw_data: Some(raw), .. } if raw == "Input #0, flac"
+        ));
+    }
+
+    #[test]
+    fn test_build_filter_graph() {
+        let (graph, outputs) = build_filter_graph(&[16000, 18000], 0, None, true);
+        assert_eq!(outputs, 6);
+        assert_eq!(
+            graph,
+            "[0:a:0]asplit=6[in0][in1][in2][in3][in4][in5];\
+             [in0]ebur128=peak=true[out0];\
+             [in1]astats@overall=metadata=1[out1];\
+             [in2]silencedetect@silence=noise=-60dB:d=0.5[out2];\
+             [in3]aformat=channel_layouts=stereo,\
+             pan=quad|c0=c0|c1=c1|c2=0.5*c0+0.5*c1|c3=0.5*c0-0.5*c1,\
+             astats@phase=metadata=1[out3];\
+             [in4]highpass=f=16000,astats@hp16000=metadata=1[out4];\
+             [in5]highpass=f=18000,astats@hp18000=metadata=1[out5]"
+        );
+
+        let (graph, _) = build_filter_graph(&[], 0, Some("dualmono=true:panlaw=-3.01"), true);
+        assert!(graph.contains("[in0]ebur128=peak=true:dualmono=true:panlaw=-3.01[out0]"));
+
+        // 电平统计来自原生解码时省去整体 astats，其余分支依次前移
+        let (graph, outputs) = build_filter_graph(&[18000], 0, None, false);
+        assert_eq!(outputs, 4);
+        assert!(!graph.contains(OVERALL_ASTATS_INSTANCE));
+        assert!(
+            graph.starts_with("[0:a:0]asplit=4[in0][in1][in2][in3];[in0]ebur128=peak=true[out0];")
+        );
+        assert!(graph.ends_with("[in3]highpass=f=18000,astats@hp18000=metadata=1[out3]"));
+        assert_eq!(
+            ebur128_filter(None, Some("target=-16")),
+            "ebur128=target=-16"
+        );
+        assert_eq!(ebur128_filter(None, None), "ebur128");
+    }
+
+    #[test]
+    fn test_parse_phase_correlation() {
+        let section = |left: &str, right: &str, mid: &str, side: &str| {
+            format!(
+                "Channel: 1\nRMS level dB: {left}\nChannel: 2\nRMS level dB: {right}\n\
+                 Channel: 3\nRMS level dB: {mid}\nChannel: 4\nRMS level dB: {side}\n\
+                 Overall\nRMS level dB: -20.0\n"
+            )
+        };
+
+        // 左右相同：侧声道为静音
+        let identical = parse_phase_correlation(&section("-20.0", "-20.0", "-20.0", "-inf"));
+        assert_eq!(identical, Some(1.0));
+        // 左右反相：中声道为静音
+        let inverted = parse_phase_correlation(&section("-20.0", "-20.0", "-inf", "-20.0"));
+        assert_eq!(inverted, Some(-1.0));
+        // 互不相关：中、侧能量相同，各为左右能量的一半
+        let uncorrelated = parse_phase_correlation(&section("-20.0", "-20.0", "-23.0", "-23.0"));
+        assert!(uncorrelated.unwrap().abs() < 1e-9);
+
+        // 一侧静音或缺少声道时无法计算
+        assert_eq!(
+            parse_phase_correlation(&section("-inf", "-20.0", "-26.0", "-26.0")),
+            None
+        );
+        assert_eq!(
+            parse_phase_correlation("Overall\nRMS level dB: -20.0\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_combined_astats_sections() {
+        let overall = filter_log_section(ASTATS_STDERR, OVERALL_ASTATS_INSTANCE);
+        let stats = parse_audio_stats(&overall).unwrap();
+        assert_eq!(stats.peak_db, Some(-1.2));
+        assert_eq!(stats.rms_db, Some(-16.8));
+        assert_eq!(stats.dc_offset, Some(-0.000012));
+        assert_eq!(stats.per_channel.len(), 2);
+        assert_eq!(stats.per_channel[0].channel, 1);
+        assert_eq!(stats.per_channel[0].peak_db, Some(-1.5));
+        assert_eq!(stats.per_channel[1].rms_db, Some(-16.6));
+        // Overall 块没有波峰因数，按峰值与RMS之差计算
+        assert!((stats.crest_factor_db.unwrap() - 15.6).abs() < 1e-9);
+        assert_eq!(stats.peak_count, Some(2.0));
+        assert_eq!(stats.sample_count, Some(480000));
+        assert_eq!(stats.flat_factor_db, Some(0.0));
+        assert_eq!(stats.rms_peak_db, Some(-9.5));
+        assert_eq!(stats.rms_trough_db, Some(-41.25));
+        assert_eq!(stats.rms_range_db(), Some(31.75));
+
+        // 含完全静音的窗口时最小值为 -inf，无法给出范围
+        let silent_gap =
+            parse_audio_stats("Overall\nPeak level dB: -1\nRMS level dB: -9\nRMS trough dB: -inf")
+                .unwrap();
+        assert_eq!(silent_gap.rms_trough_db, None);
+        assert_eq!(silent_gap.rms_range_db(), None);
+
+        let hp_18k = filter_log_section(ASTATS_STDERR, &highpass_astats_instance(18000));
+        assert_eq!(parse_highpass_rms(&hp_18k).unwrap(), -75.25);
+
+        let hp_16k = filter_log_section(ASTATS_STDERR, &highpass_astats_instance(16000));
+        assert_eq!(parse_highpass_rms(&hp_16k).unwrap(), -62.5);
+    }
+
+    #[test]
+    fn test_parse_loudness_timeseries() {
+        let stderr = "\
+[Parsed_ebur128_0 @ 0x1] t: 0.1        TARGET:-23 LUFS    M:-120.7 S:   -inf     I: -70.0 LUFS       LRA:   0.0 LU
+[Parsed_ebur128_0 @ 0x1] t: 0.5        TARGET:-23 LUFS    M: -20.0 S: -20.0     I: -20.0 LUFS       LRA:   0.0 LU
+[Parsed_ebur128_0 @ 0x1] t: 0.9        TARGET:-23 LUFS    M: -20.0 S: -20.0     I: -20.0 LUFS       LRA:   0.0 LU
+[Parsed_ebur128_0 @ 0x1] t: 1.5        TARGET:-23 LUFS    M: -10.0 S: -10.0     I: -15.0 LUFS       LRA:   1.0 LU
+[Parsed_ebur128_0 @ 0x1] t: 1.9        TARGET:-23 LUFS    M: -30.0 S: -20.0     I: -15.0 LUFS       LRA:   1.0 LU
+[Parsed_ebur128_0 @ 0x1] Summary:";
+        let series = parse_loudness_timeseries(stderr, 1.0);
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0], (0.0, -20.0));
+        // -10 与 -20 LUFS 在能量域平均约为 -12.6 LUFS
+        assert_eq!(series[1].0, 1.0);
+        assert!((series[1].1 + 12.596).abs() < 1e-3);
+
+        assert_eq!(parse_loudness_timeseries(stderr, 10.0).len(), 1);
+        assert!(parse_loudness_timeseries("", 1.0).is_empty());
+    }
+
+    /// 原生 WAV 路径与FFmpeg astats 的结果应在很小的误差内一致
+    ///
+    /// 需要通过 `AUDIO_ANALYZER_FFMPEG` 指定可运行的FFmpeg，
+    /// 用 `cargo test --features native-wav -- --ignored` 运行。
+    #[cfg(feature = "native-wav")]
+    #[test]
+    #[ignore = "需要通过 AUDIO_ANALYZER_FFMPEG 指定可运行的FFmpeg"]
+    fn test_native_wav_matches_ffmpeg() {
+        let ffmpeg = std::env::var(crate::config::FFMPEG_PATH_ENV)
+            .expect("运行此测试需要设置 AUDIO_ANALYZER_FFMPEG");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sine.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for n in 0..44100 * 3 {
+            let phase = 2.0 * std::f64::consts::PI * 440.0 * f64::from(n) / 44100.0;
+            writer
+                .write_sample((0.4 * phase.sin() * 8_388_607.0) as i32)
+                .unwrap();
+            writer
+                .write_sample((0.1 * phase.cos() * 8_388_607.0) as i32)
+                .unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(PathBuf::from(ffmpeg));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let ffmpeg_path = analyzer.dependencies.as_ref().unwrap().ffmpeg_path.clone();
+
+        let input = [OsStr::new("-i"), path.as_os_str()];
+        let run = |level_stats| {
+            analyzer
+                .run_analysis(
+                    &input,
+                    &ffmpeg_path,
+                    0,
+                    AudioMetrics::from_path(&path, 0),
+                    &ConfigOverrides::default(),
+                    level_stats,
+                )
+                .unwrap()
+        };
+        let native = run(Some(crate::native::wav_level_stats(&path).unwrap()));
+        let via_ffmpeg = run(None);
+
+        let close = |a: Option<f64>, b: Option<f64>| (a.unwrap() - b.unwrap()).abs() < 0.05;
+        assert!(close(
+            native.peak_amplitude_db,
+            via_ffmpeg.peak_amplitude_db
+        ));
+        assert!(close(native.overall_rms_db, via_ffmpeg.overall_rms_db));
+        assert!(close(native.rms_peak_db, via_ffmpeg.rms_peak_db));
+        assert!(close(native.rms_trough_db, via_ffmpeg.rms_trough_db));
+        assert_eq!(native.clipped_samples, via_ffmpeg.clipped_samples);
+        assert_eq!(native.channel_stats.len(), via_ffmpeg.channel_stats.len());
+        // 响度与高频指标两条路径都由FFmpeg得到
+        assert_eq!(native.lra, via_ffmpeg.lra);
+        assert_eq!(native.rms_db_above(18000), via_ffmpeg.rms_db_above(18000));
+        assert!(native.is_complete());
+    }
+
+    /// 原生解码只代替整体 astats，响度与高频等指标仍由FFmpeg给出
+    #[cfg(feature = "native-wav")]
+    #[test]
+    fn test_native_wav_still_runs_ffmpeg() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for n in 0..8000 * 2 {
+            let value = if n % 2 == 0 { 16384i16 } else { -16384 };
+            writer.write_sample(value).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        // 假FFmpeg报告的峰值 (-3 dB) 与文件实际的 -6.02 dB 不同，用于区分数据来源
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        let metrics = analyzer.analyze_file(&path).unwrap();
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains("ebur128"));
+        assert!(!args.contains(OVERALL_ASTATS_INSTANCE));
+        assert!((metrics.peak_amplitude_db.unwrap() + 6.02).abs() < 0.01);
+        assert_eq!(metrics.lra, Some(0.0));
+
+        // 需要 astats 原始输出时改用FFmpeg的整体 astats
+        let mut config = analyzer.config().clone();
+        config.capture_raw_astats = true;
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let metrics = analyzer.analyze_file(&path).unwrap();
+        assert_eq!(metrics.peak_amplitude_db, Some(-3.0));
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.flac");
+        let b = dir.path().join("b.flac");
+        fs::write(&a, b"a").unwrap();
+        fs::write(&b, b"b").unwrap();
+
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -1.2));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        let diff = analyzer.compare(&a, &b).unwrap();
+        assert_eq!(diff.a.filename(), "a.flac");
+        assert_eq!(diff.b.filename(), "b.flac");
+        assert_eq!(diff.get("peakAmplitudeDb").unwrap().delta(), Some(0.0));
+
+        let missing = dir.path().join("missing.flac");
+        assert!(analyzer.compare(&a, &missing).is_err());
+    }
+
+    #[test]
+    fn test_parse_all_astats() {
+        let section = "\
+Channel: 1
+Peak level dB: -1.500000
+Overall
+DC offset: -0.000012
+Peak level dB: -1.200000
+RMS trough dB: -inf
+Flat factor: 0.000000
+Peak count: 2.000000
+Noise floor dB: -78.250000
+Bit depth: 24/24
+Number of samples: 480000";
+        let fields = parse_all_astats(section);
+        assert_eq!(fields.get("Peak level dB"), Some(&-1.2));
+        assert_eq!(fields.get("Noise floor dB"), Some(&-78.25));
+        assert_eq!(fields.get("Number of samples"), Some(&480000.0));
+        assert_eq!(fields.get("DC offset"), Some(&-0.000012));
+        // 非有限值与非数值不收录
+        assert!(!fields.contains_key("RMS trough dB"));
+        assert!(!fields.contains_key("Bit depth"));
+        assert_eq!(fields.len(), 6);
+    }
+
+    #[test]
+    fn test_parse_highpass_rms_silent_vs_missing() {
+        let silent = "Overall\nPeak level dB: -inf\nRMS level dB: -inf\n";
+        assert_eq!(parse_highpass_rms(silent).unwrap(), SILENT_BAND_DB);
+
+        assert!(parse_highpass_rms("").is_err());
+        assert!(parse_highpass_rms("Overall\nRMS level dB: -\n").is_err());
+    }
+
+    #[test]
+    fn test_locale_is_per_analyzer() {
+        let process_locale = messages::locale();
+        let config = AnalyzerConfig {
+            locale: Some(messages::Locale::En),
+            num_threads: Some(0),
+            ..Default::default()
+        };
+        let message = AudioAnalyzer::new(config).err().unwrap().to_string();
+        assert!(message.contains("The thread count must be greater than 0"));
+
+        let analyzer = AudioAnalyzer::new(AnalyzerConfig {
+            locale: Some(messages::Locale::En),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(messages::locale(), process_locale);
+        // 分析过程中产生的错误同样使用分析器的语言
+        let error = analyzer
+            .analyze_file(Path::new("missing.flac"))
+            .unwrap_err();
+        assert_eq!(
+            error.localized(messages::Locale::En),
+            "Dependency error: dependencies are not initialized"
+        );
+    }
+
+    #[test]
+    fn test_cache_fingerprint_tracks_result_settings() {
+        let fingerprint = |config: AnalyzerConfig| {
+            AudioAnalyzer::new(config)
+                .unwrap()
+                .cache_fingerprint()
+                .unwrap()
+        };
+        let base = fingerprint(AnalyzerConfig::default());
+        assert_eq!(base, fingerprint(AnalyzerConfig::default()));
+
+        // 截断长度影响结果中的 rawOutput
+        let truncated = fingerprint(AnalyzerConfig {
+            raw_output_max_chars: Some(100),
+            ..Default::default()
+        });
+        assert_ne!(base, truncated);
+
+        // 线程数等不影响结果的设置不改变指纹
+        let threaded = fingerprint(AnalyzerConfig {
+            num_threads: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(base, threaded);
+    }
+
+    #[test]
+    fn test_thread_pool_honors_num_threads() {
+        let config = AnalyzerConfig {
+            num_threads: Some(1),
+            show_progress: false,
+            ..Default::default()
+        };
+        let analyzer = AudioAnalyzer::new(config).unwrap();
+
+        let pool = analyzer.build_thread_pool().unwrap();
+        assert_eq!(pool.current_num_threads(), 1);
+        assert_eq!(pool.install(rayon::current_num_threads), 1);
+
+        // 单线程下结果数量保持确定（未初始化依赖项时所有文件均失败）
+        let files: Vec<PathBuf> = (0..8)
+            .map(|i| PathBuf::from(format!("missing_{i}.flac")))
+            .collect();
+        for _ in 0..3 {
+            assert_eq!(analyzer.analyze_files(&files).unwrap().len(), 0);
+        }
+    }
+
+    #[test]
+    fn test_analyze_files_detailed_reports_failures() {
+        let config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        let analyzer = AudioAnalyzer::new(config).unwrap();
+        let files = vec![PathBuf::from("a.flac"), PathBuf::from("b.flac")];
+
+        let report = analyzer.analyze_files_detailed(&files).unwrap();
+        assert!(report.succeeded.is_empty());
+        assert_eq!(report.total(), 2);
+        let failed: Vec<&PathBuf> = report.failed.iter().map(|(path, _)| path).collect();
+        assert_eq!(failed, files.iter().collect::<Vec<_>>());
+        assert!(matches!(
+            report.failed[0].1,
+            AnalyzerError::DependencyError(_)
+        ));
+    }
+
+    #[test]
+    fn test_analyze_file_rejects_unsupported_format() {
+        let analyzer = AudioAnalyzer::with_default_config().unwrap();
+
+        let error = analyzer.analyze_file(Path::new("notes.txt")).unwrap_err();
+        assert!(matches!(
+            error,
+            AnalyzerError::UnsupportedFormat { ref path, extension: Some(ref ext) }
+                if path == "notes.txt" && ext == "txt"
+        ));
+
+        let error = analyzer.analyze_file(Path::new("README")).unwrap_err();
+        assert!(matches!(
+            error,
+            AnalyzerError::UnsupportedFormat {
+                extension: None,
+                ..
+            }
+        ));
+
+        // 支持的格式照常进入后续流程（此处因依赖未初始化而失败）
+        assert!(matches!(
+            analyzer.analyze_file(Path::new("song.FLAC")),
+            Err(AnalyzerError::DependencyError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cancelled_batch_skips_remaining_files() {
+        let config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        let analyzer = AudioAnalyzer::new(config).unwrap();
+        let files = vec![PathBuf::from("a.flac"), PathBuf::from("b.flac")];
+
+        let cancel = AtomicBool::new(true);
+        let report = analyzer.run_batch(&files, Some(&cancel), None).unwrap();
+        assert!(report.succeeded.is_empty());
+        assert!(report.failed.is_empty());
+        assert_eq!(report.skipped, files);
+
+        let cancelled = analyzer
+            .analyze_files_cancellable(&files, Arc::new(AtomicBool::new(true)))
+            .unwrap();
+        assert!(cancelled.is_empty());
+    }
+
+    #[test]
+    fn test_progress_observer_receives_events() {
+        #[derive(Default)]
+        struct CountingObserver {
+            started: AtomicUsize,
+            failed: AtomicUsize,
+        }
+
+        impl ProgressObserver for CountingObserver {
+            fn on_file_start(&self, progress: &AnalysisProgress) {
+                assert_eq!(progress.total_files, 3);
+                self.started.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn on_file_error(&self, progress: &AnalysisProgress, _path: &Path, _e: &AnalyzerError) {
+                assert!(progress.completed_files >= 1);
+                self.failed.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut analyzer = AudioAnalyzer::with_default_config().unwrap();
+        let observer = Arc::new(CountingObserver::default());
+        analyzer.set_progress_observer(Some(observer.clone()));
+
+        let files: Vec<PathBuf> = ["a.flac", "b.flac", "c.flac"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        analyzer.analyze_files_detailed(&files).unwrap();
+
+        assert_eq!(observer.started.load(Ordering::SeqCst), 3);
+        assert_eq!(observer.failed.load(Ordering::SeqCst), 3);
+    }
+
+    const FORMAT_STDERR: &str = "\
+Input #0, matroska,webm, from 'multi.mka':
+  Duration: 00:03:12.00, start: 0.000000, bitrate: 2400 kb/s
+  Stream #0:0(eng): Audio: flac, 96000 Hz, stereo, s32 (24 bit) (default)
+  Stream #0:1(jpn): Audio: aac (LC), 44100 Hz, 5.1(side), fltp
+Stream mapping:
+  Stream #0:0 (flac) -> asplit
+Output #0, null, to 'pipe:':
+  Stream #0:0: Audio: pcm_s16le, 48000 Hz, mono, s16, 768 kb/s
+";
+
+    #[test]
+    fn test_parse_format_info() {
+        let info = parse_format_info(FORMAT_STDERR, 0).unwrap();
+        assert_eq!(info.codec.as_deref(), Some("flac"));
+        assert_eq!(info.sample_rate_hz, Some(96000));
+        assert_eq!(info.channels, Some(2));
+        assert_eq!(info.bit_depth, Some(24));
+        assert_eq!(info.audio_stream_count, 2);
+
+        // 指定第二个音频流时读取该流的格式
+        let info = parse_format_info(FORMAT_STDERR, 1).unwrap();
+        assert_eq!(info.codec.as_deref(), Some("aac"));
+        assert_eq!(info.codec_profile.as_deref(), Some("LC"));
+        assert_eq!(info.channels, Some(6));
+        assert_eq!(info.audio_stream_count, 2);
+        // 多条音频流时容器码率是所有流的总和，不能代替单条流的码率
+        assert_eq!(info.bitrate_kbps, None);
+        let message = messages::with_locale(Some(messages::Locale::Zh), || {
+            parse_format_info(FORMAT_STDERR, 2).unwrap_err().to_string()
+        });
+        assert!(message.contains("音频流 0:a:2 不存在"));
+
+        assert!(parse_format_info("", 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_bitrate_and_profile() {
+        let cbr = parse_format_info(
+            "Input #0, mp3, from 'a.mp3':\n\
+             \x20 Duration: 00:03:00.00, start: 0.025057, bitrate: 320 kb/s\n\
+             \x20 Stream #0:0: Audio: mp3 (mp3float), 44100 Hz, stereo, fltp, 320 kb/s\n",
+            0,
+        )
+        .unwrap();
+        assert_eq!(cbr.bitrate_kbps, Some(320));
+        assert_eq!(cbr.codec_profile, None);
+
+        let he_aac = parse_audio_stream(
+            "aac (HE-AAC) (mp4a / 0x6134706D), 44100 Hz, stereo, fltp, 64 kb/s (default)",
+        );
+        assert_eq!(he_aac.codec_profile.as_deref(), Some("HE-AAC"));
+        assert_eq!(he_aac.bitrate_kbps, Some(64));
+
+        // VBR 文件的音频流没有码率，使用容器的平均码率
+        let vbr = parse_format_info(
+            "Input #0, ogg, from 'a.opus':\n\
+             \x20 Duration: 00:04:10.00, start: 0.000000, bitrate: 131 kb/s\n\
+             \x20 Stream #0:0: Audio: opus, 48000 Hz, stereo, fltp\n",
+            0,
+        )
+        .unwrap();
+        assert_eq!(vbr.bitrate_kbps, Some(131));
+
+        let unknown = parse_format_info(
+            "Input #0, ogg, from 'a.opus':\n\
+             \x20 Duration: N/A, start: 0.000000, bitrate: N/A\n\
+             \x20 Stream #0:0: Audio: opus, 48000 Hz, stereo, fltp\n",
+            0,
+        )
+        .unwrap();
+        assert_eq!(unknown.bitrate_kbps, None);
+    }
+
+    #[test]
+    fn test_not_really_audio() {
+        let cover_only = "\
+Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'cover.m4a':
+  Duration: 00:00:00.04, start: 0.000000, bitrate: 5 kb/s
+  Stream #0:0[0x1](und): Video: mjpeg (Baseline), yuvj420p, 600x600, 90k tbn (attached pic)
+Stream specifier ':a:0' in filtergraph description matches no streams.";
+        assert!(is_attached_picture_only(cover_only));
+        assert!(!is_attached_picture_only(FORMAT_STDERR));
+
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<PathBuf> = ["cover.m4a", "short.flac", "track.flac"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        for file in &files {
+            fs::write(file, b"audio").unwrap();
+        }
+
+        let config = AnalyzerConfig {
+            show_progress: false,
+            min_audio_duration_secs: Some(1.0),
+            ..Default::default()
+        };
+        // 文件名含 cover 时只输出封面图片的流信息并失败，含 short 时时长只有 0.4 秒
+        let analyzer = wrapped_analyzer(
+            dir.path(),
+            "ffmpeg-cover",
+            &format!(
+                "*cover*) cat >&2 <<'EOF'\n{cover_only}\nEOF\n exit 1;;\n\
+                 *short*) echo '  Duration: 00:00:00.40, start: 0.000000' >&2;;"
+            ),
+            config,
+        );
+
+        assert!(matches!(
+            analyzer.analyze_file(&files[0]),
+            Err(AnalyzerError::NotAudio {
+                reason: NotAudioReason::AttachedPictureOnly,
+                ..
+            })
+        ));
+        let report = analyzer.analyze_files_detailed(&files).unwrap();
+        assert_eq!(report.succeeded.len(), 1);
+        assert!(report.is_all_succeeded());
+        assert_eq!(
+            report.not_audio,
+            [
+                (files[0].clone(), NotAudioReason::AttachedPictureOnly),
+                (
+                    files[1].clone(),
+                    NotAudioReason::TooShort { duration_secs: 0.4 }
+                ),
+            ]
+        );
+
+        // 不检查时长时，短文件照常分析，只有封面的文件仍然跳过
+        let mut config = analyzer.config().clone();
+        config.min_audio_duration_secs = None;
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let metrics = analyzer.analyze_file(&files[1]).unwrap();
+        assert_eq!(metrics.duration_secs, Some(0.4));
+        assert!(metrics.is_not_really_audio(1.0));
+        assert!(!metrics.is_not_really_audio(0.2));
+        let report = analyzer.analyze_files_detailed(&files).unwrap();
+        assert_eq!(report.succeeded.len(), 2);
+        assert_eq!(report.not_audio.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_silent_fraction() {
+        assert_eq!(parse_duration(FORMAT_STDERR), Some(192.0));
+        assert_eq!(parse_duration("  Duration: N/A, bitrate: N/A"), None);
+
+        let stderr = "\
+[silencedetect@silence @ 0x1] silence_start: 0
+[silencedetect@silence @ 0x1] silence_end: 2.5 | silence_duration: 2.5
+[silencedetect@silence @ 0x1] silence_start: 90
+[astats@overall @ 0x2] Overall";
+        let section = filter_log_section(stderr, SILENCE_DETECT_INSTANCE);
+        // 开头 2.5 秒，加上从 90 秒持续到末尾的未闭合静音
+        let fraction = parse_silent_fraction(&section, 100.0).unwrap();
+        assert!((fraction - 0.125).abs() < 1e-9);
+
+        assert_eq!(parse_silent_fraction("", 100.0), Some(0.0));
+        assert_eq!(parse_silent_fraction(&section, 0.0), None);
+    }
+
+    #[test]
+    fn test_parse_audio_stream_variants() {
+        let mp3 = parse_audio_stream("mp3 (mp3float), 44100 Hz, stereo, fltp, 320 kb/s");
+        assert_eq!(mp3.codec.as_deref(), Some("mp3"));
+        assert_eq!(mp3.bit_depth, None);
+
+        let wav = parse_audio_stream(
+            "pcm_s24le ([1][0][0][0] / 0x0001), 48000 Hz, 6 channels, s32, 6912 kb/s",
+        );
+        assert_eq!(wav.sample_rate_hz, Some(48000));
+        assert_eq!(wav.channels, Some(6));
+        assert_eq!(wav.bit_depth, Some(24));
+
+        let cd = parse_audio_stream("flac, 44100 Hz, mono, s16");
+        assert_eq!(cd.channels, Some(1));
+        assert_eq!(cd.bit_depth, Some(16));
+
+        assert_eq!(parse_channel_layout("7.1"), Some(8));
+    }
+
+    #[test]
+    fn test_external_ffmpeg_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let fake_ffmpeg = dir.path().join("ffmpeg");
+        fs::write(&fake_ffmpeg, "#!/bin/sh\necho 'ffmpeg version test'\n").unwrap();
+        fs::set_permissions(&fake_ffmpeg, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut config = AnalyzerConfig::default();
+        config.ffmpeg.binary_path = Some(fake_ffmpeg.clone());
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        assert_eq!(
+            analyzer.dependencies.as_ref().unwrap().ffmpeg_path,
+            fake_ffmpeg
+        );
+        assert_eq!(analyzer.ffmpeg_version().unwrap(), "ffmpeg version test");
+
+        let mut config = AnalyzerConfig::default();
+        config.ffmpeg.binary_path = Some(dir.path().join("missing-ffmpeg"));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        assert!(matches!(
+            analyzer.initialize_dependencies(),
+            Err(AnalyzerError::DependencyError(_))
+        ));
+        assert!(!analyzer.is_initialized());
+        assert!(matches!(
+            analyzer.ffmpeg_version(),
+            Err(AnalyzerError::DependencyError(_))
+        ));
+    }
+
+    #[test]
+    fn test_environment_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AnalyzerConfig {
+            num_threads: Some(3),
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -18.06));
+
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        let report = analyzer.environment_report();
+        assert_eq!(report.crate_version, crate::VERSION);
+        assert_eq!(report.thread_count, 3);
+        assert!(report.ffmpeg_path.is_none());
+        assert!(report.ffmpeg_version.is_none());
+        assert!(report.ffmpeg_error.is_some());
+        assert!(!report.python_analyzer_available);
+
+        analyzer.initialize_dependencies().unwrap();
+        let report = analyzer.environment_report();
+        assert_eq!(
+            report.ffmpeg_version.as_deref(),
+            Some("ffmpeg version fake")
+        );
+        assert!(report.ffmpeg_error.is_none());
+        assert!(report.cpu_count >= 1);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["threadCount"], 3);
+        assert_eq!(json["ffmpegVersion"], "ffmpeg version fake");
+    }
+
+    /// 创建输出固定分析结果的假FFmpeg脚本，并记录收到的参数
+    fn fake_analysis_ffmpeg(dir: &Path, peak_db: f64) -> PathBuf {
+        let script = format!(
+            "#!/bin/sh\n\
+             [ \"$1\" = -version ] && {{ echo 'ffmpeg version fake'; exit 0; }}\n\
+             echo \"$@\" > \"{args}\"\n\
+             cat >&2 <<'EOF'\n\
+             [Parsed_ebur128_0 @ 0x1] Summary:\n\
+             \n  Loudness range:\n    LRA:         0.0 LU\n\
+             [astats@overall @ 0x2] Overall\n\
+             [astats@overall @ 0x2] Peak level dB: {peak_db}\n\
+             [astats@overall @ 0x2] RMS level dB: -21.070000\n\
+             EOF\n",
+            args = dir.join("args.txt").display()
+        );
+        let path = dir.join("ffmpeg");
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// 创建先按 `case_arms`（`case "$*"` 的分支）处理、其余调用交给 [`fake_analysis_ffmpeg`]
+    /// 的假FFmpeg，返回使用它并已初始化依赖项的分析器
+    fn wrapped_analyzer(
+        dir: &Path,
+        name: &str,
+        case_arms: &str,
+        mut config: AnalyzerConfig,
+    ) -> AudioAnalyzer {
+        let analysis = fake_analysis_ffmpeg(dir, -3.0);
+        let script = format!(
+            "#!/bin/sh\n\
+             case \"$*\" in\n{case_arms}\nesac\n\
+             exec \"{}\" \"$@\"\n",
+            analysis.display()
+        );
+        let path = dir.join(name);
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        config.ffmpeg.binary_path = Some(path);
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        analyzer
+    }
+
+    #[test]
+    fn test_analyze_files_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = dir.path().join("a.flac");
+        fs::write(&good, b"audio").unwrap();
+        let unsupported = dir.path().join("notes.txt");
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let analyzer = Arc::new(analyzer);
+
+        let receiver = Arc::clone(&analyzer)
+            .analyze_files_channel(vec![good.clone(), unsupported.clone()])
+            .unwrap();
+        let mut results: Vec<_> = receiver.iter().collect();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, good);
+        assert_eq!(results[0].1.as_ref().unwrap().peak_amplitude_db, Some(-3.0));
+        assert_eq!(results[1].0, unsupported);
+        assert!(matches!(
+            results[1].1,
+            Err(AnalyzerError::UnsupportedFormat { .. })
+        ));
+
+        // 没有文件时通道直接关闭
+        let receiver = analyzer.analyze_files_channel(Vec::new()).unwrap();
+        assert!(receiver.recv().is_err());
+    }
+
+    #[test]
+    fn test_analysis_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.wav");
+        fs::write(&file, b"audio").unwrap();
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        config.ffmpeg.analyze_start_secs = Some(600.0);
+        config.ffmpeg.analyze_duration_secs = Some(90.5);
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        // 只分析片段时即使启用了原生 WAV 解码也交给FFmpeg
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains(&format!("-ss 600 -t 90.5 -i {}", file.display())));
+        assert_eq!(
+            metrics.analysis_window,
+            Some(AnalysisWindow {
+                start_secs: 600.0,
+                duration_secs: Some(90.5),
+            })
+        );
+        let json = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(json["analysisWindow"]["durationSecs"], 90.5);
+    }
+
+    #[test]
+    fn test_analyze_file_with_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("interlude.flac");
+        fs::write(&file, b"audio").unwrap();
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -0.5));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        // 峰值 -0.5 dB 超过母带预设的削波阈值 (-1.0 dB)，但低于音乐预设的阈值 (-0.1 dB)
+        let overrides = ConfigOverrides {
+            quality_thresholds: Some(QualityThresholds::mastering()),
+            highpass_frequencies: Some(vec![12000]),
+        };
+        let metrics = analyzer.analyze_file_with(&file, &overrides).unwrap();
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains("highpass=f=12000"));
+        assert!(!args.contains("highpass=f=18000"));
+        assert_eq!(metrics.clipped_samples, None);
+        assert_eq!(
+            metrics.quality_verdict,
+            Some(metrics.verdict(&QualityThresholds::mastering()))
+        );
+
+        // 共享配置不受影响
+        assert_eq!(
+            analyzer.config().highpass_frequencies,
+            [16000, 18000, 20000]
+        );
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains("highpass=f=18000"));
+        assert_eq!(metrics.clipped_samples, Some(0));
+
+        // 无效的覆盖项不会调用FFmpeg
+        fs::remove_file(dir.path().join("args.txt")).unwrap();
+        let invalid = ConfigOverrides {
+            highpass_frequencies: Some(vec![18000, 16000]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            analyzer.analyze_file_with(&file, &invalid),
+            Err(AnalyzerError::ConfigError(_))
+        ));
+        assert!(!dir.path().join("args.txt").exists());
+
+        // 批量分析按 profile_rules 为匹配的文件选择预设
+        let mut config = analyzer.config().clone();
+        config.profile_rules = vec![ProfileRule {
+            pattern: "*interlude*".to_string(),
+            profile: QualityProfile::Mastering,
+        }];
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let report = analyzer.analyze_files_detailed(&[file]).unwrap();
+        assert_eq!(report.succeeded[0].clipped_samples, None);
+    }
+
+    #[test]
+    fn test_stream_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.wav");
+        fs::write(&file, b"audio").unwrap();
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            stream_index: Some(1),
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        // 指定了其他音频流时即使启用了原生 WAV 解码也交给FFmpeg
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        assert_eq!(metrics.peak_amplitude_db, Some(-3.0));
+        // 只有峰值时核心指标不足，不给出分数等级
+        assert_eq!(metrics.quality_verdict, Some(QualityVerdict::Incomplete));
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains("[0:a:1]asplit="));
+
+        // 单项提取同样只读取指定的音频流
+        let _ = analyzer.extract_silence(&file);
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains(&format!("-i {} -map 0:a:1 -af", file.display())));
+    }
+
+    #[test]
+    fn test_extra_ffmpeg_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.flac");
+        fs::write(&file, b"audio").unwrap();
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            highpass_frequencies: vec![18000],
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        config.ffmpeg.extra_input_args = vec!["-err_detect".to_string(), "ignore_err".to_string()];
+        config.ffmpeg.extra_output_args = vec!["-threads".to_string(), "1".to_string()];
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        analyzer.analyze_file(&file).unwrap();
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains(&format!("-err_detect ignore_err -i {}", file.display())));
+        // 每个输出都带有输出选项
+        assert_eq!(args.matches("-threads 1 -f null -").count(), 5);
+
+        let _ = analyzer.extract_silence(&file);
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains(&format!(
+            "-err_detect ignore_err -i {} -map 0:a:0 -threads 1 -af",
+            file.display()
+        )));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.flac");
+        fs::write(&file, b"audio").unwrap();
+        let config = AnalyzerConfig {
+            show_progress: false,
+            fingerprint: true,
+            ..Default::default()
+        };
+        // chromaprint 调用输出两个小端序指纹值，其余调用交给分析用的假FFmpeg
+        let analyzer = wrapped_analyzer(
+            dir.path(),
+            "ffmpeg-fp",
+            "*chromaprint*) printf '\\170\\126\\064\\022\\377\\000\\000\\000'; exit 0;;",
+            config,
+        );
+
+        assert_eq!(
+            analyzer.extract_fingerprint(&file).unwrap(),
+            "12345678000000ff"
+        );
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        assert_eq!(metrics.peak_amplitude_db, Some(-3.0));
+        assert_eq!(metrics.fingerprint.as_deref(), Some("12345678000000ff"));
+        let json = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(json["fingerprint"], "12345678000000ff");
+
+        // FFmpeg 没有 chromaprint 时省略指纹，其余指标照常
+        let analyzer = wrapped_analyzer(
+            dir.path(),
+            "ffmpeg-no-fp",
+            "*chromaprint*) echo \"[out#0 @ 0x1] Requested output format 'chromaprint' is not known.\" >&2; exit 1;;",
+            analyzer.config().clone(),
+        );
+        assert!(matches!(
+            analyzer.extract_fingerprint(&file),
+            Err(AnalyzerError::FfmpegError { .. })
+        ));
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        assert_eq!(metrics.peak_amplitude_db, Some(-3.0));
+        assert_eq!(metrics.fingerprint, None);
+
+        // 没有指纹输出时返回解析错误
+        let mut config = analyzer.config().clone();
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        assert!(matches!(
+            analyzer.extract_fingerprint(&file),
+            Err(AnalyzerError::ParseError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_spectral_flatness() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.flac");
+        fs::write(&file, b"audio").unwrap();
+        let config = AnalyzerConfig {
+            show_progress: false,
+            spectral_flatness: true,
+            ..Default::default()
+        };
+        // 频谱平坦度调用输出本底噪声与逐帧元数据；静音窗口的 nan 不参与平均
+        let analyzer = wrapped_analyzer(
+            dir.path(),
+            "ffmpeg-flatness",
+            "*aspectralstats*) cat >&2 <<'EOF'\n\
+             [astats@noisefloor @ 0x1] Overall\n\
+             [astats@noisefloor @ 0x1] Noise floor dB: -84.500000\n\
+             [ametadata@flatness @ 0x2] frame:0    pts:0       pts_time:0\n\
+             [ametadata@flatness @ 0x2] lavfi.aspectralstats.1.flatness=0.200000\n\
+             [ametadata@flatness @ 0x2] lavfi.aspectralstats.2.flatness=0.400000\n\
+             [ametadata@flatness @ 0x2] lavfi.aspectralstats.1.flatness=nan\n\
+             EOF\n\
+             exit 0;;",
+            config.clone(),
+        );
+
+        let flatness = analyzer.extract_spectral_flatness(&file).unwrap().unwrap();
+        assert!((flatness.flatness - 0.3).abs() < 1e-9);
+        assert_eq!(flatness.noise_floor_db, Some(-84.5));
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        assert_eq!(metrics.noise_floor_db, Some(-84.5));
+        let json = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(json["noiseFloorDb"], -84.5);
+
+        // 旧版FFmpeg没有 aspectralstats 滤镜时不计算，也不影响其余分析
+        let analyzer = wrapped_analyzer(
+            dir.path(),
+            "ffmpeg-old",
+            "*aspectralstats*) \
+             echo \"[AVFilterGraph @ 0x1] No such filter: 'aspectralstats'\" >&2; exit 1;;",
+            config,
+        );
+
+        assert_eq!(analyzer.extract_spectral_flatness(&file).unwrap(), None);
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        assert_eq!(metrics.peak_amplitude_db, Some(-3.0));
+        assert_eq!(metrics.spectral_flatness, None);
+        assert!(serde_json::to_value(&metrics)
+            .unwrap()
+            .get("spectralFlatness")
+            .is_none());
+    }
+
+    #[test]
+    fn test_keep_raw_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.flac");
+        fs::write(&file, b"audio").unwrap();
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            keep_raw_output: true,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config.clone()).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        let metrics = analyzer.analyze_file(&file).unwrap();
+        let raw = metrics.raw_output.unwrap();
+        assert!(raw["ebur128"].contains("LRA:         0.0 LU"));
+        assert_eq!(
+            raw["astats"],
+            "Overall\nPeak level dB: -3\nRMS level dB: -21.070000"
+        );
+        // 高通频段没有输出，同样保留空的原始输出便于确认
+        assert_eq!(raw["rmsDbAbove18k"], "");
+
+        config.raw_output_max_chars = Some(7);
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let raw = analyzer.analyze_file(&file).unwrap().raw_output.unwrap();
+        assert_eq!(raw["astats"], "Overall…（已截断）");
+
+        // 解析错误同样附带截断后的完整输出
+        let error = analyzer.extract_silence(&file).unwrap_err();
+        let AnalyzerError::ParseError { raw_data, .. } = error else {
+            panic!("应为解析错误: {error}");
+        };
+        assert_eq!(raw_data.unwrap(), "[Parsed…（已截断）");
+    }
+
+    #[test]
+    fn test_timeout_keeps_partial_metrics() {
+        let analyze = |log: &str| {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("a.flac");
+            fs::write(&file, b"audio").unwrap();
+            // 输出部分日志后挂起；exec 使 kill 直接作用于 sleep，管道随之关闭
+            let script = format!(
+                "#!/bin/sh\n\
+                 [ \"$1\" = -version ] && {{ echo 'ffmpeg version fake'; exit 0; }}\n\
+                 printf '%s\\n' '{log}' >&2\n\
+                 exec sleep 5\n"
+            );
+            let ffmpeg = dir.path().join("ffmpeg");
+            fs::write(&ffmpeg, script).unwrap();
+            fs::set_permissions(&ffmpeg, fs::Permissions::from_mode(0o755)).unwrap();
+
+            let mut config = AnalyzerConfig {
+                show_progress: false,
+                ..Default::default()
+            };
+            config.ffmpeg.binary_path = Some(ffmpeg);
+            config.ffmpeg.timeout_seconds = Some(1);
+            let mut analyzer = AudioAnalyzer::new(config).unwrap();
+            analyzer.initialize_dependencies().unwrap();
+            analyzer.analyze_file(&file)
+        };
+
+        let metrics = analyze(
+            "[Parsed_ebur128_0 @ 0x1] t: 0.4 TARGET:-23 LUFS M: -18.2 S: -19.0 I: -18.2 LUFS LRA: 2.5 LU",
+        )
+        .unwrap();
+        assert!(metrics.partial);
+        assert_eq!(metrics.integrated_lufs, Some(-18.2));
+        assert_eq!(metrics.lra, Some(2.5));
+        // astats 只在结束时输出，超时后缺失
+        assert_eq!(metrics.peak_amplitude_db, None);
+
+        // 超时前没有任何测量值时仍报告超时错误
+        let error = analyze("Input #0, flac, from 'a.flac':").unwrap_err();
+        assert!(process_utils::is_timeout_error(&error));
+    }
+
+    /// 写出一个模拟FFmpeg：每次调用追加一行到 `calls.txt`，
+    /// 前 `kills` 次分析调用以 SIGKILL 结束自身，之后输出正常的分析日志
+    fn flaky_ffmpeg(dir: &Path, kills: usize) -> PathBuf {
+        let inner_dir = dir.join("inner");
+        fs::create_dir_all(&inner_dir).unwrap();
+        let inner = fake_analysis_ffmpeg(&inner_dir, -3.0);
+        let calls = dir.join("calls.txt");
+        let script = format!(
+            "#!/bin/sh\n\
+             [ \"$1\" = -version ] && {{ echo 'ffmpeg version fake'; exit 0; }}\n\
+             echo call >> \"{calls}\"\n\
+             [ $(wc -l < \"{calls}\") -le {kills} ] && kill -9 $$\n\
+             exec \"{inner}\" \"$@\"\n",
+            calls = calls.display(),
+            inner = inner.display()
+        );
+        let path = dir.join("ffmpeg");
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_retry_transient_ffmpeg_failures() {
+        let analyze = |kills: usize, max_retries: u8| {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("a.flac");
+            fs::write(&file, b"audio").unwrap();
+            let mut config = AnalyzerConfig {
+                show_progress: false,
+                max_retries,
+                ..Default::default()
+            };
+            config.ffmpeg.binary_path = Some(flaky_ffmpeg(dir.path(), kills));
+            let mut analyzer = AudioAnalyzer::new(config).unwrap();
+            analyzer.initialize_dependencies().unwrap();
+            let result = analyzer.analyze_file(&file);
+            let calls = fs::read_to_string(dir.path().join("calls.txt")).unwrap();
+            (result, calls.lines().count())
+        };
+
+        let (result, calls) = analyze(2, 2);
+        assert_eq!(result.unwrap().peak_amplitude_db, Some(-3.0));
+        assert_eq!(calls, 3);
+
+        let (result, calls) = analyze(2, 1);
+        assert!(matches!(result, Err(AnalyzerError::FfmpegError { .. })));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_parse_decode_warnings() {
+        let stderr = "\
+Input #0, mp3, from 'broken.mp3':
+  Stream #0:0: Audio: mp3, 44100 Hz, stereo, fltp, 320 kb/s
+[mp3float @ 0x55d1] Header missing
+[mp3float @ 0x55d1] Header missing
+[in#0/mp3 @ 0x55d2] Error while decoding stream #0:0: Invalid data found when processing input
+[astats@overall @ 0x55d3] Overall
+[astats@overall @ 0x55d3] Peak level dB: -1.000000";
+
+        let warnings = parse_decode_warnings(stderr);
+        assert_eq!(
+            warnings,
+            vec![
+                "[mp3float @ 0x55d1] Header missing",
+                "[in#0/mp3 @ 0x55d2] Error while decoding stream #0:0: Invalid data found when processing input",
+            ]
+        );
+
+        let repeated = "[flac @ 0x1] invalid residual\n".repeat(3)
+            + &(0..50)
+                .map(|i| format!("[flac @ 0x1] frame {i} truncated\n"))
+                .collect::<String>();
+        assert_eq!(parse_decode_warnings(&repeated).len(), MAX_DECODE_WARNINGS);
+
+        assert!(parse_decode_warnings("[astats@overall @ 0x2] Overall").is_empty());
+
+        // 文件名与标签中的可疑字样不算解码异常
+        let suspicious_names = "\
+Input #0, flac, from '/music/corrupt_take/truncated mix.flac':
+  Metadata:
+    title           : Corrupt Partial File (Demo)
+    comment         : lost sync edit
+  Duration: 00:03:12.00, start: 0.000000, bitrate: 912 kb/s
+  Stream #0:0: Audio: flac, 44100 Hz, stereo, s16
+Output #0, null, to 'pipe:':
+[astats@overall @ 0x2] Overall";
+        assert!(parse_decode_warnings(suspicious_names).is_empty());
+    }
+
+    #[test]
+    fn test_self_test() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AnalyzerConfig {
+            show_progress: false,
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -18.06));
+
+        let mut analyzer = AudioAnalyzer::new(config.clone()).unwrap();
+        assert!(matches!(
+            analyzer.self_test(),
+            Err(AnalyzerError::DependencyError(_))
+        ));
+        analyzer.initialize_dependencies().unwrap();
+        analyzer.self_test().unwrap();
+
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains("-f lavfi -i sine=frequency=1000"));
+
+        let tone = dir.path().join("tone.flac");
+        analyzer.write_test_tone(&tone).unwrap();
+        let args = fs::read_to_string(dir.path().join("args.txt")).unwrap();
+        assert!(args.contains(&format!(
+            "-f lavfi -i {} -y {}",
+            self_test_source(),
+            tone.display()
+        )));
+
+        // 指标超出预期范围时报告具体字段
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        let message = analyzer.self_test().unwrap_err().to_string();
+        assert!(message.contains("peakAmplitudeDb"));
+    }
+
+    // 需要当前平台有内置的二进制文件
+    #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+    #[test]
+    fn test_extraction_dir_reuses_complete_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let extraction_dir = dir.path().join("bin");
+        let config = AnalyzerConfig {
+            extraction_dir: Some(extraction_dir.clone()),
+            ..Default::default()
+        };
+        let analyzer_path = extraction_dir.join("audio_analyzer");
+
+        let mut analyzer = AudioAnalyzer::new(config.clone()).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        // Python分析器在首次使用时才解压
+        assert!(extraction_dir.join("ffmpeg").exists());
+        assert!(!analyzer_path.exists());
+        assert_eq!(analyzer.get_analyzer_path(), Some(analyzer_path.as_path()));
+        assert_eq!(
+            fs::metadata(&analyzer_path).unwrap().len(),
+            ANALYZER_BYTES.unwrap().len() as u64
+        );
+        assert!(hash_stamp_path(&analyzer_path).exists());
+        drop(analyzer);
+        // 固定目录不随分析器释放而删除
+        assert!(analyzer_path.exists());
+
+        // 完整的文件直接复用
+        let modified = fs::metadata(&analyzer_path).unwrap().modified().unwrap();
+        let mut analyzer = AudioAnalyzer::new(config.clone()).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        assert!(analyzer.get_analyzer_path().is_some());
+        assert_eq!(
+            fs::metadata(&analyzer_path).unwrap().modified().unwrap(),
+            modified
+        );
+
+        // 被截断的文件会重新解压
+        fs::write(&analyzer_path, b"partial").unwrap();
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+        assert!(analyzer.get_analyzer_path().is_some());
+        assert_eq!(
+            fs::metadata(&analyzer_path).unwrap().len(),
+            ANALYZER_BYTES.unwrap().len() as u64
+        );
+    }
+
+    #[cfg(not(all(target_arch = "aarch64", target_os = "macos")))]
+    #[test]
+    fn test_no_embedded_ffmpeg_for_platform() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AnalyzerConfig {
+            extraction_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        if analyzer.config().ffmpeg.resolve_binary_path().is_some() {
+            return; // 环境变量指定了外部FFmpeg
+        }
+
+        let err = analyzer.initialize_dependencies().unwrap_err();
+        assert!(matches!(err, AnalyzerError::DependencyError(_)));
+        let message = err.to_string();
+        assert!(message.contains(std::env::consts::ARCH));
+        assert!(message.contains("binary_path"));
+        assert!(!analyzer.is_initialized());
+        assert!(!dir.path().join("ffmpeg").exists());
+    }
+
+    #[test]
+    fn test_extracted_binary_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let analyzer = AudioAnalyzer::new(AnalyzerConfig::default()).unwrap();
+        let bytes = b"#!/bin/sh\nexit 0\n";
+        let path = dir.path().join("tool");
+
+        analyzer
+            .extract_binary_optimized(bytes, &path, "tool")
+            .unwrap();
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        // 被截断的文件
+        fs::write(&path, &bytes[..4]).unwrap();
+        let message = messages::with_locale(Some(messages::Locale::Zh), || {
+            let err = verify_extracted(&path, bytes.len() as u64, "tool").unwrap_err();
+            assert!(matches!(err, AnalyzerError::DependencyError(_)));
+            err.to_string()
+        });
+        assert!(message.contains("不完整"));
+    }
+
+    #[test]
+    fn test_reused_binary_is_checked_against_stamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let analyzer = AudioAnalyzer::new(AnalyzerConfig::default()).unwrap();
+        let bytes = b"#!/bin/sh\nexit 0\n";
+        let path = dir.path().join("tool");
+        let stamp_path = hash_stamp_path(&path);
+
+        analyzer
+            .extract_binary_reusable(bytes, 0x1234, &path, "tool")
+            .unwrap();
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+        let stamp = fs::read_to_string(&stamp_path).unwrap();
+        assert!(stamp.starts_with(&format!("{:016x} {} ", 0x1234, bytes.len())));
+
+        // 未改动的文件直接复用
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        analyzer
+            .extract_binary_reusable(bytes, 0x1234, &path, "tool")
+            .unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), modified);
+        assert_eq!(fs::read_to_string(&stamp_path).unwrap(), stamp);
+
+        // 长度不变但内容被改写的文件会重新解压
+        let corrupted = vec![b'x'; bytes.len()];
+        fs::write(&path, &corrupted).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified + std::time::Duration::from_secs(1))
+            .unwrap();
+        analyzer
+            .extract_binary_reusable(bytes, 0x1234, &path, "tool")
+            .unwrap();
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        // 嵌入内容的校验值变化（新版本）时重新解压
+        analyzer
+            .extract_binary_reusable(bytes, 0x5678, &path, "tool")
+            .unwrap();
+        assert!(fs::read_to_string(&stamp_path)
+            .unwrap()
+            .starts_with(&format!("{:016x} ", 0x5678)));
+    }
+
+    #[test]
+    fn test_hash_stamp_path() {
+        assert_eq!(
+            hash_stamp_path(Path::new("/tmp/bin/ffmpeg")),
+            Path::new("/tmp/bin/ffmpeg.hash")
+        );
+    }
+
+    #[test]
+    fn test_analyzer_is_send_and_sync() {
+        fn _assert_send<T: Send>() {}
+        fn _assert_sync<T: Sync>() {}
+        _assert_send::<AudioAnalyzer>();
+        _assert_sync::<AudioAnalyzer>();
+    }
+
+    #[test]
+    fn test_estimate_clipped_samples() {
+        let clipped = "Overall\nPeak level dB: 0.000000\nRMS level dB: -8.000000\n\
+                       Flat factor: 18.500000\nPeak count: 1200.000000\nNumber of samples: 480000";
+        let stats = parse_audio_stats(clipped).unwrap();
+        assert_eq!(stats.flat_factor_db, Some(18.5));
+        assert_eq!(stats.estimate_clipped_samples(-0.1), Some(1200));
+        // 峰值低于阈值时不算削波
+        assert_eq!(stats.estimate_clipped_samples(0.5), Some(0));
+
+        // 归一化到满幅但峰值只是孤立采样点
+        let normalized = "Overall\nPeak level dB: 0.000000\nRMS level dB: -14.000000\n\
+                          Flat factor: 0.000000\nPeak count: 2\nNumber of samples: 480000";
+        let stats = parse_audio_stats(normalized).unwrap();
+        assert_eq!(stats.estimate_clipped_samples(-0.1), Some(0));
+
+        let inf =
+            parse_audio_stats("Overall\nPeak level dB: -1\nRMS level dB: -9\nFlat factor: -inf")
+                .unwrap();
+        assert_eq!(inf.flat_factor_db, Some(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_parse_audio_stats_simple_fallback() {
+        let section =
+            "Peak level dB: -3.000000\nRMS level dB: -20.000000\nCrest factor: 10.000000\n\
+                       RMS peak dB: -12.000000\nRMS trough dB: -30.000000";
+        let stats = parse_audio_stats(section).unwrap();
+        assert_eq!(stats.peak_db, Some(-3.0));
+        assert_eq!(stats.rms_db, Some(-20.0));
+        assert_eq!(stats.dc_offset, None);
+        assert_eq!(stats.crest_factor_db, Some(20.0));
+        assert_eq!(stats.rms_peak_db, None);
+        assert_eq!(stats.rms_trough_db, None);
+    }
+
+    #[test]
+    fn test_ffmpeg_command_forces_c_locale() {
+        let analyzer = AudioAnalyzer::with_default_config().unwrap();
+        let input = [OsStr::new("-i"), OsStr::new("a.flac")];
+        let command = analyzer.build_analysis_command(
+            &input,
+            Path::new("/usr/bin/ffmpeg"),
+            0,
+            &[18000],
+            true,
+        );
+
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new("LC_ALL"), Some(OsStr::new("C")))));
+        assert!(envs.contains(&(OsStr::new("LANG"), Some(OsStr::new("C")))));
+    }
+
+    #[test]
+    fn test_analyze_directory_streaming() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.flac", "b.mp3", "notes.txt"] {
+            fs::write(dir.path().join(name), b"data").unwrap();
+        }
+        let config = AnalyzerConfig {
+            num_threads: Some(1),
+            ..Default::default()
+        };
+        let analyzer = AudioAnalyzer::new(config).unwrap();
+
+        // 未初始化依赖项，每个音频文件都产出一个错误结果
+        let results: Vec<_> = analyzer
+            .analyze_directory_streaming(dir.path())
+            .unwrap()
+            .collect();
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|r| matches!(r, Err(AnalyzerError::DependencyError(_)))));
+    }
+
+    #[test]
+    fn test_streaming_saves_cache_when_stopped_early() {
+        let dir = tempfile::tempdir().unwrap();
+        let music = dir.path().join("music");
+        fs::create_dir(&music).unwrap();
+        for name in ["a.flac", "b.flac", "c.flac"] {
+            fs::write(music.join(name), b"audio").unwrap();
+        }
+        let cache_path = dir.path().join("cache.json");
+        let mut config = AnalyzerConfig {
+            num_threads: Some(1),
+            cache_path: Some(cache_path.clone()),
+            ..Default::default()
+        };
+        config.ffmpeg.binary_path = Some(fake_analysis_ffmpeg(dir.path(), -3.0));
+        let mut analyzer = AudioAnalyzer::new(config).unwrap();
+        analyzer.initialize_dependencies().unwrap();
+
+        let mut results = analyzer.analyze_directory_streaming(&music).unwrap();
+        assert!(results.next().unwrap().is_ok());
+        assert!(!cache_path.exists());
+        drop(results);
+
+        let cache = AnalysisCache::load(&cache_path, &analyzer.cache_fingerprint().unwrap());
+        assert!(cache.get(&music.join("a.flac")).is_some());
+    }
+
+    #[test]
+    fn test_parse_ebur128_empty_output() {
+        assert!(parse_ebur128_output("").is_err());
     }
 }
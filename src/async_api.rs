@@ -0,0 +1,47 @@
+//! # 异步分析接口
+//!
+//! 供基于 Tokio 的异步服务使用（`tokio` 特性）。FFmpeg 分析本身是阻塞的，
+//! 这里把每个文件的分析放到 `spawn_blocking` 线程池中执行，避免占用异步运行时的工作线程。
+//! FFmpeg 的超时与并发限制沿用同步接口的配置（`ffmpeg.timeout_seconds`、`max_concurrent_ffmpeg`）。
+
+use crate::analyzer::AudioAnalyzer;
+use crate::error::{AnalyzerError, Result};
+use crate::types::AudioMetrics;
+use futures_util::stream::{self, Stream, StreamExt};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+impl AudioAnalyzer {
+    /// 异步分析单个音频文件
+    ///
+    /// 与 [`AudioAnalyzer::analyze_file`] 相同，但在 Tokio 的阻塞线程池中执行。
+    /// 需要在 Tokio 运行时中调用，分析器通过 `Arc` 在任务间共享。
+    ///
+    /// 丢弃返回的 Future 不会中断已经开始的分析：阻塞线程持有分析器直到本次FFmpeg调用结束，
+    /// 子进程随后被回收，不会成为孤儿或僵尸进程。需要限制单个文件的耗时请设置
+    /// `ffmpeg.timeout_seconds`。
+    pub async fn analyze_file_async(self: Arc<Self>, file_path: PathBuf) -> Result<AudioMetrics> {
+        tokio::task::spawn_blocking(move || self.analyze_file(&file_path))
+            .await
+            .map_err(|e| {
+                AnalyzerError::Other(crate::tr!(
+                    "分析任务异常终止: {e}",
+                    "The analysis task terminated abnormally: {e}"
+                ))
+            })?
+    }
+
+    /// 异步分析多个音频文件，按输入顺序逐个产出结果
+    ///
+    /// 同时进行的分析数与同步批量分析的线程数相同（`num_threads`）；
+    /// 丢弃返回的 `Stream` 后不会再启动新的分析。
+    pub fn analyze_files_async(
+        self: Arc<Self>,
+        file_paths: Vec<PathBuf>,
+    ) -> impl Stream<Item = Result<AudioMetrics>> {
+        let concurrency = self.config().effective_thread_count();
+        stream::iter(file_paths)
+            .map(move |path| Arc::clone(&self).analyze_file_async(path))
+            .buffered(concurrency)
+    }
+}
@@ -3,173 +3,912 @@
 //! 这是音频质量分析器的主入口点，提供命令行界面和用户交互功能。
 
 use audio_analyzer_ultimate::{
-    utils::{input_utils, Timer},
-    AnalyzerConfig, AudioAnalyzer, Result,
+    config::PathStyler,
+    messages::{self, text},
+    report, tr,
+    types::QualityProfile,
+    utils::{fs_utils, input_utils, process_utils, string_utils, Timer},
+    AnalysisReport, AnalysisSummary, AnalyzerConfig, AudioAnalyzer, AudioMetrics, Locale,
+    MetricKey, MetricsDiff, OutputFormat, PathStyle, ProfileMatcher, Result, ThresholdSource,
 };
 use chrono::Local;
 use clap::{Arg, Command as ClapCommand};
-use std::fs;
-use std::path::PathBuf;
+use log::LevelFilter;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-/// 主程序入口点
-fn main() -> Result<()> {
+/// 主程序入口点，出错时以本地化的文字输出错误并以退出码 1 结束
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+/// 解析命令行参数并执行分析
+fn run() -> Result<()> {
     // 解析命令行参数
     let matches = ClapCommand::new("audio-analyzer")
         .version("4.0.0")
         .author("Audio Analyzer Team")
-        .about("高性能音频质量分析器")
-        .long_about(
+        .about(text("高性能音频质量分析器", "High-performance audio quality analyzer"))
+        .long_about(text(
             "一个基于 Rust + Python 的高性能音频质量分析工具，支持批量处理和详细的质量评估报告。",
-        )
+            "A high-performance audio quality analysis tool built on Rust + Python, \
+             with batch processing and detailed quality reports.",
+        ))
         .arg(
             Arg::new("input")
-                .help("要分析的音频文件或目录路径")
+                .help(text(
+                    "要分析的音频文件或目录路径",
+                    "Audio file or directory to analyze",
+                ))
                 .value_name("PATH")
                 .index(1),
         )
+        .arg(
+            Arg::new("files-from")
+                .long("files-from")
+                .help(text(
+                    "从文件读取要分析的文件列表（每行一个路径，- 表示标准输入），不扫描目录",
+                    "Read the files to analyze from a list (one path per line, - for stdin) instead of scanning a directory",
+                ))
+                .value_name("FILE")
+                .conflicts_with("input"),
+        )
         .arg(
             Arg::new("output")
                 .short('o')
                 .long("output")
-                .help("输出目录路径")
+                .help(text(
+                    "输出目录路径",
+                    "Output directory",
+                ))
                 .value_name("DIR"),
         )
         .arg(
             Arg::new("threads")
                 .short('j')
                 .long("threads")
-                .help("并行线程数")
+                .help(text(
+                    "并行线程数",
+                    "Number of worker threads",
+                ))
                 .value_name("NUM")
                 .value_parser(clap::value_parser!(usize)),
         )
+        .arg(
+            Arg::new("max-ffmpeg")
+                .long("max-ffmpeg")
+                .help(text(
+                    "同时运行的FFmpeg进程数上限（默认不额外限制，每个分析线程各自运行FFmpeg）",
+                    "Maximum number of concurrent FFmpeg processes (default: no extra limit, one per analysis thread)",
+                ))
+                .value_name("NUM")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("stream")
+                .long("stream")
+                .help(text(
+                    "要分析的音频流序号（从 0 开始，默认第一个音频流），用于包含多个音轨的文件",
+                    "Index of the audio stream to analyze (0-based, defaults to the first), for files with several audio tracks",
+                ))
+                .value_name("INDEX")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("low-priority")
+                .long("low-priority")
+                .help(text(
+                    "以较低的优先级运行FFmpeg，减少对前台程序的影响",
+                    "Run FFmpeg at a lower priority to stay out of the way of foreground programs",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("verbose")
                 .short('v')
                 .long("verbose")
-                .help("启用详细输出")
+                .help(text(
+                    "启用详细输出",
+                    "Enable verbose output",
+                ))
                 .action(clap::ArgAction::SetTrue),
         )
         .arg(
             Arg::new("quiet")
                 .short('q')
                 .long("quiet")
-                .help("静默模式，只显示错误")
+                .help(text(
+                    "静默模式，只显示警告和错误",
+                    "Quiet mode, only show warnings and errors",
+                ))
                 .action(clap::ArgAction::SetTrue),
         )
         .arg(
             Arg::new("config")
                 .short('c')
                 .long("config")
-                .help("配置文件路径")
+                .help(text(
+                    "配置文件路径",
+                    "Configuration file path",
+                ))
                 .value_name("FILE"),
         )
         .arg(
             Arg::new("formats")
                 .long("formats")
-                .help("支持的音频格式列表")
+                .help(text(
+                    "支持的音频格式列表",
+                    "Audio file extensions to analyze",
+                ))
                 .value_name("EXT1,EXT2,...")
                 .value_delimiter(','),
         )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .help(text(
+                    "扫描时排除的文件或目录（glob 模式，可重复指定）",
+                    "Files or directories to exclude while scanning (glob pattern, repeatable)",
+                ))
+                .value_name("PATTERN")
+                .value_delimiter(',')
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("max-depth")
+                .long("max-depth")
+                .help(text(
+                    "扫描目录的最大递归深度（1 表示只扫描顶层文件）",
+                    "Maximum directory recursion depth (1 scans only top-level files)",
+                ))
+                .value_name("NUM")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("follow-symlinks")
+                .long("follow-symlinks")
+                .help(text(
+                    "扫描目录时跟随符号链接",
+                    "Follow symbolic links while scanning",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("parallel-scan")
+                .long("parallel-scan")
+                .help(text(
+                    "并行扫描目录（适合网络存储，文件顺序不固定）",
+                    "Scan directories in parallel (useful on network storage; file order varies)",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .help(text(
+                    "质量评估预设: music（默认）、podcast、mastering",
+                    "Quality profile: music (default), podcast, mastering",
+                ))
+                .value_name("PROFILE")
+                .value_parser(clap::value_parser!(QualityProfile)),
+        )
+        .arg(
+            Arg::new("target-lufs")
+                .long("target-lufs")
+                .help(text(
+                    "响度归一化目标 (LUFS)，如 -14；CSV报告将包含所需增益列",
+                    "Loudness normalization target (LUFS), e.g. -14; adds a required-gain column to the CSV report",
+                ))
+                .value_name("LUFS")
+                .allow_negative_numbers(true)
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("analyze-start")
+                .long("analyze-start")
+                .help(text(
+                    "从指定秒数处开始分析（与 --analyze-duration 一起只分析文件中的一段）",
+                    "Start analyzing at the given second (use with --analyze-duration to analyze a slice)",
+                ))
+                .value_name("SECS")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("analyze-duration")
+                .long("analyze-duration")
+                .help(text(
+                    "每个文件最多分析的秒数，结果将注明只分析了片段",
+                    "Analyze at most this many seconds of each file; results are marked as covering a slice",
+                ))
+                .value_name("SECS")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("cache")
+                .long("cache")
+                .help(text(
+                    "分析缓存文件路径，未变化的文件将直接复用缓存结果",
+                    "Analysis cache file; unchanged files reuse their cached results",
+                ))
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .help(text(
+                    "从输出目录中已有的JSON/NDJSON结果继续，跳过已完整分析且大小未变的文件",
+                    "Continue from the existing JSON/NDJSON results in the output directory, skipping files already fully analyzed whose size is unchanged",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("fingerprint")
+                .long("fingerprint")
+                .help(text(
+                    "计算声学指纹并列出疑似重复的文件（需要FFmpeg支持 chromaprint）",
+                    "Compute acoustic fingerprints and list likely duplicate files (requires FFmpeg with chromaprint)",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("spectral-flatness")
+                .long("spectral-flatness")
+                .help(text(
+                    "计算频谱平坦度与本底噪声（需要FFmpeg 5.0 及以上的 aspectralstats 滤镜）",
+                    "Compute spectral flatness and the noise floor (requires the aspectralstats filter from FFmpeg 5.0+)",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("top-worst")
+                .long("top-worst")
+                .help(text(
+                    "分析完成后列出问题最严重的 N 个文件",
+                    "After analysis, list the N most problematic files",
+                ))
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("worst-by")
+                .long("worst-by")
+                .help(text(
+                    "--top-worst 的排序依据: score（质量分最低，默认）、peak（峰值最高）、highband（18kHz以上RMS最低）",
+                    "Ranking for --top-worst: score (lowest quality score, default), peak (highest peak), highband (lowest RMS above 18 kHz)",
+                ))
+                .value_name("KEY")
+                .value_parser(clap::value_parser!(MetricKey))
+                .requires("top-worst"),
+        )
+        .arg(
+            Arg::new("no-cache")
+                .long("no-cache")
+                .help(text(
+                    "忽略配置中的分析缓存，重新分析所有文件",
+                    "Ignore the configured analysis cache and re-analyze every file",
+                ))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("cache"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help(text(
+                    "输出格式列表: json、ndjson、csv、summary、markdown 或 all（默认 json,csv,summary）",
+                    "Output formats: json, ndjson, csv, summary, markdown or all (default json,csv,summary)",
+                ))
+                .value_name("FMT1,FMT2,...")
+                .value_delimiter(','),
+        )
+        .arg(
+            Arg::new("path-style")
+                .long("path-style")
+                .help(text(
+                    "报告中文件路径的写法: absolute（默认）、relative（相对于输入目录）、filename",
+                    "How file paths are written in reports: absolute (default), relative (to the input directory), filename",
+                ))
+                .value_name("STYLE")
+                .value_parser(clap::value_parser!(PathStyle)),
+        )
+        .arg(
+            Arg::new("loudness-timeseries")
+                .long("loudness-timeseries")
+                .help(text(
+                    "额外导出短期响度随时间变化的CSV（按给定秒数的窗口，仅适用于单个文件）",
+                    "Also export short-term loudness over time as CSV, in windows of the given seconds (single file only)",
+                ))
+                .value_name("SECS")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("compare")
+                .long("compare")
+                .help(text(
+                    "对比两个文件的各项指标（差值为 B - A），然后退出",
+                    "Compare the metrics of two files (deltas are B - A) and exit",
+                ))
+                .value_names(["FILE_A", "FILE_B"])
+                .num_args(2)
+                .conflicts_with_all(["input", "files-from"]),
+        )
+        .arg(
+            Arg::new("self-test")
+                .long("self-test")
+                .help(text(
+                    "用合成的测试音检查分析流程是否正常工作，然后退出",
+                    "Check the analysis pipeline with a synthesized test tone and exit",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .help(text(
+                    "打印版本、FFmpeg、CPU/线程数与Python分析模块等运行环境信息，然后退出",
+                    "Print version, FFmpeg, CPU/thread and Python analyzer information, then exit",
+                ))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["compare", "self-test"]),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .help(text(
+                    "只列出将要分析的文件及总大小，不运行FFmpeg",
+                    "Only list the files that would be analyzed and their total size, without running FFmpeg",
+                ))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["compare", "self-test", "env"]),
+        )
+        .arg(
+            Arg::new("python-report")
+                .long("python-report")
+                .help(text(
+                    "使用Python分析模块生成最终报告（默认使用内置CSV生成器）",
+                    "Generate the final report with the Python analysis module (the built-in CSV writer is the default)",
+                ))
+                .action(clap::ArgAction::SetTrue),
+        )
         .get_matches();
 
+    // 创建配置
+    let mut config = create_config_from_matches(&matches)?;
+
+    // 配置文件指定了语言时，欢迎信息也使用该语言
+    if let Some(locale) = config.locale {
+        messages::set_locale(locale);
+    }
+
+    init_logger(log_level(config.verbose, matches.get_flag("quiet")));
+
     // 显示欢迎信息（除非是静默模式）
     if !matches.get_flag("quiet") {
-        println!("🎵 音频质量分析器 v4.0 (重构优化版)");
-        println!("开始时间: {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+        println!(
+            "{}",
+            text(
+                "🎵 音频质量分析器 v4.0 (重构优化版)",
+                "🎵 Audio Quality Analyzer v4.0"
+            )
+        );
+        println!(
+            "{}: {}",
+            text("开始时间", "Started"),
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
         println!();
     }
 
-    // 创建配置
-    let config = create_config_from_matches(&matches)?;
+    // Python分析模块以JSON数据为输入并自行生成CSV报告
+    let python_report = matches.get_flag("python-report");
+    if python_report {
+        let formats = &mut config.output.formats;
+        formats.retain(|format| *format != OutputFormat::Csv);
+        if !formats.contains(&OutputFormat::Json) {
+            formats.push(OutputFormat::Json);
+        }
+    } else if config.output.output_paths(Path::new("")).is_empty() {
+        return Err(audio_analyzer_ultimate::AnalyzerError::ConfigError(
+            text(
+                "不保留JSON时，输出格式中至少需要JSON以外的一种",
+                "Without keeping the JSON, at least one output format other than JSON is required",
+            )
+            .to_string(),
+        ));
+    }
+    // 不保留JSON时，Python分析模块的输入在分析完成后写入临时目录
+    let json_is_temporary = python_report && !config.output.keep_intermediate_json;
 
     // 创建分析器实例
     let mut analyzer = AudioAnalyzer::new(config)?;
 
-    // 初始化依赖项
-    if !matches.get_flag("quiet") {
-        println!("🔧 正在初始化依赖项...");
+    // 环境报告用于排查依赖问题，初始化失败时同样输出，并附上失败原因
+    if matches.get_flag("env") {
+        let init_error = analyzer.initialize_dependencies().err();
+        let mut report = analyzer.environment_report();
+        if let Some(e) = init_error {
+            report.ffmpeg_error = Some(e.to_string());
+        }
+        println!("{report}");
+        return Ok(());
     }
-    analyzer.initialize_dependencies()?;
 
-    // 获取输入路径
-    let folder_path = if let Some(input_path) = matches.get_one::<String>("input") {
-        let path = PathBuf::from(input_path);
-        if !path.exists() {
-            eprintln!("❌ 错误: 指定的路径不存在: {}", path.display());
+    // 初始化依赖项（只列出文件时不需要FFmpeg）
+    let dry_run = matches.get_flag("dry-run");
+    if !dry_run {
+        if !matches.get_flag("quiet") {
+            println!(
+                "{}",
+                text("🔧 正在初始化依赖项...", "🔧 Initializing dependencies...")
+            );
+        }
+        analyzer.initialize_dependencies()?;
+        if analyzer.config().verbose {
+            match analyzer.ffmpeg_version() {
+                Ok(version) => println!("🎬 {version}"),
+                Err(e) => eprintln!(
+                    "{}",
+                    tr!(
+                        "⚠️  无法获取FFmpeg版本: {e}",
+                        "⚠️  Could not determine the FFmpeg version: {e}"
+                    )
+                ),
+            }
+        }
+    }
+
+    if matches.get_flag("self-test") {
+        analyzer.self_test()?;
+        println!(
+            "{}",
+            text(
+                "✅ 自检通过: FFmpeg 与分析流程工作正常",
+                "✅ Self-test passed: FFmpeg and the analysis pipeline work"
+            )
+        );
+        return Ok(());
+    }
+
+    if let Some(mut files) = matches.get_many::<String>("compare") {
+        let (Some(a), Some(b)) = (files.next(), files.next()) else {
+            unreachable!("clap 保证 --compare 恰好有两个参数");
+        };
+        let diff = analyzer.compare(Path::new(a), Path::new(b))?;
+        print_metrics_diff(&diff);
+        return Ok(());
+    }
+
+    // 获取输入：文件列表，或文件/目录路径
+    let input = if let Some(source) = matches.get_one::<String>("files-from") {
+        let files = read_file_list_arg(source)?;
+        if files.is_empty() {
+            eprintln!(
+                "{}",
+                tr!(
+                    "❌ 错误: 文件列表为空: {source}",
+                    "❌ Error: the file list is empty: {source}"
+                )
+            );
             std::process::exit(1);
         }
-        if !path.is_dir() {
-            eprintln!("❌ 错误: 指定的路径不是目录: {}", path.display());
+        AnalysisInput::FileList(files)
+    } else if let Some(input_path) = matches.get_one::<String>("input") {
+        let path = PathBuf::from(input_path);
+        if !path.exists() {
+            eprintln!(
+                "{}",
+                tr!(
+                    "❌ 错误: 指定的路径不存在: {}",
+                    "❌ Error: path does not exist: {}",
+                    path.display()
+                )
+            );
             std::process::exit(1);
         }
-        path
+        AnalysisInput::Path(path)
     } else {
-        input_utils::get_folder_path_from_user()?
+        AnalysisInput::Path(input_utils::get_folder_path_from_user()?)
     };
+    let single_file = input.single_file();
 
-    if !matches.get_flag("quiet") {
-        println!("📂 正在扫描文件夹: {}", folder_path.display());
+    if dry_run {
+        match &input {
+            AnalysisInput::Path(dir) if dir.is_dir() => {
+                let scan = analyzer.scan_report(dir)?;
+                print_dry_run(&scan.matched, fs_utils::total_file_size(&scan.matched));
+                print_scan_skipped(&scan);
+            }
+            AnalysisInput::Path(path) => {
+                let (files, total_bytes) = analyzer.dry_run(path)?;
+                print_dry_run(&files, total_bytes);
+            }
+            AnalysisInput::FileList(files) => {
+                print_dry_run(files, fs_utils::total_file_size(files));
+            }
+        }
+        return Ok(());
     }
 
-    // 分析目录中的音频文件
-    let timer = Timer::new("总体分析");
-    let results = analyzer.analyze_directory(&folder_path)?;
+    let timer = Timer::new(text("总体分析", "Total analysis"));
+    let quiet = matches.get_flag("quiet");
+    let cancel = install_interrupt_handler()?;
 
-    if results.is_empty() {
-        if !matches.get_flag("quiet") {
-            println!("⚠️  在指定路径下没有找到支持的音频文件。");
+    // 单个文件的报告输出到文件所在目录，目录则输出到该目录，文件列表输出到当前目录
+    let base_dir = match (&input, single_file) {
+        (_, Some(file)) => file
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+        (AnalysisInput::Path(dir), None) => dir.clone(),
+        (AnalysisInput::FileList(_), None) => PathBuf::from("."),
+    };
+
+    // 输出目录：命令行 -o 或配置文件中的 output_dir，否则为输入所在目录
+    let output_config = analyzer.config().output.clone();
+    let output_dir = output_config.output_dir.clone().unwrap_or(base_dir);
+    let outputs = output_config.output_paths(&output_dir);
+    let json_output_path = output_config.path_for(OutputFormat::Json, &output_dir);
+    let csv_output_path = output_config.path_for(OutputFormat::Csv, &output_dir);
+
+    let loudness_window = matches.get_one::<f64>("loudness-timeseries").copied();
+    if loudness_window.is_some() && single_file.is_none() {
+        eprintln!(
+            "{}",
+            text(
+                "⚠️  --loudness-timeseries 仅适用于单个文件，已忽略",
+                "⚠️  --loudness-timeseries only applies to a single file, ignored"
+            )
+        );
+    }
+    let loudness_output_path = loudness_window
+        .and(single_file)
+        .map(|file| output_dir.join(loudness_csv_filename(file)));
+    if let Some(path) = &loudness_output_path {
+        report::check_overwrite(path, analyzer.config())?;
+    }
+
+    match (&input, single_file) {
+        (_, Some(file)) => {
+            if !fs_utils::is_supported_audio_file(file, &analyzer.config().supported_extensions) {
+                eprintln!(
+                    "{}",
+                    tr!(
+                        "❌ 错误: 不支持的音频格式: {}",
+                        "❌ Error: unsupported audio format: {}",
+                        file.display()
+                    )
+                );
+                std::process::exit(1);
+            }
+            if !quiet {
+                println!(
+                    "{}",
+                    tr!(
+                        "🎧 正在分析文件: {}",
+                        "🎧 Analyzing file: {}",
+                        file.display()
+                    )
+                );
+            }
+        }
+        (AnalysisInput::Path(dir), None) if !quiet => {
             println!(
-                "支持的格式: {}",
-                analyzer.config().supported_extensions.join(", ")
+                "{}",
+                tr!(
+                    "📂 正在扫描文件夹: {}",
+                    "📂 Scanning folder: {}",
+                    dir.display()
+                )
             );
         }
-        return Ok(());
+        (AnalysisInput::FileList(files), None) if !quiet => {
+            println!(
+                "{}",
+                tr!(
+                    "📋 正在分析文件列表中的 {} 个文件",
+                    "📋 Analyzing {} files from the list",
+                    files.len()
+                )
+            );
+        }
+        _ => {}
     }
 
-    if !matches.get_flag("quiet") {
-        println!("\n✅ 数据提取完成");
-        timer.print_elapsed();
-        println!("📊 成功分析 {} 个文件", results.len());
+    let mut analysis = match &input {
+        AnalysisInput::Path(path) => analyzer.run_cancellable(path, &output_dir, cancel.clone())?,
+        AnalysisInput::FileList(files) => {
+            analyzer.run_files(files, &output_dir, Some(cancel.clone()))?
+        }
+    };
+
+    if analysis.total() == 0 {
+        eprintln!(
+            "{}",
+            text(
+                "⚠️  在指定目录中未找到支持的音频文件",
+                "⚠️  No supported audio files found"
+            )
+        );
     }
 
-    // 保存中间数据到JSON文件
-    let output_dir = if let Some(output) = matches.get_one::<String>("output") {
-        PathBuf::from(output)
-    } else {
-        folder_path.clone()
-    };
+    // 单个文件分析失败时直接报告错误
+    if single_file.is_some() && !analysis.failed.is_empty() {
+        let (_, error) = analysis.failed.remove(0);
+        return Err(error);
+    }
+    for (path, error) in &analysis.failed {
+        eprintln!(
+            "{}",
+            tr!(
+                "处理失败: {}\n └─> 错误详情: {}",
+                "Failed: {}\n └─> Error: {}",
+                path.display(),
+                error
+            )
+        );
+    }
+    if !quiet && !analysis.resumed.is_empty() {
+        println!(
+            "{}",
+            tr!(
+                "⏩ 沿用已有结果的文件: {} 个",
+                "⏩ Reused existing results for {} files",
+                analysis.resumed.len()
+            )
+        );
+    }
+    for path in &analysis.undersized {
+        eprintln!(
+            "{}",
+            tr!(
+                "已跳过空文件或过小的文件: {}",
+                "Skipped empty or undersized file: {}",
+                path.display()
+            )
+        );
+    }
+    for (path, reason) in &analysis.not_audio {
+        eprintln!(
+            "{}",
+            tr!(
+                "已跳过不是音频的文件: {}（{}）",
+                "Skipped file that is not really audio: {} ({})",
+                path.display(),
+                reason
+            )
+        );
+    }
 
-    let json_output_path = output_dir.join("analysis_data.json");
-    if !matches.get_flag("quiet") {
-        println!("💾 正在保存分析数据到: {}", json_output_path.display());
+    if let (Some(window), Some(file), Some(path)) =
+        (loudness_window, single_file, &loudness_output_path)
+    {
+        let series = analyzer.extract_loudness_timeseries(file, window)?;
+        report::write_loudness_csv_file(path, &series, analyzer.config())?;
     }
 
-    let json_content = serde_json::to_string_pretty(&results)?;
-    fs::write(&json_output_path, json_content)?;
+    let interrupted = cancel.load(Ordering::SeqCst);
+    if interrupted {
+        eprintln!(
+            "{}",
+            tr!(
+                "⚠️  分析已中断，已写出完成的 {} 个文件的结果",
+                "⚠️  Analysis interrupted, wrote results for the {} completed files",
+                analysis.succeeded.len()
+            )
+        );
+    }
 
-    if !matches.get_flag("quiet") {
-        println!("✅ 分析数据保存成功");
+    if !quiet {
+        println!("\n{}", text("✅ 数据提取完成", "✅ Extraction finished"));
+        timer.print_elapsed();
+        println!(
+            "{}",
+            tr!(
+                "📊 成功分析 {} 个文件",
+                "📊 Analyzed {} files",
+                analysis.succeeded.len()
+            )
+        );
+        if analyzer.config().verbose {
+            let summary = AnalysisSummary::from_report(&analysis, analyzer.profile_matcher());
+            if let (Some(files), Some(megabytes)) =
+                (summary.files_per_second, summary.megabytes_per_second)
+            {
+                println!(
+                    "{}",
+                    tr!(
+                        "⚡ 吞吐量: {files:.2} 个文件/秒，{megabytes:.2} MB/秒",
+                        "⚡ Throughput: {files:.2} files/s, {megabytes:.2} MB/s"
+                    )
+                );
+            }
+        }
+        if analyzer.config().fingerprint {
+            let groups = AnalysisSummary::duplicate_groups(&analysis.succeeded);
+            println!(
+                "{}",
+                tr!(
+                    "🔁 疑似重复的文件: {} 组",
+                    "🔁 Likely duplicates: {} groups",
+                    groups.len()
+                )
+            );
+            for (number, group) in groups.iter().enumerate() {
+                println!("  {}.", number + 1);
+                for path in group {
+                    println!("     {}", path.display());
+                }
+            }
+        }
     }
 
-    // 调用Python分析模块生成最终报告
-    let csv_output_path = output_dir.join("audio_quality_report.csv");
-    call_python_analyzer(
-        &json_output_path,
-        &csv_output_path,
-        matches.get_flag("quiet"),
-    )?;
+    // 显式要求时即使在静默模式下也打印
+    if let Some(&count) = matches.get_one::<usize>("top-worst") {
+        let by = matches
+            .get_one::<MetricKey>("worst-by")
+            .copied()
+            .unwrap_or_default();
+        let profiles = analyzer.profile_matcher();
+        let worst =
+            AnalysisSummary::worst_with_thresholds(&analysis.succeeded, count, by, profiles);
+        print_worst(&worst, by, profiles);
+    }
 
-    if !matches.get_flag("quiet") {
-        println!("\n🎉 分析流程完成");
-        println!("📄 最终报告: {}", csv_output_path.display());
-        println!("📄 原始数据: {}", json_output_path.display());
-        println!("⏰ 结束时间: {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+    // 显式指定时调用Python分析模块，以写出的JSON数据为输入生成最终报告
+    if python_report {
+        // 临时目录在生成报告后随之删除
+        let intermediate_json_dir = json_is_temporary.then(tempfile::tempdir).transpose()?;
+        let json_path = match &intermediate_json_dir {
+            Some(dir) => {
+                let path = dir.path().join(&output_config.json_filename);
+                write_intermediate_json(&path, &analysis, &input, analyzer.config())?;
+                path
+            }
+            None => json_output_path,
+        };
+        let python_analyzer = resolve_python_analyzer(analyzer.get_analyzer_path());
+        call_python_analyzer(
+            python_analyzer,
+            &json_path,
+            &csv_output_path,
+            !json_is_temporary,
+            quiet,
+        )?;
+    }
+
+    if !quiet {
+        println!("\n{}", text("🎉 分析流程完成", "🎉 Analysis complete"));
+        for (format, path) in &outputs {
+            if json_is_temporary && *format == OutputFormat::Json {
+                continue;
+            }
+            let label = match format {
+                OutputFormat::Csv => text("最终报告", "Final report"),
+                OutputFormat::Json => text("原始数据", "Raw data"),
+                OutputFormat::Ndjson => text("NDJSON数据", "NDJSON data"),
+                OutputFormat::Summary => text("汇总统计", "Summary"),
+                OutputFormat::Markdown => text("可读报告", "Markdown report"),
+            };
+            println!("📄 {label}: {}", path.display());
+        }
+        if python_report {
+            println!(
+                "📄 {}: {}",
+                text("最终报告", "Final report"),
+                csv_output_path.display()
+            );
+        }
+        if let Some(path) = &loudness_output_path {
+            println!(
+                "📄 {}: {}",
+                text("响度时间序列", "Loudness over time"),
+                path.display()
+            );
+        }
+        println!(
+            "⏰ {}: {}",
+            text("结束时间", "Finished"),
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+
+    // 与被 SIGINT 终止的进程一致，便于脚本区分完整结果与部分结果
+    if interrupted {
+        std::process::exit(130);
     }
 
     Ok(())
 }
 
+/// 要分析的输入
+enum AnalysisInput {
+    /// 单个文件或要扫描的目录
+    Path(PathBuf),
+    /// `--files-from` 给出的文件列表
+    FileList(Vec<PathBuf>),
+}
+
+impl AnalysisInput {
+    /// 输入为单个文件时返回该文件
+    fn single_file(&self) -> Option<&Path> {
+        match self {
+            Self::Path(path) if path.is_file() => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// 读取 `--files-from` 指定的文件列表，`-` 表示标准输入
+fn read_file_list_arg(source: &str) -> Result<Vec<PathBuf>> {
+    if source == "-" {
+        fs_utils::read_file_list(std::io::stdin().lock())
+    } else {
+        fs_utils::read_file_list(BufReader::new(File::open(source)?))
+    }
+}
+
+/// 安装 Ctrl-C 处理器，返回中断标志
+///
+/// 第一次 Ctrl-C 只置位标志：尚未开始的文件被跳过，已完成的结果照常写出；
+/// 再次按下 Ctrl-C 时立即退出。
+fn install_interrupt_handler() -> Result<Arc<AtomicBool>> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = cancel.clone();
+    ctrlc::set_handler(move || {
+        if flag.swap(true, Ordering::SeqCst) {
+            // FFmpeg子进程位于独立的进程组，没有随 Ctrl-C 结束
+            process_utils::terminate_children();
+            std::process::exit(130);
+        }
+        eprintln!(
+            "\n{}",
+            text(
+                "⚠️  收到中断信号，完成当前文件后将写出已有结果（再次按 Ctrl-C 立即退出）",
+                "⚠️  Interrupted, writing the finished results after the current files (press Ctrl-C again to exit immediately)"
+            )
+        );
+    })
+    .map_err(|e| {
+        audio_analyzer_ultimate::AnalyzerError::Other(tr!(
+            "无法安装 Ctrl-C 处理器: {e}",
+            "Failed to install the Ctrl-C handler: {e}"
+        ))
+    })?;
+    Ok(cancel)
+}
+
+/// 按 `--verbose`/`--quiet` 确定本程序的日志级别
+///
+/// 静默模式仍保留警告（如处理失败的文件），默认显示 `info` 级别的逐文件进度。
+fn log_level(verbose: bool, quiet: bool) -> LevelFilter {
+    if quiet {
+        LevelFilter::Warn
+    } else if verbose {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// 初始化 `env_logger`，日志写到标准错误
+///
+/// 只输出消息本身，不带时间和级别前缀；环境变量 `RUST_LOG` 可覆盖这里的级别。
+fn init_logger(level: LevelFilter) {
+    env_logger::Builder::new()
+        .filter_level(LevelFilter::Warn)
+        .filter_module("audio_analyzer_ultimate", level)
+        .parse_default_env()
+        .format(|buf, record| writeln!(buf, "{}", record.args()))
+        .init();
+}
+
 /// 从命令行参数创建配置
 fn create_config_from_matches(matches: &clap::ArgMatches) -> Result<AnalyzerConfig> {
     let mut config = AnalyzerConfig::default();
@@ -193,10 +932,86 @@ fn create_config_from_matches(matches: &clap::ArgMatches) -> Result<AnalyzerConf
         config.num_threads = Some(threads);
     }
 
+    if let Some(&limit) = matches.get_one::<usize>("max-ffmpeg") {
+        config.max_concurrent_ffmpeg = Some(limit);
+    }
+
+    if let Some(&index) = matches.get_one::<usize>("stream") {
+        config.stream_index = Some(index);
+    }
+
+    if matches.get_flag("low-priority") {
+        config.low_priority = true;
+    }
+
     if let Some(formats) = matches.get_many::<String>("formats") {
         config.supported_extensions = formats.cloned().collect();
     }
 
+    if let Some(patterns) = matches.get_many::<String>("exclude") {
+        config.exclude_patterns.extend(patterns.cloned());
+    }
+
+    if let Some(&max_depth) = matches.get_one::<usize>("max-depth") {
+        config.max_depth = Some(max_depth);
+    }
+
+    if matches.get_flag("follow-symlinks") {
+        config.follow_symlinks = true;
+    }
+
+    if matches.get_flag("parallel-scan") {
+        config.parallel_scan = true;
+    }
+
+    if let Some(&profile) = matches.get_one::<QualityProfile>("profile") {
+        config.set_profile(profile);
+    }
+
+    if let Some(formats) = matches.get_many::<String>("format") {
+        config.output.formats = OutputFormat::parse_list(formats)?;
+    }
+
+    if let Some(&style) = matches.get_one::<PathStyle>("path-style") {
+        config.output.path_style = style;
+    }
+
+    if let Some(&target) = matches.get_one::<f64>("target-lufs") {
+        config.target_lufs = Some(target);
+    }
+
+    if let Some(&start) = matches.get_one::<f64>("analyze-start") {
+        config.ffmpeg.analyze_start_secs = Some(start);
+    }
+
+    if let Some(&duration) = matches.get_one::<f64>("analyze-duration") {
+        config.ffmpeg.analyze_duration_secs = Some(duration);
+    }
+
+    if let Some(output) = matches.get_one::<String>("output") {
+        config.output.output_dir = Some(PathBuf::from(output));
+    }
+
+    if let Some(cache) = matches.get_one::<String>("cache") {
+        config.cache_path = Some(PathBuf::from(cache));
+    }
+
+    if matches.get_flag("no-cache") {
+        config.cache_path = None;
+    }
+
+    if matches.get_flag("resume") {
+        config.resume = true;
+    }
+
+    if matches.get_flag("fingerprint") {
+        config.fingerprint = true;
+    }
+
+    if matches.get_flag("spectral-flatness") {
+        config.spectral_flatness = true;
+    }
+
     // 从环境变量读取配置（优先级最低）
     if !matches.get_flag("verbose") && !matches.get_flag("quiet") {
         if let Ok(verbose) = std::env::var("AUDIO_ANALYZER_VERBOSE") {
@@ -220,41 +1035,256 @@ fn create_config_from_matches(matches: &clap::ArgMatches) -> Result<AnalyzerConf
     Ok(config)
 }
 
-/// 调用Python分析器生成最终报告
-fn call_python_analyzer(json_path: &PathBuf, csv_path: &PathBuf, quiet: bool) -> Result<()> {
-    if !quiet {
-        println!("\n🐍 正在调用Python分析模块生成最终报告...");
+/// 以表格形式打印两个文件的指标对比
+fn print_metrics_diff(diff: &MetricsDiff) {
+    let format_value =
+        |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"));
+
+    println!("A: {}", diff.a.file_path);
+    println!("B: {}", diff.b.file_path);
+    println!();
+    // 中文标签每个字占两列，按16个字符对齐即与18列的英文指标名对齐
+    match messages::locale() {
+        Locale::Zh => println!("{:<16} {:>10} {:>10} {:>10}", "指标", "A", "B", "B - A"),
+        Locale::En => println!("{:<18} {:>10} {:>10} {:>10}", "Metric", "A", "B", "B - A"),
+    }
+    for delta in &diff.deltas {
+        let change = delta
+            .delta()
+            .map_or_else(|| "-".to_string(), |d| format!("{d:+.2}"));
+        println!(
+            "{:<18} {:>10} {:>10} {:>10}",
+            delta.name,
+            format_value(delta.a),
+            format_value(delta.b),
+            change
+        );
+    }
+    let (score_label, score_width) = match messages::locale() {
+        Locale::Zh => ("质量分", 15),
+        Locale::En => ("Quality score", 18),
+    };
+    println!(
+        "{:<score_width$} {:>10} {:>10} {:>10}",
+        score_label,
+        diff.score_a,
+        diff.score_b,
+        format!("{:+}", diff.score_b - diff.score_a)
+    );
+    println!();
+    println!("{}: {}", text("结论", "Verdict"), diff.summary());
+}
+
+/// 打印 `--top-worst` 的结果：排名、质量分、综合判定、峰值与高频RMS
+fn print_worst(worst: &[&AudioMetrics], by: MetricKey, profiles: &ProfileMatcher) {
+    let format_value =
+        |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"));
+
+    println!(
+        "\n{}",
+        tr!(
+            "🚩 问题最严重的 {} 个文件（按 {}）:",
+            "🚩 The {} most problematic files (by {}):",
+            worst.len(),
+            by
+        )
+    );
+    // 中文标签每个字占两列，按显示宽度对齐
+    match messages::locale() {
+        Locale::Zh => println!(
+            "{:>3}  {:>3}  {:<8}  {:>6}  {:>6}  文件",
+            "#", "质量分", "判定", "峰值", "高频RMS"
+        ),
+        Locale::En => println!(
+            "{:>3}  {:>6}  {:<10}  {:>8}  {:>8}  File",
+            "#", "Score", "Verdict", "Peak dB", "HF RMS"
+        ),
+    }
+    for (rank, metrics) in worst.iter().enumerate() {
+        let thresholds = &*profiles.thresholds_for(metrics.path());
+        println!(
+            "{:>3}  {:>6}  {:<10}  {:>8}  {:>8}  {}",
+            rank + 1,
+            metrics.quality_score(thresholds),
+            metrics.verdict(thresholds).as_str(),
+            format_value(metrics.peak_amplitude_db),
+            format_value(metrics.rms_db_above(18000)),
+            metrics.file_path
+        );
+    }
+}
+
+/// 打印 `--dry-run` 的结果：文件数、总大小与文件列表
+fn print_dry_run(files: &[PathBuf], total_bytes: u64) {
+    println!(
+        "{}",
+        tr!(
+            "📋 将分析 {} 个文件，共 {}",
+            "📋 Would analyze {} files, {} in total",
+            files.len(),
+            string_utils::format_file_size(total_bytes)
+        )
+    );
+    for file in files {
+        println!("{}", file.display());
     }
+}
 
-    // 尝试使用系统中的Python分析器
-    let python_script_path = std::env::current_dir()?
+/// 打印扫描时跳过的文件：不受支持的扩展名数量与无法读取的条目
+fn print_scan_skipped(scan: &fs_utils::ScanReport) {
+    if scan.skipped_unsupported > 0 {
+        println!(
+            "{}",
+            tr!(
+                "⏭️  扩展名不受支持而跳过: {} 个文件",
+                "⏭️  Skipped (unsupported extension): {} files",
+                scan.skipped_unsupported
+            )
+        );
+    }
+    for (path, error) in &scan.skipped_errors {
+        eprintln!(
+            "{}",
+            tr!(
+                "⚠️  无法读取，已跳过: {} ({})",
+                "⚠️  Unreadable, skipped: {} ({})",
+                path.display(),
+                error
+            )
+        );
+    }
+}
+
+/// 响度时间序列CSV的文件名，如 `track.flac` 对应 `track_loudness.csv`
+fn loudness_csv_filename(input_path: &Path) -> String {
+    let stem = input_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    format!("{stem}_loudness.csv")
+}
+
+/// `$PATH` 中Python分析器可执行文件的名称（与嵌入的二进制文件同名）
+const PYTHON_ANALYZER_NAME: &str = "audio_analyzer";
+
+/// 为Python分析模块写出JSON数据（不保留JSON时流程本身不写出），路径按 `output.path_style` 转换
+fn write_intermediate_json(
+    path: &Path,
+    analysis: &AnalysisReport,
+    input: &AnalysisInput,
+    config: &AnalyzerConfig,
+) -> Result<()> {
+    // 与分析流程使用相同的基准目录
+    let root = match input {
+        AnalysisInput::Path(path) if path.is_file() => path.parent(),
+        AnalysisInput::Path(path) => Some(path.as_path()),
+        AnalysisInput::FileList(_) => None,
+    };
+    let styler = PathStyler::new(config.output.path_style, root);
+    let metrics: Vec<AudioMetrics> = analysis
+        .succeeded
+        .iter()
+        .map(|metrics| AudioMetrics {
+            file_path: styler.apply(metrics.path()),
+            ..metrics.clone()
+        })
+        .collect();
+    report::write_json_file(path, &metrics, config)
+}
+
+/// 查找Python分析模块，返回调用它的命令（不含参数）
+///
+/// 依次尝试：解压出的嵌入分析器、`$PATH` 中的 `audio_analyzer`、
+/// 开发目录下的 `src/bin/audio_analyzer.py`（通过 `python3` 运行）。
+fn resolve_python_analyzer(bundled: Option<&Path>) -> Option<Command> {
+    if let Some(path) = bundled.filter(|path| path.is_file()) {
+        return Some(Command::new(path));
+    }
+
+    if let Some(path) = process_utils::find_executable(PYTHON_ANALYZER_NAME) {
+        return Some(Command::new(path));
+    }
+
+    let script = std::env::current_dir()
+        .ok()?
         .join("src")
         .join("bin")
         .join("audio_analyzer.py");
-
-    if python_script_path.exists() {
+    script.is_file().then(|| {
         let mut command = Command::new("python3");
+        command.arg(script);
         command
-            .arg(&python_script_path)
-            .arg(json_path)
-            .arg("-o")
-            .arg(csv_path);
+    })
+}
+
+/// 调用Python分析器生成最终报告
+///
+/// `keep_json` 为 `false` 时JSON位于稍后删除的临时目录，找不到Python分析模块时不提示其路径。
+fn call_python_analyzer(
+    python_analyzer: Option<Command>,
+    json_path: &Path,
+    csv_path: &Path,
+    keep_json: bool,
+    quiet: bool,
+) -> Result<()> {
+    if !quiet {
+        println!(
+            "\n{}",
+            text(
+                "🐍 正在调用Python分析模块生成最终报告...",
+                "🐍 Running the Python analysis module to build the final report..."
+            )
+        );
+    }
+
+    if let Some(mut command) = python_analyzer {
+        command.arg(json_path).arg("-o").arg(csv_path);
 
         let status = command.status()?;
 
         if !status.success() {
-            return Err(audio_analyzer_ultimate::AnalyzerError::Other(format!(
-                "Python分析模块执行失败，退出代码: {:?}",
-                status.code()
-            )));
+            return Err(audio_analyzer_ultimate::AnalyzerError::Other(
+                match status.code() {
+                    Some(code) => tr!(
+                        "Python分析模块执行失败，退出代码: {code}",
+                        "The Python analysis module failed with exit code {code}"
+                    ),
+                    None => text(
+                        "Python分析模块被信号终止",
+                        "The Python analysis module was killed by a signal",
+                    )
+                    .to_string(),
+                },
+            ));
         }
 
         if !quiet {
-            println!("✅ Python分析模块执行成功");
+            println!(
+                "{}",
+                text(
+                    "✅ Python分析模块执行成功",
+                    "✅ Python analysis module finished"
+                )
+            );
         }
     } else if !quiet {
-        println!("⚠️  警告: 未找到Python分析模块，跳过最终报告生成");
-        println!("📄 中间数据已保存到: {}", json_path.display());
+        println!(
+            "{}",
+            text(
+                "⚠️  警告: 未找到Python分析模块，跳过最终报告生成",
+                "⚠️  Warning: Python analysis module not found, skipping the final report"
+            )
+        );
+        if keep_json {
+            println!(
+                "{}",
+                tr!(
+                    "📄 中间数据已保存到: {}",
+                    "📄 Intermediate data saved to: {}",
+                    json_path.display()
+                )
+            );
+        }
     }
 
     Ok(())
@@ -271,6 +1301,7 @@ fn show_help() {
     println!("环境变量:");
     println!("  AUDIO_ANALYZER_VERBOSE=true    启用详细输出");
     println!("  AUDIO_ANALYZER_THREADS=4       设置并行线程数");
+    println!("  RUST_LOG=debug                 覆盖日志级别");
     println!();
     println!("支持的音频格式:");
     println!("  WAV, MP3, FLAC, AAC, OGG, OPUS, WMA, AIFF, ALAC, M4A");
@@ -304,18 +1335,170 @@ mod tests {
                     .long("threads")
                     .value_parser(clap::value_parser!(usize)),
             )
+            .arg(
+                clap::Arg::new("max-ffmpeg")
+                    .long("max-ffmpeg")
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(
+                clap::Arg::new("stream")
+                    .long("stream")
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(
+                clap::Arg::new("low-priority")
+                    .long("low-priority")
+                    .action(clap::ArgAction::SetTrue),
+            )
             .arg(clap::Arg::new("config").long("config").value_name("FILE"))
+            .arg(clap::Arg::new("output").long("output").value_name("DIR"))
+            .arg(
+                clap::Arg::new("profile")
+                    .long("profile")
+                    .value_parser(clap::value_parser!(QualityProfile)),
+            )
+            .arg(
+                clap::Arg::new("target-lufs")
+                    .long("target-lufs")
+                    .allow_negative_numbers(true)
+                    .value_parser(clap::value_parser!(f64)),
+            )
+            .arg(
+                clap::Arg::new("analyze-start")
+                    .long("analyze-start")
+                    .value_parser(clap::value_parser!(f64)),
+            )
+            .arg(
+                clap::Arg::new("analyze-duration")
+                    .long("analyze-duration")
+                    .value_parser(clap::value_parser!(f64)),
+            )
+            .arg(clap::Arg::new("cache").long("cache").value_name("FILE"))
+            .arg(
+                clap::Arg::new("no-cache")
+                    .long("no-cache")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("resume")
+                    .long("resume")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("fingerprint")
+                    .long("fingerprint")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("spectral-flatness")
+                    .long("spectral-flatness")
+                    .action(clap::ArgAction::SetTrue),
+            )
             .arg(
                 clap::Arg::new("formats")
                     .long("formats")
                     .value_delimiter(','),
             )
-            .try_get_matches_from(vec!["test", "--verbose"])
+            .arg(
+                clap::Arg::new("exclude")
+                    .long("exclude")
+                    .value_delimiter(',')
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(clap::Arg::new("format").long("format").value_delimiter(','))
+            .arg(
+                clap::Arg::new("path-style")
+                    .long("path-style")
+                    .value_parser(clap::value_parser!(PathStyle)),
+            )
+            .arg(
+                clap::Arg::new("max-depth")
+                    .long("max-depth")
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(
+                clap::Arg::new("follow-symlinks")
+                    .long("follow-symlinks")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                clap::Arg::new("parallel-scan")
+                    .long("parallel-scan")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .try_get_matches_from(vec![
+                "test",
+                "--verbose",
+                "--output",
+                "/tmp/reports",
+                "--profile",
+                "podcast",
+                "--exclude",
+                "@eaDir,lossy/*",
+                "--follow-symlinks",
+                "--parallel-scan",
+                "--format",
+                "ndjson,csv",
+                "--path-style",
+                "relative",
+                "--max-ffmpeg",
+                "6",
+                "--low-priority",
+                "--stream",
+                "1",
+                "--resume",
+                "--fingerprint",
+                "--spectral-flatness",
+                "--analyze-duration",
+                "90",
+                "--target-lufs",
+                "-14",
+            ])
             .unwrap();
 
         let config = create_config_from_matches(&matches).unwrap();
         assert!(config.verbose);
+        assert_eq!(
+            config.output.output_dir,
+            Some(PathBuf::from("/tmp/reports"))
+        );
+        assert_eq!(config.profile, QualityProfile::Podcast);
+        assert_eq!(config.exclude_patterns, vec!["@eaDir", "lossy/*"]);
+        assert!(config.follow_symlinks);
+        assert!(config.parallel_scan);
+        assert_eq!(
+            config.output.formats,
+            vec![OutputFormat::Ndjson, OutputFormat::Csv]
+        );
+        assert_eq!(config.output.path_style, PathStyle::RelativeToInput);
+        assert_eq!(config.max_depth, None);
+        assert_eq!(config.max_concurrent_ffmpeg, Some(6));
+        assert!(config.low_priority);
+        assert_eq!(config.stream_index, Some(1));
+        assert!(config.resume);
+        assert!(config.fingerprint);
+        assert!(config.spectral_flatness);
+        assert_eq!(config.ffmpeg.analyze_start_secs, None);
+        assert_eq!(config.ffmpeg.analyze_duration_secs, Some(90.0));
+        assert_eq!(config.target_lufs, Some(-14.0));
         // 验证默认配置
         assert!(config.show_progress); // 默认应该显示进度
     }
+
+    #[test]
+    fn test_log_level() {
+        assert_eq!(log_level(false, false), LevelFilter::Info);
+        assert_eq!(log_level(true, false), LevelFilter::Debug);
+        // 静默模式优先于详细模式
+        assert_eq!(log_level(true, true), LevelFilter::Warn);
+        assert_eq!(log_level(false, true), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn test_loudness_csv_filename() {
+        assert_eq!(
+            loudness_csv_filename(Path::new("/music/mix.flac")),
+            "mix_loudness.csv"
+        );
+    }
 }
@@ -0,0 +1,216 @@
+//! # 分析缓存模块
+//!
+//! 以 `(规范化路径, 修改时间, 文件大小)` 为键，将分析结果持久化到JSON文件，
+//! 重复分析未变化的文件时直接复用结果，避免再次调用FFmpeg。
+
+use crate::error::Result;
+use crate::types::AudioMetrics;
+use crate::utils::fs_utils;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+/// 缓存文件格式版本，格式变化时递增以丢弃旧缓存
+const CACHE_FORMAT_VERSION: u32 = 5;
+
+/// 单个文件的缓存条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// 修改时间（自 UNIX 纪元的秒数）
+    mtime_secs: u64,
+    /// 修改时间的纳秒部分
+    mtime_nanos: u32,
+    /// 文件大小（字节）
+    size: u64,
+    /// 缓存的分析结果
+    metrics: AudioMetrics,
+}
+
+/// 缓存文件的磁盘格式
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    /// 生成缓存时的分析设置，设置不同则缓存整体失效
+    fingerprint: String,
+    entries: HashMap<String, CacheEntry>,
+}
+
+/// 文件的缓存键信息
+struct FileStamp {
+    key: String,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    /// 读取文件的规范化路径、修改时间与大小
+    fn read(path: &Path) -> Option<Self> {
+        let canonical = fs::canonicalize(path).ok()?;
+        let metadata = fs::metadata(&canonical).ok()?;
+        let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+
+        Some(Self {
+            key: canonical.to_string_lossy().to_string(),
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+            size: metadata.len(),
+        })
+    }
+
+    /// 缓存条目是否与当前文件状态一致
+    fn matches(&self, entry: &CacheEntry) -> bool {
+        entry.mtime_secs == self.mtime_secs
+            && entry.mtime_nanos == self.mtime_nanos
+            && entry.size == self.size
+    }
+}
+
+/// 持久化的分析结果缓存
+///
+/// 可在分析线程间共享；修改只在内存中进行，调用 [`AnalysisCache::save`] 后写回磁盘。
+pub struct AnalysisCache {
+    /// 缓存文件路径
+    path: PathBuf,
+    /// 分析设置指纹
+    fingerprint: String,
+    /// 缓存条目（键为规范化路径）
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    /// 是否有未保存的修改
+    dirty: AtomicBool,
+}
+
+impl AnalysisCache {
+    /// 从磁盘加载缓存
+    ///
+    /// 缓存文件不存在、无法解析、版本或 `fingerprint` 不一致时返回空缓存。
+    pub fn load<P: AsRef<Path>>(path: P, fingerprint: &str) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let entries = File::open(&path)
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, CacheFile>(BufReader::new(file)).ok())
+            .filter(|cache| {
+                cache.version == CACHE_FORMAT_VERSION && cache.fingerprint == fingerprint
+            })
+            .map(|cache| cache.entries)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            fingerprint: fingerprint.to_string(),
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// 查找文件的缓存结果，文件修改时间或大小变化时视为未命中
+    pub fn get(&self, file_path: &Path) -> Option<AudioMetrics> {
+        let stamp = FileStamp::read(file_path)?;
+        let entries = self.entries.lock().ok()?;
+
+        let mut metrics = entries
+            .get(&stamp.key)
+            .filter(|entry| stamp.matches(entry))
+            .map(|entry| entry.metrics.clone())?;
+        // 原始路径不参与序列化，使用本次查询的路径
+        metrics.source_path = Some(file_path.to_path_buf());
+        Some(metrics)
+    }
+
+    /// 记录文件的分析结果
+    pub fn insert(&self, file_path: &Path, metrics: &AudioMetrics) {
+        let Some(stamp) = FileStamp::read(file_path) else {
+            return;
+        };
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                stamp.key,
+                CacheEntry {
+                    mtime_secs: stamp.mtime_secs,
+                    mtime_nanos: stamp.mtime_nanos,
+                    size: stamp.size,
+                    metrics: metrics.clone(),
+                },
+            );
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// 缓存条目数量
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 将缓存写回磁盘（没有修改时跳过）
+    pub fn save(&self) -> Result<()> {
+        if !self.dirty.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let entries = self
+            .entries
+            .lock()
+            .map(|entries| entries.clone())
+            .unwrap_or_default();
+        let cache = CacheFile {
+            version: CACHE_FORMAT_VERSION,
+            fingerprint: self.fingerprint.clone(),
+            entries,
+        };
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        // 先写入临时文件再替换，保存中途被中断时旧缓存保持完整
+        fs_utils::write_atomic(&self.path, &serde_json::to_vec(&cache)?)?;
+
+        self.dirty.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_roundtrip_and_invalidation() {
+        let dir = TempDir::new().unwrap();
+        let audio = dir.path().join("a.flac");
+        let cache_path = dir.path().join("cache.json");
+        fs::write(&audio, b"audio").unwrap();
+
+        let mut metrics = AudioMetrics::new(audio.to_string_lossy().to_string(), 5);
+        metrics.lra = Some(7.5);
+
+        let cache = AnalysisCache::load(&cache_path, "v1");
+        assert!(cache.get(&audio).is_none());
+        cache.insert(&audio, &metrics);
+        cache.save().unwrap();
+
+        let reloaded = AnalysisCache::load(&cache_path, "v1");
+        assert_eq!(reloaded.get(&audio).unwrap().lra, Some(7.5));
+
+        // 设置变化后缓存整体失效
+        assert!(AnalysisCache::load(&cache_path, "v2").is_empty());
+
+        // 文件内容（大小）变化后条目失效
+        fs::write(&audio, b"changed audio").unwrap();
+        assert!(reloaded.get(&audio).is_none());
+    }
+}
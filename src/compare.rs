@@ -0,0 +1,189 @@
+//! # 文件对比模块
+//!
+//! 对比两个文件的分析结果，给出各项指标的差值，
+//! 用于 A/B 测试不同的编码设置或母带版本。
+
+use crate::messages::{self, Locale};
+use crate::types::{highpass_field_name, AudioMetrics, QualityThresholds};
+use std::cmp::Ordering;
+
+/// 单项指标的对比
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDelta {
+    /// 指标名称（与JSON字段名一致，如 `lra`、`rmsDbAbove18k`）
+    pub name: String,
+    /// 文件 A 的数值
+    pub a: Option<f64>,
+    /// 文件 B 的数值
+    pub b: Option<f64>,
+}
+
+impl MetricDelta {
+    /// 差值 B - A，任一方缺失时为 `None`
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.b? - self.a?)
+    }
+}
+
+/// 两个文件分析结果的对比
+#[derive(Debug, Clone)]
+pub struct MetricsDiff {
+    /// 文件 A 的分析结果
+    pub a: AudioMetrics,
+    /// 文件 B 的分析结果
+    pub b: AudioMetrics,
+    /// 文件 A 的质量分数
+    pub score_a: i32,
+    /// 文件 B 的质量分数
+    pub score_b: i32,
+    /// 各项指标的对比：LRA、峰值、整体RMS，以及两个文件中出现过的所有高通频段
+    pub deltas: Vec<MetricDelta>,
+}
+
+impl MetricsDiff {
+    /// 根据两个文件的分析结果生成对比
+    pub fn new(a: AudioMetrics, b: AudioMetrics, thresholds: &QualityThresholds) -> Self {
+        let mut deltas = vec![
+            MetricDelta {
+                name: "lra".to_string(),
+                a: a.lra,
+                b: b.lra,
+            },
+            MetricDelta {
+                name: "peakAmplitudeDb".to_string(),
+                a: a.peak_amplitude_db,
+                b: b.peak_amplitude_db,
+            },
+            MetricDelta {
+                name: "overallRmsDb".to_string(),
+                a: a.overall_rms_db,
+                b: b.overall_rms_db,
+            },
+        ];
+
+        let mut frequencies: Vec<u32> = a
+            .highpass_rms
+            .keys()
+            .chain(b.highpass_rms.keys())
+            .copied()
+            .collect();
+        frequencies.sort_unstable();
+        frequencies.dedup();
+        deltas.extend(frequencies.into_iter().map(|frequency| MetricDelta {
+            name: highpass_field_name(frequency),
+            a: a.rms_db_above(frequency),
+            b: b.rms_db_above(frequency),
+        }));
+
+        Self {
+            score_a: a.quality_score(thresholds),
+            score_b: b.quality_score(thresholds),
+            a,
+            b,
+            deltas,
+        }
+    }
+
+    /// 按名称查找指标对比
+    pub fn get(&self, name: &str) -> Option<&MetricDelta> {
+        self.deltas.iter().find(|delta| delta.name == name)
+    }
+
+    /// 哪个文件更"干净"：`Greater` 表示 B 更好，`Less` 表示 A 更好
+    ///
+    /// 先比较质量分数；分数相同时比较 18kHz 以上的能量，高频保留更多的一方更好。
+    pub fn cleaner(&self) -> Ordering {
+        self.score_b.cmp(&self.score_a).then_with(|| {
+            self.get(&highpass_field_name(18000))
+                .and_then(MetricDelta::delta)
+                .and_then(|delta| delta.partial_cmp(&0.0))
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+
+    /// 人类可读的对比结论（使用当前界面语言）
+    pub fn summary(&self) -> String {
+        self.localized_summary(messages::locale())
+    }
+
+    /// 指定语言的对比结论
+    pub fn localized_summary(&self, locale: Locale) -> String {
+        let (a, b) = (self.a.filename(), self.b.filename());
+        match locale {
+            Locale::Zh => {
+                let scores = format!("质量分 A {} / B {}", self.score_a, self.score_b);
+                match self.cleaner() {
+                    Ordering::Greater => format!("B ({b}) 更干净，{scores}"),
+                    Ordering::Less => format!("A ({a}) 更干净，{scores}"),
+                    Ordering::Equal => format!("两个文件没有明显差异，{scores}"),
+                }
+            }
+            Locale::En => {
+                let scores = format!("quality score A {} / B {}", self.score_a, self.score_b);
+                match self.cleaner() {
+                    Ordering::Greater => format!("B ({b}) is cleaner, {scores}"),
+                    Ordering::Less => format!("A ({a}) is cleaner, {scores}"),
+                    Ordering::Equal => format!("No clear difference between the files, {scores}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(path: &str, lra: f64, peak: f64, rms_18k: f64) -> AudioMetrics {
+        let mut metrics = AudioMetrics::new(path.to_string(), 1024);
+        metrics.lra = Some(lra);
+        metrics.peak_amplitude_db = Some(peak);
+        metrics.highpass_rms.insert(18000, rms_18k);
+        metrics
+    }
+
+    #[test]
+    fn test_metrics_diff() {
+        let thresholds = QualityThresholds::default();
+        let mut a = metrics("/music/v0.mp3", 10.0, -6.5, -82.0);
+        a.highpass_rms.insert(20000, -95.0);
+        let b = metrics("/music/master.flac", 10.0, -6.5, -65.0);
+
+        let diff = MetricsDiff::new(a, b, &thresholds);
+        let names: Vec<&str> = diff.deltas.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "lra",
+                "peakAmplitudeDb",
+                "overallRmsDb",
+                "rmsDbAbove18k",
+                "rmsDbAbove20k"
+            ]
+        );
+        assert_eq!(diff.get("lra").unwrap().delta(), Some(0.0));
+        assert_eq!(diff.get("rmsDbAbove18k").unwrap().delta(), Some(17.0));
+        // 只有一方有数据时没有差值
+        assert_eq!(diff.get("rmsDbAbove20k").unwrap().delta(), None);
+        assert_eq!(diff.get("overallRmsDb").unwrap().delta(), None);
+
+        assert_eq!(diff.cleaner(), Ordering::Greater);
+        assert!(diff
+            .localized_summary(Locale::Zh)
+            .starts_with("B (master.flac) 更干净"));
+        assert!(diff
+            .localized_summary(Locale::En)
+            .starts_with("B (master.flac) is cleaner"));
+    }
+
+    #[test]
+    fn test_identical_files() {
+        let thresholds = QualityThresholds::default();
+        let a = metrics("a.flac", 10.0, -6.5, -65.0);
+        let diff = MetricsDiff::new(a.clone(), a, &thresholds);
+        assert_eq!(diff.cleaner(), Ordering::Equal);
+        assert!(diff
+            .localized_summary(Locale::Zh)
+            .starts_with("两个文件没有明显差异"));
+    }
+}
@@ -2,24 +2,118 @@
 //!
 //! 管理音频分析器的配置选项和参数设置。
 
-use crate::error::{AnalyzerError, Result};
-use crate::types::QualityThresholds;
+use crate::error::{AnalyzerError, ErrorSource, Result};
+use crate::messages::{text, Locale};
+use crate::quality::QualityVerdict;
+use crate::report;
+use crate::types::{AnalysisWindow, QualityProfile, QualityThresholds};
+use crate::utils::fs_utils::ScanOptions;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 
 /// 音频分析器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AnalyzerConfig {
     /// 支持的音频文件扩展名
     pub supported_extensions: Vec<String>,
 
+    /// 扩展名不在已知音频格式列表（[`SUPPORTED_EXTENSIONS`]）中时报错，而不只是警告
+    ///
+    /// [`SUPPORTED_EXTENSIONS`]: crate::SUPPORTED_EXTENSIONS
+    pub strict_extensions: bool,
+
+    /// 质量评估预设（配置文件中未给出 `quality_thresholds` 时决定所用阈值）
+    pub profile: QualityProfile,
+
     /// 质量评估阈值
     pub quality_thresholds: QualityThresholds,
 
+    /// 高通分析的截止频率列表 (Hz)，须为非零且严格递增
+    pub highpass_frequencies: Vec<u32>,
+
+    /// 按路径选择质量预设的规则，按顺序取第一条匹配的规则（见 [`ProfileMatcher`]）
+    ///
+    /// 用于同一目录中混有不同类型的音频，例如音乐专辑中的口播插曲使用 `podcast` 预设。
+    pub profile_rules: Vec<ProfileRule>,
+
+    /// 响度归一化目标 (LUFS)，如主流流媒体平台的 -14.0；设置后CSV报告包含所需增益列
+    pub target_lufs: Option<f64>,
+
+    /// 是否在结果中保留整体 astats 输出的全部数值（`rawAstats`），供分析尚未建模的指标
+    pub capture_raw_astats: bool,
+
+    /// 是否在结果中按指标保留对应的FFmpeg原始输出（`rawOutput`），用于排查异常文件
+    pub keep_raw_output: bool,
+
+    /// 要分析的音频流序号（从 0 开始，None表示第一个音频流）
+    ///
+    /// 用于 MKV、多轨 WAV 等包含多个音频流的文件，例如分析 5.1 声道音轨而不是立体声缩混。
+    /// 所有FFmpeg调用都通过 `-map 0:a:<n>` 选择该音频流，文件中没有该音频流时分析失败。
+    pub stream_index: Option<usize>,
+
+    /// 是否为每个文件计算声学指纹（`fingerprint`），用于查找重复曲目
+    ///
+    /// 需要额外运行一次FFmpeg，且FFmpeg须启用 chromaprint 支持。
+    pub fingerprint: bool,
+
+    /// 是否为每个文件计算频谱平坦度与本底噪声（`spectralFlatness`、`noiseFloorDb`）
+    ///
+    /// 用于区分本身明亮的母带与额外加入的噪声/抖动。需要额外运行一次FFmpeg；
+    /// FFmpeg 没有 `aspectralstats` 滤镜（5.0 之前的版本）时这两项留空。
+    pub spectral_flatness: bool,
+
+    /// 解析错误与 `rawOutput` 中原始输出的最大字符数（None表示保留完整输出）
+    pub raw_output_max_chars: Option<usize>,
+
     /// 并行处理线程数（None表示使用系统默认）
     pub num_threads: Option<usize>,
 
-    /// 是否启用详细日志
+    /// 同时运行的FFmpeg进程数上限（None表示不额外限制）
+    ///
+    /// 分析线程数仍由 `num_threads` 决定：超出上限的线程会等待空闲的FFmpeg名额，
+    /// 而缓存命中等不需要FFmpeg的工作不受影响。每个FFmpeg进程自身也是多线程的，
+    /// 在核心数很多的机器上设置较小的上限通常比让每个线程各自启动FFmpeg更快。
+    pub max_concurrent_ffmpeg: Option<usize>,
+
+    /// FFmpeg 因暂时性错误（启动失败、被系统结束）失败时的重试次数
+    ///
+    /// 每次重试前等待的时间从 250 毫秒起逐次加倍；超时、非零退出码和解析失败不会重试。
+    pub max_retries: u8,
+
+    /// 小于此大小（字节）的文件直接跳过而不启动FFmpeg（None表示不限制）
+    ///
+    /// 用于排除残留的占位文件、只含封面的 `.m4a` 等；空文件无论是否设置都会被跳过。
+    pub min_file_size_bytes: Option<u64>,
+
+    /// 时长短于此值（秒）的文件视为不是音频，跳过而不计入结果（默认None，不检查时长）
+    ///
+    /// 用于排除只含封面图片或几乎为空的音频容器（如设为 `1.0`）；
+    /// 没有音频流、只有封面图片的文件无论是否设置都会被跳过。
+    pub min_audio_duration_secs: Option<f64>,
+
+    /// 以较低的调度优先级运行FFmpeg子进程，避免批量分析时影响前台使用
+    ///
+    /// 目前只在 Unix 上生效（nice 值增加 10），其他平台忽略此设置。
+    pub low_priority: bool,
+
+    /// 断点续跑：沿用输出目录中已有JSON结果里的文件，只分析其余文件并合并写出
+    ///
+    /// 已有结果取自JSON，以及中断的运行逐个写出的NDJSON（两者都有时以NDJSON为准）。
+    /// 文件大小与记录的 `fileSizeBytes` 不一致、或上次结果不完整（`partial` 或缺少核心指标）时仍会重新分析。
+    pub resume: bool,
+
+    /// 界面语言（None表示按环境变量 `AUDIO_ANALYZER_LANG`/`LANG` 确定，默认中文）
+    ///
+    /// 只影响使用此配置的分析器产生的提示与错误信息，不改变进程的界面语言；
+    /// 报告文件的字段名与列名不受影响。
+    pub locale: Option<Locale>,
+
+    /// 是否启用详细日志（只供命令行程序使用）
+    ///
+    /// 库本身不读取此项：内部的诊断信息一律通过 `log` 以 `debug` 级别输出，是否显示由调用方的日志后端决定；
+    /// 命令行程序据此把日志级别设为 `debug`，并额外输出FFmpeg版本等信息。
     pub verbose: bool,
 
     /// 是否启用进度条
@@ -30,10 +124,31 @@ pub struct AnalyzerConfig {
 
     /// FFmpeg 配置
     pub ffmpeg: FfmpegConfig,
+
+    /// 分析缓存文件路径（None表示不使用缓存）
+    pub cache_path: Option<PathBuf>,
+
+    /// 内置二进制文件的固定解压目录（None表示每次运行解压到新的临时目录）
+    ///
+    /// 设置后，目录中已有且与内置版本一致的文件会直接复用，省去每次启动时的解压。
+    pub extraction_dir: Option<PathBuf>,
+
+    /// 扫描目录时排除的 glob 模式（如 `@eaDir`、`.Trash*`、`lossy/*`）
+    pub exclude_patterns: Vec<String>,
+
+    /// 扫描目录的最大递归深度（None表示不限制，1表示只扫描顶层文件）
+    pub max_depth: Option<usize>,
+
+    /// 扫描目录时是否跟随符号链接
+    pub follow_symlinks: bool,
+
+    /// 是否并行扫描目录（适合高延迟的网络存储，文件顺序不固定）
+    pub parallel_scan: bool,
 }
 
 /// 输出配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OutputConfig {
     /// 输出目录
     pub output_dir: Option<PathBuf>,
@@ -44,11 +159,368 @@ pub struct OutputConfig {
     /// CSV 输出文件名
     pub csv_filename: String,
 
+    /// NDJSON 输出文件名
+    pub ndjson_filename: String,
+
+    /// 汇总统计 JSON 输出文件名
+    pub summary_filename: String,
+
+    /// Markdown 报告输出文件名
+    pub markdown_filename: String,
+
+    /// 生成的输出格式
+    pub formats: Vec<OutputFormat>,
+
     /// 是否包含处理时间信息
     pub include_timing: bool,
 
     /// 最小质量分数过滤
     pub min_quality_score: Option<i32>,
+
+    /// 最低质量判定过滤（如 `"good"`），与 `min_quality_score` 同时设置时两者都须满足
+    pub min_quality_verdict: Option<QualityVerdict>,
+
+    /// CSV 报告中输出的列及其顺序（`None` 时输出全部列）
+    pub csv_columns: Option<Vec<String>>,
+
+    /// CSV 字段分隔符（如欧洲地区的 Excel 需要 `;`）
+    pub csv_delimiter: char,
+
+    /// 是否在CSV报告末尾追加按目录（专辑）汇总的段落
+    pub csv_album_summary: bool,
+
+    /// 是否覆盖已存在的报告文件（为 `false` 时报错而不是覆盖）
+    pub overwrite: bool,
+
+    /// 报告中文件路径的写法（只影响写出的报告，分析时仍使用实际路径）
+    pub path_style: PathStyle,
+
+    /// 是否在输出目录中保留JSON数据文件（`analysis_data.json`）
+    ///
+    /// 为 `false` 时即使 `formats` 包含 `json` 也不写出；命令行使用Python报告时，
+    /// JSON 改为写入临时目录，生成报告后删除。断点续跑 (`resume`) 需要保留JSON。
+    pub keep_intermediate_json: bool,
+}
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// 格式化的JSON数组
+    Json,
+    /// 每行一个JSON对象，逐个文件写出
+    Ndjson,
+    /// CSV质量报告
+    Csv,
+    /// 批量汇总统计（文件数、失败列表、响度统计与频谱检测结果）
+    Summary,
+    /// 供人工阅读的 Markdown 报告（汇总与逐文件表格）
+    Markdown,
+}
+
+impl OutputFormat {
+    /// 全部输出格式，格式列表中的 `all` 展开为此列表
+    pub const ALL: [OutputFormat; 5] = [
+        OutputFormat::Json,
+        OutputFormat::Ndjson,
+        OutputFormat::Csv,
+        OutputFormat::Summary,
+        OutputFormat::Markdown,
+    ];
+
+    /// 格式名称，与配置文件中的写法一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Summary => "summary",
+            OutputFormat::Markdown => "markdown",
+        }
+    }
+
+    /// 解析格式列表（如 `--format json,csv`）
+    ///
+    /// 每一项可以再用逗号分隔，大小写不敏感；`all` 表示全部格式。
+    /// 重复的格式只保留第一次出现的位置。
+    pub fn parse_list<I, S>(values: I) -> Result<Vec<Self>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut formats = Vec::new();
+        for value in values {
+            for name in value.as_ref().split(',').map(str::trim) {
+                let parsed = if name.eq_ignore_ascii_case("all") {
+                    Self::ALL.to_vec()
+                } else {
+                    vec![name.parse()?]
+                };
+                for format in parsed {
+                    if !formats.contains(&format) {
+                        formats.push(format);
+                    }
+                }
+            }
+        }
+        Ok(formats)
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "summary" => Ok(OutputFormat::Summary),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(AnalyzerError::ConfigError(crate::tr!(
+                "未知的输出格式: {s}（可选: json, ndjson, csv, summary, markdown，或 all 表示全部）",
+                "Unknown output format: {s} (expected: json, ndjson, csv, summary, markdown, or all)"
+            ))),
+        }
+    }
+}
+
+/// 报告中文件路径的写法
+///
+/// 用于在不同机器之间比较或提交到版本库的报告，避免主目录等前缀带来的差异。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathStyle {
+    /// 分析时使用的路径（扫描目录时通常为绝对路径）
+    #[default]
+    Absolute,
+    /// 相对于扫描的输入目录（输入为单个文件时为其所在目录）
+    ///
+    /// 没有输入目录（如 `--files-from` 文件列表）或文件不在该目录下时保留原路径。
+    RelativeToInput,
+    /// 只保留文件名
+    FilenameOnly,
+}
+
+impl PathStyle {
+    /// 写法名称，与配置文件中的写法一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PathStyle::Absolute => "absolute",
+            PathStyle::RelativeToInput => "relative_to_input",
+            PathStyle::FilenameOnly => "filename_only",
+        }
+    }
+
+    /// 按此写法转换 `path`，`root` 为扫描的输入目录
+    ///
+    /// 转换大量路径时使用 [`PathStyler`]，输入目录只需规范化一次。
+    pub fn apply(self, path: &Path, root: Option<&Path>) -> String {
+        PathStyler::new(self, root).apply(path)
+    }
+}
+
+/// 按 [`PathStyle`] 转换一批路径
+#[derive(Debug, Clone)]
+pub struct PathStyler {
+    style: PathStyle,
+    /// 计算相对路径的基准：输入目录本身，以及规范化后的目录
+    roots: Vec<PathBuf>,
+}
+
+impl PathStyler {
+    /// 创建转换器，`root` 为扫描的输入目录
+    pub fn new(style: PathStyle, root: Option<&Path>) -> Self {
+        let roots = match (style, root) {
+            // 扫描结果可能基于规范化后的目录，两种写法都尝试
+            (PathStyle::RelativeToInput, Some(root)) => std::iter::once(root.to_path_buf())
+                .chain(root.canonicalize().ok())
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self { style, roots }
+    }
+
+    /// 转换使用的写法
+    pub fn style(&self) -> PathStyle {
+        self.style
+    }
+
+    /// 按写法转换 `path`
+    pub fn apply(&self, path: &Path) -> String {
+        let styled = match self.style {
+            PathStyle::Absolute => None,
+            PathStyle::RelativeToInput => self
+                .roots
+                .iter()
+                .find_map(|root| path.strip_prefix(root).ok())
+                .filter(|relative| !relative.as_os_str().is_empty())
+                .map(|relative| relative.to_string_lossy().into_owned()),
+            PathStyle::FilenameOnly => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+        };
+        styled.unwrap_or_else(|| path.to_string_lossy().into_owned())
+    }
+
+    /// 由报告中的路径还原实际路径，无法还原（只保留了文件名）时返回 `None`
+    pub fn restore(&self, reported: &str) -> Option<PathBuf> {
+        let reported = Path::new(reported);
+        if reported.is_absolute() {
+            return Some(reported.to_path_buf());
+        }
+        match self.style {
+            PathStyle::Absolute => Some(reported.to_path_buf()),
+            PathStyle::RelativeToInput => self.roots.first().map(|root| root.join(reported)),
+            PathStyle::FilenameOnly => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PathStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PathStyle {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "absolute" => Ok(PathStyle::Absolute),
+            "relative" | "relative_to_input" => Ok(PathStyle::RelativeToInput),
+            "filename" | "filename_only" => Ok(PathStyle::FilenameOnly),
+            _ => Err(AnalyzerError::ConfigError(crate::tr!(
+                "未知的路径写法: {s}（可选: absolute, relative, filename）",
+                "Unknown path style: {s} (expected: absolute, relative, filename)"
+            ))),
+        }
+    }
+}
+
+/// 按路径选择质量预设的规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRule {
+    /// glob 模式（区分大小写），与文件的完整路径或文件名匹配（如 `*interlude*`、`*/spoken/*`）
+    pub pattern: String,
+    /// 匹配的文件使用的质量预设
+    pub profile: QualityProfile,
+}
+
+/// 为每个文件选择质量阈值
+///
+/// [`QualityThresholds`] 对所有文件使用同一组阈值；[`ProfileMatcher`] 按 `profile_rules` 为匹配的文件
+/// 使用预设的阈值。汇总与报告函数（如 [`crate::AnalysisSummary::from_report`]）接受两者之一。
+pub trait ThresholdSource {
+    /// 评估 `path` 对应文件时使用的阈值，`path` 应为原始路径（见 [`crate::AudioMetrics::path`]）
+    fn thresholds_for(&self, path: &Path) -> Cow<'_, QualityThresholds>;
+}
+
+impl ThresholdSource for QualityThresholds {
+    fn thresholds_for(&self, _path: &Path) -> Cow<'_, QualityThresholds> {
+        Cow::Borrowed(self)
+    }
+}
+
+/// 编译后的 `profile_rules`，按路径选择质量预设
+///
+/// 由 [`AnalyzerConfig::profile_matcher`] 创建，模式只编译一次；没有规则匹配的文件使用配置中的
+/// `quality_thresholds`。
+#[derive(Debug, Clone, Default)]
+pub struct ProfileMatcher {
+    rules: Vec<(glob::Pattern, QualityProfile)>,
+    thresholds: QualityThresholds,
+}
+
+impl ProfileMatcher {
+    /// 为文件选择质量预设（没有规则匹配时为 `None`）
+    ///
+    /// 模式与文件的完整路径或文件名匹配，按顺序取第一条匹配的规则。
+    pub fn profile_for(&self, path: &Path) -> Option<QualityProfile> {
+        let name = path.file_name().map(Path::new);
+        self.rules.iter().find_map(|(pattern, profile)| {
+            (pattern.matches_path(path) || name.is_some_and(|name| pattern.matches_path(name)))
+                .then_some(*profile)
+        })
+    }
+
+    /// 文件分析时使用的覆盖项：匹配了规则时使用该预设的阈值
+    pub fn overrides_for(&self, path: &Path) -> ConfigOverrides {
+        self.profile_for(path)
+            .map(ConfigOverrides::from)
+            .unwrap_or_default()
+    }
+}
+
+impl ThresholdSource for ProfileMatcher {
+    fn thresholds_for(&self, path: &Path) -> Cow<'_, QualityThresholds> {
+        match self.profile_for(path) {
+            Some(profile) => Cow::Owned(profile.thresholds()),
+            None => Cow::Borrowed(&self.thresholds),
+        }
+    }
+}
+
+/// 单次分析时覆盖的配置项
+///
+/// 只作用于 [`crate::AudioAnalyzer::analyze_file_with`] 的这一次调用，不修改分析器的共享配置；
+/// 为 `None` 的项沿用分析器配置。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigOverrides {
+    /// 质量评估阈值
+    pub quality_thresholds: Option<QualityThresholds>,
+    /// 高通分析的截止频率列表 (Hz)
+    pub highpass_frequencies: Option<Vec<u32>>,
+}
+
+impl ConfigOverrides {
+    /// 是否没有覆盖任何配置项
+    pub fn is_empty(&self) -> bool {
+        self.quality_thresholds.is_none() && self.highpass_frequencies.is_none()
+    }
+
+    /// 在 `config` 的基础上应用覆盖后的质量阈值
+    pub fn thresholds<'a>(&'a self, config: &'a AnalyzerConfig) -> &'a QualityThresholds {
+        self.quality_thresholds
+            .as_ref()
+            .unwrap_or(&config.quality_thresholds)
+    }
+
+    /// 在 `config` 的基础上应用覆盖后的高通截止频率
+    pub fn highpass_frequencies<'a>(&'a self, config: &'a AnalyzerConfig) -> &'a [u32] {
+        self.highpass_frequencies
+            .as_deref()
+            .unwrap_or(&config.highpass_frequencies)
+    }
+
+    /// 验证覆盖项，规则与 [`AnalyzerConfig::validate`] 中的对应项相同
+    pub fn validate(&self) -> Result<()> {
+        if let Some(frequencies) = &self.highpass_frequencies {
+            validate_highpass_frequencies(frequencies)?;
+        }
+        if let Some(thresholds) = &self.quality_thresholds {
+            thresholds.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl From<QualityProfile> for ConfigOverrides {
+    /// 使用预设对应的质量阈值，其余配置项不变
+    fn from(profile: QualityProfile) -> Self {
+        Self {
+            quality_thresholds: Some(profile.thresholds()),
+            highpass_frequencies: None,
+        }
+    }
 }
 
 /// FFmpeg 配置
@@ -62,6 +534,38 @@ pub struct FfmpegConfig {
 
     /// 超时时间（秒）
     pub timeout_seconds: Option<u64>,
+
+    /// 外部FFmpeg可执行文件路径（None表示使用嵌入的二进制文件）
+    pub binary_path: Option<PathBuf>,
+
+    /// 只分析从此处（秒）开始的片段（None表示从文件开头分析）
+    pub analyze_start_secs: Option<f64>,
+
+    /// 最多分析多少秒（None表示分析到文件末尾）
+    ///
+    /// 用于长时间的 DJ 混音、有声书等只需抽查一段的内容；
+    /// 结果中的 `analysisWindow` 会注明只分析了片段。
+    pub analyze_duration_secs: Option<f64>,
+
+    /// 附加在 `-i` 之前的输入选项（如 `["-err_detect", "ignore_err"]`），作用于所有FFmpeg调用
+    ///
+    /// 用于本库未单独建模的解码器选项。参数原样传给FFmpeg，不做检查：
+    /// 改变日志输出（如 `-loglevel`、`-nostats`）或额外的 `-i` 输入都可能导致结果无法解析。
+    #[serde(default)]
+    pub extra_input_args: Vec<String>,
+
+    /// 附加在每个输出之前的输出选项（如 `["-threads", "1"]`），作用于所有FFmpeg调用
+    ///
+    /// 与 `extra_input_args` 一样原样传给FFmpeg，误用可能导致结果无法解析。
+    #[serde(default)]
+    pub extra_output_args: Vec<String>,
+
+    /// 附加到 `ebur128` 滤镜的选项（如 `"dualmono=true:panlaw=-3.01"`，None表示使用FFmpeg默认值）
+    ///
+    /// 用于按交付规范调整响度测量的约定。只接受 [`EBUR128_ALLOWED_OPTIONS`] 中的选项，
+    /// 以免改变日志输出导致结果无法解析；`framelog=verbose` 会隐藏逐帧日志，
+    /// 此时响度时间序列为空。
+    pub ebur128_args: Option<String>,
 }
 
 impl Default for AnalyzerConfig {
@@ -79,24 +583,89 @@ impl Default for AnalyzerConfig {
                 "aiff".to_string(),
                 "alac".to_string(),
             ],
+            strict_extensions: false,
+            profile: QualityProfile::default(),
             quality_thresholds: QualityThresholds::default(),
+            highpass_frequencies: vec![16000, 18000, 20000],
+            profile_rules: Vec::new(),
+            target_lufs: None,
+            capture_raw_astats: false,
+            keep_raw_output: false,
+            stream_index: None,
+            fingerprint: false,
+            spectral_flatness: false,
+            raw_output_max_chars: None,
             num_threads: None,
+            max_concurrent_ffmpeg: None,
+            max_retries: 2,
+            min_file_size_bytes: None,
+            min_audio_duration_secs: None,
+            low_priority: false,
+            resume: false,
+            locale: None,
             verbose: false,
             show_progress: true,
             output: OutputConfig::default(),
             ffmpeg: FfmpegConfig::default(),
+            cache_path: None,
+            extraction_dir: None,
+            exclude_patterns: Vec::new(),
+            max_depth: None,
+            follow_symlinks: false,
+            parallel_scan: false,
         }
     }
 }
 
+impl OutputConfig {
+    /// 指定格式的输出文件在 `output_dir` 中的路径
+    pub fn path_for(&self, format: OutputFormat, output_dir: &Path) -> PathBuf {
+        let filename = match format {
+            OutputFormat::Json => &self.json_filename,
+            OutputFormat::Ndjson => &self.ndjson_filename,
+            OutputFormat::Csv => &self.csv_filename,
+            OutputFormat::Summary => &self.summary_filename,
+            OutputFormat::Markdown => &self.markdown_filename,
+        };
+        output_dir.join(filename)
+    }
+
+    /// 按 `formats` 的顺序列出所有输出文件的路径（重复的格式只列出一次）
+    ///
+    /// `keep_intermediate_json` 为 `false` 时不含JSON，因此可能为空（批量分析只返回结果，不写出文件）。
+    pub fn output_paths(&self, output_dir: &Path) -> Vec<(OutputFormat, PathBuf)> {
+        let mut paths: Vec<(OutputFormat, PathBuf)> = Vec::new();
+        for &format in &self.formats {
+            if format == OutputFormat::Json && !self.keep_intermediate_json {
+                continue;
+            }
+            if paths.iter().all(|(existing, _)| *existing != format) {
+                paths.push((format, self.path_for(format, output_dir)));
+            }
+        }
+        paths
+    }
+}
+
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             output_dir: None,
             json_filename: "analysis_data.json".to_string(),
             csv_filename: "audio_quality_report.csv".to_string(),
+            ndjson_filename: "analysis_data.ndjson".to_string(),
+            summary_filename: "summary.json".to_string(),
+            markdown_filename: "report.md".to_string(),
+            formats: vec![OutputFormat::Json, OutputFormat::Csv, OutputFormat::Summary],
             include_timing: true,
             min_quality_score: None,
+            min_quality_verdict: None,
+            csv_columns: None,
+            csv_delimiter: ',',
+            csv_album_summary: false,
+            overwrite: true,
+            path_style: PathStyle::default(),
+            keep_intermediate_json: true,
         }
     }
 }
@@ -107,58 +676,360 @@ impl Default for FfmpegConfig {
             log_level: "info".to_string(),
             hide_banner: true,
             timeout_seconds: Some(300), // 5分钟超时
+            binary_path: None,
+            analyze_start_secs: None,
+            analyze_duration_secs: None,
+            extra_input_args: Vec::new(),
+            extra_output_args: Vec::new(),
+            ebur128_args: None,
         }
     }
 }
 
+/// 指定外部FFmpeg路径的环境变量
+pub const FFMPEG_PATH_ENV: &str = "AUDIO_ANALYZER_FFMPEG";
+
+/// `ffmpeg.ebur128_args` 中允许使用的 `ebur128` 滤镜选项
+pub const EBUR128_ALLOWED_OPTIONS: &[&str] =
+    &["framelog", "gauge", "target", "scale", "dualmono", "panlaw"];
+
+impl FfmpegConfig {
+    /// 配置的分析片段，分析整个文件时为 `None`
+    pub fn analysis_window(&self) -> Option<AnalysisWindow> {
+        if self.analyze_start_secs.is_none() && self.analyze_duration_secs.is_none() {
+            return None;
+        }
+        Some(AnalysisWindow {
+            start_secs: self.analyze_start_secs.unwrap_or(0.0),
+            duration_secs: self.analyze_duration_secs,
+        })
+    }
+
+    /// 解析外部FFmpeg路径：配置项优先，其次为 `AUDIO_ANALYZER_FFMPEG` 环境变量
+    pub fn resolve_binary_path(&self) -> Option<PathBuf> {
+        self.binary_path.clone().or_else(|| {
+            std::env::var_os(FFMPEG_PATH_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        })
+    }
+}
+
 impl AnalyzerConfig {
+    /// 创建以默认配置为起点的构建器
+    pub fn builder() -> AnalyzerConfigBuilder {
+        AnalyzerConfigBuilder::default()
+    }
+
     /// 从文件加载配置
+    ///
+    /// 按扩展名选择格式：`.toml`（无扩展名时同样按TOML解析）、`.json`、`.yaml`/`.yml`。
+    /// `quality_thresholds` 表中未给出的阈值取 `profile` 预设对应的值（未指定预设时为音乐）。
     pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)?;
         let content = std::fs::read_to_string(path)?;
-        let config: AnalyzerConfig = toml::from_str(&content)
-            .map_err(|e| AnalyzerError::ConfigError(format!("配置文件解析错误: {e}")))?;
-        Ok(config)
+
+        // 保留原始的解析错误，调用方可以通过 `source()` 取得行号等细节
+        let parse_error = |e: ErrorSource| AnalyzerError::ConfigParseError {
+            path: path.display().to_string(),
+            source: e,
+        };
+        let mut value: serde_json::Value = match format {
+            ConfigFormat::Toml => toml::from_str(&content).map_err(|e| parse_error(e.into()))?,
+            ConfigFormat::Json => {
+                serde_json::from_str(&content).map_err(|e| parse_error(e.into()))?
+            }
+            ConfigFormat::Yaml => {
+                serde_yaml::from_str(&content).map_err(|e| parse_error(e.into()))?
+            }
+        };
+        merge_profile_thresholds(&mut value).map_err(|e| parse_error(e.into()))?;
+
+        serde_json::from_value(value).map_err(|e| parse_error(e.into()))
+    }
+
+    /// 切换质量评估预设，并替换为该预设的阈值
+    pub fn set_profile(&mut self, profile: QualityProfile) {
+        self.profile = profile;
+        self.quality_thresholds = profile.thresholds();
     }
 
-    /// 保存配置到文件
+    /// 保存配置到文件，格式规则与 [`AnalyzerConfig::from_file`] 相同
     pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
-        let content = toml::to_string_pretty(self)
-            .map_err(|e| AnalyzerError::ConfigError(format!("配置序列化错误: {e}")))?;
+        let path = path.as_ref();
+        let serialize_error = |e: &dyn std::fmt::Display| {
+            AnalyzerError::ConfigError(crate::tr!(
+                "配置序列化错误: {e}",
+                "Failed to serialize the configuration: {e}"
+            ))
+        };
+        let content = match ConfigFormat::from_path(path)? {
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(|e| serialize_error(&e))?,
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(|e| serialize_error(&e))?
+            }
+            ConfigFormat::Yaml => serde_yaml::to_string(self).map_err(|e| serialize_error(&e))?,
+        };
         std::fs::write(path, content)?;
         Ok(())
     }
 
     /// 验证配置的有效性
+    ///
+    /// 只检查配置，不输出日志；非严格模式下的未知扩展名见
+    /// [`AnalyzerConfig::unknown_extensions_warning`]，由 [`crate::AudioAnalyzer::new`] 提示一次。
     pub fn validate(&self) -> Result<()> {
         if self.supported_extensions.is_empty() {
             return Err(AnalyzerError::ConfigError(
-                "支持的文件扩展名列表不能为空".to_string(),
+                text(
+                    "支持的文件扩展名列表不能为空",
+                    "The list of supported extensions must not be empty",
+                )
+                .to_string(),
+            ));
+        }
+
+        if self.strict_extensions {
+            if let Some(message) = self.unknown_extensions_warning() {
+                return Err(AnalyzerError::ConfigError(message));
+            }
+        }
+
+        if self.resume && !self.output.overwrite {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "resume 需要更新已有的JSON结果，不能与 output.overwrite = false 同时使用",
+                    "resume updates the existing JSON results and cannot be combined with output.overwrite = false",
+                )
+                .to_string(),
+            ));
+        }
+
+        if self.resume && self.output.path_style == PathStyle::FilenameOnly {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "resume 需要按路径匹配已有结果，不能与 output.path_style = filename_only 同时使用",
+                    "resume matches existing results by path and cannot be combined with output.path_style = filename_only",
+                )
+                .to_string(),
+            ));
+        }
+
+        if self.resume && !self.output.keep_intermediate_json {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "resume 需要读取已有的JSON结果，不能与 output.keep_intermediate_json = false 同时使用",
+                    "resume reads the existing JSON results and cannot be combined with output.keep_intermediate_json = false",
+                )
+                .to_string(),
             ));
         }
 
         if let Some(threads) = self.num_threads {
             if threads == 0 {
-                return Err(AnalyzerError::ConfigError("线程数必须大于0".to_string()));
+                return Err(AnalyzerError::ConfigError(
+                    text("线程数必须大于0", "The thread count must be greater than 0").to_string(),
+                ));
             }
         }
 
-        // 验证质量阈值的合理性
-        let thresholds = &self.quality_thresholds;
-        if thresholds.lra_poor_max >= thresholds.lra_low_max {
+        if self.target_lufs.is_some_and(|target| !target.is_finite()) {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "目标响度必须是有限的数值",
+                    "The target loudness must be a finite number",
+                )
+                .to_string(),
+            ));
+        }
+
+        if self
+            .ffmpeg
+            .analyze_start_secs
+            .is_some_and(|start| !start.is_finite() || start < 0.0)
+        {
             return Err(AnalyzerError::ConfigError(
-                "LRA阈值配置不合理: poor_max 应小于 low_max".to_string(),
+                text(
+                    "分析片段的起点必须是不小于0的有限数值",
+                    "The analysis window start must be a finite number of at least 0",
+                )
+                .to_string(),
             ));
         }
 
-        if thresholds.peak_good_db >= thresholds.peak_medium_db {
+        if self
+            .ffmpeg
+            .analyze_duration_secs
+            .is_some_and(|duration| !duration.is_finite() || duration <= 0.0)
+        {
             return Err(AnalyzerError::ConfigError(
-                "峰值阈值配置不合理: good_db 应小于 medium_db".to_string(),
+                text(
+                    "分析片段的长度必须是大于0的有限数值",
+                    "The analysis window duration must be a finite number greater than 0",
+                )
+                .to_string(),
             ));
         }
 
+        if self
+            .min_audio_duration_secs
+            .is_some_and(|duration| !duration.is_finite() || duration < 0.0)
+        {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "最短音频时长必须是不小于0的有限数值",
+                    "The minimum audio duration must be a finite number of at least 0",
+                )
+                .to_string(),
+            ));
+        }
+
+        if self.max_concurrent_ffmpeg == Some(0) {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "FFmpeg并发数上限必须大于0",
+                    "The FFmpeg concurrency limit must be greater than 0",
+                )
+                .to_string(),
+            ));
+        }
+
+        validate_highpass_frequencies(&self.highpass_frequencies)?;
+
+        if let Some(args) = &self.ffmpeg.ebur128_args {
+            validate_ebur128_args(args)?;
+        }
+
+        self.compile_exclude_patterns()?;
+        self.compile_profile_rules()?;
+
+        if self.output.formats.is_empty() {
+            return Err(AnalyzerError::ConfigError(
+                text(
+                    "输出格式列表不能为空",
+                    "The list of output formats must not be empty",
+                )
+                .to_string(),
+            ));
+        }
+
+        self.validate_csv_options()?;
+
+        self.quality_thresholds.validate()
+    }
+
+    /// 验证CSV列名与分隔符
+    fn validate_csv_options(&self) -> Result<()> {
+        let delimiter = self.output.csv_delimiter;
+        if !delimiter.is_ascii() || matches!(delimiter, '"' | '\r' | '\n') {
+            return Err(AnalyzerError::ConfigError(crate::tr!(
+                "无效的CSV分隔符 {delimiter:?}：必须是引号和换行以外的单个ASCII字符",
+                "Invalid CSV delimiter {delimiter:?}: it must be a single ASCII character other than quotes and newlines"
+            )));
+        }
+
+        if let Some(columns) = &self.output.csv_columns {
+            if columns.is_empty() {
+                return Err(AnalyzerError::ConfigError(
+                    text(
+                        "CSV列列表不能为空",
+                        "The list of CSV columns must not be empty",
+                    )
+                    .to_string(),
+                ));
+            }
+            if let Some(unknown) = columns.iter().find(|c| !report::is_csv_column(c)) {
+                return Err(AnalyzerError::ConfigError(crate::tr!(
+                    "未知的CSV列: {unknown}（可选: {}，以及 rmsDbAbove18k 形式的高通频段列）",
+                    "Unknown CSV column: {unknown} (expected: {}, or a high-pass band column such as rmsDbAbove18k)",
+                    report::fixed_csv_columns().collect::<Vec<_>>().join(", ")
+                )));
+            }
+        }
         Ok(())
     }
 
+    /// 将 `exclude_patterns` 编译为 glob 模式
+    pub fn compile_exclude_patterns(&self) -> Result<Vec<glob::Pattern>> {
+        self.exclude_patterns
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern).map_err(|e| {
+                    AnalyzerError::ConfigError(crate::tr!(
+                        "无效的排除模式 '{pattern}': {e}",
+                        "Invalid exclude pattern '{pattern}': {e}"
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// 将 `profile_rules` 的模式编译为 glob 模式，与规则一一对应
+    fn compile_profile_rules(&self) -> Result<Vec<(glob::Pattern, QualityProfile)>> {
+        self.profile_rules
+            .iter()
+            .map(|rule| {
+                glob::Pattern::new(&rule.pattern)
+                    .map(|pattern| (pattern, rule.profile))
+                    .map_err(|e| {
+                        AnalyzerError::ConfigError(crate::tr!(
+                            "无效的预设规则模式 '{}': {e}",
+                            "Invalid profile rule pattern '{}': {e}",
+                            rule.pattern
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// 编译 `profile_rules`，用于按路径选择质量阈值
+    ///
+    /// 模式无效时返回错误（[`AnalyzerConfig::validate`] 同样会报告）。
+    pub fn profile_matcher(&self) -> Result<ProfileMatcher> {
+        Ok(ProfileMatcher {
+            rules: self.compile_profile_rules()?,
+            thresholds: self.quality_thresholds.clone(),
+        })
+    }
+
+    /// 根据配置生成目录扫描选项
+    pub fn scan_options(&self) -> Result<ScanOptions> {
+        Ok(ScanOptions {
+            exclude: self.compile_exclude_patterns()?,
+            max_depth: self.max_depth,
+            follow_symlinks: self.follow_symlinks,
+            parallel_scan: self.parallel_scan,
+        })
+    }
+
+    /// `supported_extensions` 中不属于已知音频格式的扩展名（多为拼写错误或视频格式）
+    pub fn unknown_extensions(&self) -> Vec<&str> {
+        self.supported_extensions
+            .iter()
+            .map(String::as_str)
+            .filter(|ext| {
+                !crate::SUPPORTED_EXTENSIONS
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(ext))
+            })
+            .collect()
+    }
+
+    /// 列出未知扩展名的提示文字，没有未知扩展名时返回 `None`
+    pub fn unknown_extensions_warning(&self) -> Option<String> {
+        let unknown = self.unknown_extensions();
+        if unknown.is_empty() {
+            return None;
+        }
+        Some(crate::tr!(
+            "未知的音频扩展名: {}（已知: {}）",
+            "Unknown audio extensions: {} (known: {})",
+            unknown.join(", "),
+            crate::SUPPORTED_EXTENSIONS.join(", ")
+        ))
+    }
+
     /// 检查文件扩展名是否支持
     pub fn is_supported_extension(&self, extension: &str) -> bool {
         self.supported_extensions
@@ -172,6 +1043,194 @@ impl AnalyzerConfig {
     }
 }
 
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// 根据文件扩展名判断格式，无扩展名时按TOML处理
+    fn from_path(path: &std::path::Path) -> Result<Self> {
+        let Some(extension) = path.extension() else {
+            return Ok(ConfigFormat::Toml);
+        };
+        match extension.to_string_lossy().to_ascii_lowercase().as_str() {
+            "toml" => Ok(ConfigFormat::Toml),
+            "json" => Ok(ConfigFormat::Json),
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            other => Err(AnalyzerError::ConfigError(crate::tr!(
+                "不支持的配置文件格式: .{other}（可选: toml, json, yaml, yml）",
+                "Unsupported config file format: .{other} (expected: toml, json, yaml, yml)"
+            ))),
+        }
+    }
+}
+
+/// [`AnalyzerConfig`] 构建器
+///
+/// 从默认配置开始，链式设置需要修改的选项，最后由 [`build`](Self::build) 验证：
+///
+/// ```
+/// use audio_analyzer_ultimate::{AnalyzerConfig, OutputFormat};
+///
+/// let config = AnalyzerConfig::builder()
+///     .threads(4)
+///     .extensions(["flac", "wav"])
+///     .output_format(OutputFormat::Ndjson)
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.num_threads, Some(4));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerConfigBuilder {
+    config: AnalyzerConfig,
+}
+
+impl AnalyzerConfigBuilder {
+    /// 是否启用详细日志
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.config.verbose = verbose;
+        self
+    }
+
+    /// 并行处理线程数
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.num_threads = Some(threads);
+        self
+    }
+
+    /// 支持的音频文件扩展名
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.supported_extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 质量评估预设（同时替换为该预设的阈值）
+    pub fn profile(mut self, profile: QualityProfile) -> Self {
+        self.config.set_profile(profile);
+        self
+    }
+
+    /// 高通分析的截止频率列表 (Hz)
+    pub fn highpass_frequencies<I: IntoIterator<Item = u32>>(mut self, frequencies: I) -> Self {
+        self.config.highpass_frequencies = frequencies.into_iter().collect();
+        self
+    }
+
+    /// 界面语言
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.config.locale = Some(locale);
+        self
+    }
+
+    /// 只生成指定的一种输出格式
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.config.output.formats = vec![format];
+        self
+    }
+
+    /// 验证并生成配置
+    pub fn build(self) -> Result<AnalyzerConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// 以 `profile` 预设的阈值为底，覆盖配置文件中 `quality_thresholds` 表给出的各项
+///
+/// 这样只写出部分阈值时，其余阈值沿用所选预设，而不是 [`QualityThresholds`] 的默认值。
+/// 表的格式不正确时保持原样，由随后的反序列化报告错误。
+fn merge_profile_thresholds(value: &mut serde_json::Value) -> serde_json::Result<()> {
+    let Some(table) = value.as_object_mut() else {
+        return Ok(());
+    };
+
+    let profile: QualityProfile = match table.get("profile") {
+        Some(profile) => serde_json::from_value(profile.clone())?,
+        None => QualityProfile::default(),
+    };
+    let mut thresholds = serde_json::to_value(profile.thresholds())?;
+
+    match (table.get("quality_thresholds"), thresholds.as_object_mut()) {
+        (Some(serde_json::Value::Object(given)), Some(merged)) => {
+            merged.extend(
+                given
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+        }
+        (Some(_), _) => return Ok(()),
+        (None, _) => {}
+    }
+    table.insert("quality_thresholds".to_string(), thresholds);
+    Ok(())
+}
+
+/// 验证高通截止频率：必须大于0且严格递增
+fn validate_highpass_frequencies(frequencies: &[u32]) -> Result<()> {
+    if frequencies.contains(&0) {
+        return Err(AnalyzerError::ConfigError(
+            text(
+                "高通截止频率必须大于0",
+                "High-pass cutoff frequencies must be greater than 0",
+            )
+            .to_string(),
+        ));
+    }
+
+    if frequencies.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(AnalyzerError::ConfigError(
+            text(
+                "高通截止频率必须按升序排列且不能重复",
+                "High-pass cutoff frequencies must be in ascending order without duplicates",
+            )
+            .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 验证 `ebur128` 滤镜选项：以 `:` 分隔的 `选项=值`，选项须在允许列表中
+///
+/// 值只能包含字母、数字和 `.`、`+`、`-`，避免混入滤镜图的分隔符。
+fn validate_ebur128_args(args: &str) -> Result<()> {
+    for option in args.split(':') {
+        let Some((name, value)) = option.split_once('=') else {
+            return Err(AnalyzerError::ConfigError(crate::tr!(
+                "ebur128 选项必须写成 选项=值: {option:?}",
+                "ebur128 options must be written as option=value: {option:?}"
+            )));
+        };
+
+        if !EBUR128_ALLOWED_OPTIONS.contains(&name) {
+            return Err(AnalyzerError::ConfigError(crate::tr!(
+                "不支持的 ebur128 选项: {name:?}（可选: {}）",
+                "Unsupported ebur128 option: {name:?} (expected: {})",
+                EBUR128_ALLOWED_OPTIONS.join(", ")
+            )));
+        }
+
+        let valid_value = !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'));
+        if !valid_value {
+            return Err(AnalyzerError::ConfigError(crate::tr!(
+                "ebur128 选项 {name} 的值无效: {value:?}",
+                "Invalid value for ebur128 option {name}: {value:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// 从环境变量或默认值创建配置
 pub fn create_default_config() -> AnalyzerConfig {
     let mut config = AnalyzerConfig::default();
@@ -214,6 +1273,448 @@ mod tests {
         assert!(invalid_config.validate().is_err());
     }
 
+    #[test]
+    fn test_unknown_extensions() {
+        let mut config = AnalyzerConfig {
+            supported_extensions: ["FLAC", "falc", "mp4"].map(String::from).to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(config.unknown_extensions(), ["falc", "mp4"]);
+        // 默认只警告（由分析器创建时输出）
+        assert!(config.validate().is_ok());
+        assert!(config
+            .unknown_extensions_warning()
+            .is_some_and(|warning| warning.contains("falc, mp4")));
+
+        config.strict_extensions = true;
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("falc, mp4"));
+        assert!(message.contains("wav, mp3"));
+
+        config.supported_extensions = vec!["flac".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_max_concurrent_ffmpeg_validation() {
+        let mut config = AnalyzerConfig {
+            max_concurrent_ffmpeg: Some(6),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.max_concurrent_ffmpeg = Some(0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_min_audio_duration_validation() {
+        let mut config = AnalyzerConfig::default();
+        assert_eq!(config.min_audio_duration_secs, None);
+        config.min_audio_duration_secs = Some(1.0);
+        assert!(config.validate().is_ok());
+        config.min_audio_duration_secs = Some(-1.0);
+        assert!(config.validate().is_err());
+        config.min_audio_duration_secs = Some(f64::NAN);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_analysis_window_validation() {
+        let mut config = AnalyzerConfig::default();
+        assert_eq!(config.ffmpeg.analysis_window(), None);
+
+        config.ffmpeg.analyze_duration_secs = Some(120.0);
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.ffmpeg.analysis_window(),
+            Some(AnalysisWindow {
+                start_secs: 0.0,
+                duration_secs: Some(120.0),
+            })
+        );
+
+        config.ffmpeg.analyze_duration_secs = Some(0.0);
+        assert!(config.validate().is_err());
+
+        config.ffmpeg.analyze_duration_secs = None;
+        config.ffmpeg.analyze_start_secs = Some(-1.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ebur128_args_validation() {
+        let mut config = AnalyzerConfig::default();
+        config.ffmpeg.ebur128_args = Some("dualmono=true:panlaw=-3.01".to_string());
+        assert!(config.validate().is_ok());
+        config.ffmpeg.ebur128_args = Some("framelog=verbose:target=-16".to_string());
+        assert!(config.validate().is_ok());
+
+        for invalid in [
+            "",
+            "peak=true",
+            "metadata=1",
+            "dualmono",
+            "target=",
+            "target=-16[out9]",
+            "gauge=m,volume=2",
+        ] {
+            config.ffmpeg.ebur128_args = Some(invalid.to_string());
+            assert!(
+                matches!(config.validate(), Err(AnalyzerError::ConfigError(_))),
+                "{invalid:?} 应被拒绝"
+            );
+        }
+    }
+
+    #[test]
+    fn test_highpass_frequencies_validation() {
+        let mut config = AnalyzerConfig {
+            highpass_frequencies: vec![16000, 19000, 21000],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.highpass_frequencies = vec![18000, 16000];
+        assert!(config.validate().is_err());
+
+        config.highpass_frequencies = vec![0, 16000];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_builder() {
+        let config = AnalyzerConfig::builder()
+            .verbose(true)
+            .threads(2)
+            .extensions(["flac", "wav"])
+            .profile(QualityProfile::Podcast)
+            .highpass_frequencies([15000, 19000])
+            .output_format(OutputFormat::Csv)
+            .build()
+            .unwrap();
+
+        assert!(config.verbose);
+        assert_eq!(config.num_threads, Some(2));
+        assert_eq!(config.supported_extensions, vec!["flac", "wav"]);
+        assert_eq!(config.profile, QualityProfile::Podcast);
+        assert_eq!(
+            config.quality_thresholds.lra_low_max,
+            QualityThresholds::podcast().lra_low_max
+        );
+        assert_eq!(config.highpass_frequencies, vec![15000, 19000]);
+        assert_eq!(config.output.formats, vec![OutputFormat::Csv]);
+
+        // build 时执行验证
+        assert!(AnalyzerConfig::builder().threads(0).build().is_err());
+        assert!(AnalyzerConfig::builder()
+            .highpass_frequencies([18000, 16000])
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_threshold_order_validation() {
+        let config = AnalyzerConfig::default();
+
+        let mut spectrum = config.clone();
+        spectrum.quality_thresholds.spectrum_good_threshold = -82.0;
+        let message = spectrum.validate().unwrap_err().to_string();
+        assert!(message.contains("spectrum_processed_threshold"));
+        assert!(message.contains("spectrum_good_threshold"));
+
+        let mut lra = config.clone();
+        lra.quality_thresholds.lra_acceptable_max = 10.0;
+        let message = crate::messages::with_locale(Some(Locale::Zh), || lra.validate())
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("lra_excellent_max (12) 应小于 lra_acceptable_max (10)"));
+
+        let mut peak = config.clone();
+        peak.quality_thresholds.peak_medium_db = 0.0;
+        let message = peak.validate().unwrap_err().to_string();
+        assert!(message.contains("peak_medium_db"));
+        assert!(message.contains("peak_clipping_db"));
+
+        let mut nan = config;
+        nan.quality_thresholds.lra_low_max = f64::NAN;
+        assert!(nan.validate().is_err());
+    }
+
+    #[test]
+    fn test_exclude_patterns_validation() {
+        let mut config = AnalyzerConfig {
+            exclude_patterns: vec!["@eaDir".to_string(), "lossy/*".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.compile_exclude_patterns().unwrap().len(), 2);
+
+        config.exclude_patterns.push("[unclosed".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_profile_rules() {
+        let temp_file = NamedTempFile::with_suffix(".toml").unwrap();
+        std::fs::write(
+            temp_file.path(),
+            "[[profile_rules]]\npattern = \"*interlude*\"\nprofile = \"podcast\"\n\n\
+             [[profile_rules]]\npattern = \"*/masters/*\"\nprofile = \"mastering\"\n",
+        )
+        .unwrap();
+        let mut config = AnalyzerConfig::from_file(temp_file.path()).unwrap();
+        assert!(config.validate().is_ok());
+        let matcher = config.profile_matcher().unwrap();
+
+        let interlude = Path::new("/music/album/05 Interlude.flac");
+        assert_eq!(matcher.profile_for(interlude), None);
+        let interlude = Path::new("/music/album/05 interlude.flac");
+        assert_eq!(
+            matcher.profile_for(interlude),
+            Some(QualityProfile::Podcast)
+        );
+        assert_eq!(
+            matcher.profile_for(Path::new("/work/masters/mix.wav")),
+            Some(QualityProfile::Mastering)
+        );
+        assert_eq!(matcher.profile_for(Path::new("/music/album/01.flac")), None);
+
+        let overrides = matcher.overrides_for(interlude);
+        assert_eq!(
+            overrides.thresholds(&config).lra_low_max,
+            QualityThresholds::podcast().lra_low_max
+        );
+        assert_eq!(
+            overrides.highpass_frequencies(&config),
+            config.highpass_frequencies
+        );
+        assert!(matcher
+            .overrides_for(Path::new("/music/album/01.flac"))
+            .is_empty());
+        assert_eq!(
+            matcher
+                .thresholds_for(Path::new("/music/album/01.flac"))
+                .lra_low_max,
+            config.quality_thresholds.lra_low_max
+        );
+
+        config.profile_rules.push(ProfileRule {
+            pattern: "[unclosed".to_string(),
+            profile: QualityProfile::Music,
+        });
+        assert!(config.validate().is_err());
+        assert!(config.profile_matcher().is_err());
+
+        let overrides = ConfigOverrides {
+            highpass_frequencies: Some(vec![0, 16000]),
+            ..Default::default()
+        };
+        assert!(overrides.validate().is_err());
+    }
+
+    #[test]
+    fn test_csv_options_validation() {
+        let mut config = AnalyzerConfig::default();
+        config.output.csv_columns = Some(vec!["filePath".to_string(), "rmsDbAbove18k".to_string()]);
+        config.output.csv_delimiter = ';';
+        assert!(config.validate().is_ok());
+
+        config.output.csv_columns = Some(vec!["filePath".to_string(), "bitrate".to_string()]);
+        let error = crate::messages::with_locale(Some(Locale::En), || config.validate())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Unknown CSV column: bitrate"));
+
+        config.output.csv_columns = Some(Vec::new());
+        assert!(config.validate().is_err());
+
+        config.output.csv_columns = None;
+        config.output.csv_delimiter = '；';
+        assert!(config.validate().is_err());
+        config.output.csv_delimiter = '"';
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_output_formats() {
+        assert_eq!(
+            "NDJSON".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Ndjson
+        );
+        assert_eq!(
+            "jsonl".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Ndjson
+        );
+        assert_eq!(
+            "summary".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Summary
+        );
+        assert_eq!(
+            "md".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Markdown
+        );
+        assert!("xml".parse::<OutputFormat>().is_err());
+
+        // Display 与配置文件中的写法一致，可以原样解析回来
+        for format in OutputFormat::ALL {
+            assert_eq!(format.to_string().parse::<OutputFormat>().unwrap(), format);
+        }
+        assert_eq!(
+            OutputFormat::parse_list(["CSV, json", "csv"]).unwrap(),
+            vec![OutputFormat::Csv, OutputFormat::Json]
+        );
+        assert_eq!(
+            OutputFormat::parse_list(["csv", "all"]).unwrap(),
+            vec![
+                OutputFormat::Csv,
+                OutputFormat::Json,
+                OutputFormat::Ndjson,
+                OutputFormat::Summary,
+                OutputFormat::Markdown
+            ]
+        );
+        assert!(matches!(
+            OutputFormat::parse_list(["json", "xml"]),
+            Err(AnalyzerError::ConfigError(_))
+        ));
+
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "[output]\nformats = [\"ndjson\"]\n").unwrap();
+        let config = AnalyzerConfig::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.output.formats, vec![OutputFormat::Ndjson]);
+        assert_eq!(
+            config.output.csv_filename,
+            OutputConfig::default().csv_filename
+        );
+
+        let mut empty = config;
+        empty.output.formats.clear();
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_keep_intermediate_json() {
+        let mut config = AnalyzerConfig::default();
+        config.output.keep_intermediate_json = false;
+        let outputs = config.output.output_paths(Path::new("/reports"));
+        assert!(outputs
+            .iter()
+            .all(|(format, _)| *format != OutputFormat::Json));
+        assert!(outputs.contains(&(
+            OutputFormat::Csv,
+            PathBuf::from("/reports/audio_quality_report.csv")
+        )));
+        assert!(config.validate().is_ok());
+
+        // 不写出任何文件的配置有效，调用方自行处理返回的结果
+        let mut json_only = config.clone();
+        json_only.output.formats = vec![OutputFormat::Json];
+        assert!(json_only.validate().is_ok());
+        assert!(json_only
+            .output
+            .output_paths(Path::new("/reports"))
+            .is_empty());
+
+        let mut resume = config;
+        resume.resume = true;
+        let message = resume.validate().unwrap_err().to_string();
+        assert!(message.contains("keep_intermediate_json"));
+
+        // 只保留文件名时无法区分不同目录中的同名文件
+        let mut resume = AnalyzerConfig {
+            resume: true,
+            ..Default::default()
+        };
+        resume.output.path_style = PathStyle::FilenameOnly;
+        let message = resume.validate().unwrap_err().to_string();
+        assert!(message.contains("filename_only"));
+    }
+
+    #[test]
+    fn test_path_style() {
+        let root = Path::new("/home/alice/music");
+        let path = Path::new("/home/alice/music/album/01.flac");
+        assert_eq!(
+            PathStyle::Absolute.apply(path, Some(root)),
+            "/home/alice/music/album/01.flac"
+        );
+        assert_eq!(
+            PathStyle::RelativeToInput.apply(path, Some(root)),
+            "album/01.flac"
+        );
+        assert_eq!(PathStyle::FilenameOnly.apply(path, None), "01.flac");
+        // 没有输入目录或不在输入目录下时保留原路径
+        assert_eq!(
+            PathStyle::RelativeToInput.apply(path, None),
+            "/home/alice/music/album/01.flac"
+        );
+        assert_eq!(
+            PathStyle::RelativeToInput.apply(Path::new("/tmp/a.flac"), Some(root)),
+            "/tmp/a.flac"
+        );
+
+        // 还原实际路径
+        let styler = PathStyler::new(PathStyle::RelativeToInput, Some(root));
+        assert_eq!(styler.restore("album/01.flac").as_deref(), Some(path));
+        assert_eq!(
+            styler.restore("/tmp/a.flac").as_deref(),
+            Some(Path::new("/tmp/a.flac"))
+        );
+        let styler = PathStyler::new(PathStyle::FilenameOnly, Some(root));
+        assert_eq!(styler.restore("01.flac"), None);
+
+        assert_eq!(
+            "relative-to-input".parse::<PathStyle>().unwrap(),
+            PathStyle::RelativeToInput
+        );
+        assert_eq!(
+            "Filename".parse::<PathStyle>().unwrap(),
+            PathStyle::FilenameOnly
+        );
+        assert!("home".parse::<PathStyle>().is_err());
+
+        let config: AnalyzerConfig =
+            toml::from_str("[output]\npath_style = \"relative_to_input\"\n").unwrap();
+        assert_eq!(config.output.path_style, PathStyle::RelativeToInput);
+    }
+
+    #[test]
+    fn test_profile_from_file() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "profile = \"podcast\"\n").unwrap();
+
+        let config = AnalyzerConfig::from_file(temp_file.path()).unwrap();
+        assert_eq!(config.profile, QualityProfile::Podcast);
+        assert_eq!(
+            config.quality_thresholds.lra_excellent_min,
+            QualityThresholds::podcast().lra_excellent_min
+        );
+        assert!(config.validate().is_ok());
+
+        // 只给出部分阈值时，其余阈值取所选预设的值
+        std::fs::write(
+            temp_file.path(),
+            "profile = \"podcast\"\n\n[quality_thresholds]\nlra_poor_max = 2.5\n",
+        )
+        .unwrap();
+        let config = AnalyzerConfig::from_file(temp_file.path()).unwrap();
+        let podcast = QualityThresholds::podcast();
+        assert_eq!(config.quality_thresholds.lra_poor_max, 2.5);
+        assert_eq!(
+            config.quality_thresholds.lra_excellent_min,
+            podcast.lra_excellent_min
+        );
+        assert_eq!(config.quality_thresholds.lra_too_high, podcast.lra_too_high);
+
+        assert!(AnalyzerConfig {
+            quality_thresholds: QualityThresholds::mastering(),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = AnalyzerConfig::default();
@@ -233,6 +1734,74 @@ mod tests {
         assert_eq!(config.verbose, loaded_config.verbose);
     }
 
+    #[test]
+    fn test_config_formats_by_extension() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = AnalyzerConfig::builder()
+            .threads(3)
+            .profile(QualityProfile::Mastering)
+            .build()
+            .unwrap();
+
+        for name in ["config.toml", "config.json", "config.yaml", "config.YML"] {
+            let path = dir.path().join(name);
+            config.save_to_file(&path).unwrap();
+            let loaded = AnalyzerConfig::from_file(&path).unwrap();
+            assert_eq!(loaded.num_threads, Some(3), "{name}");
+            assert_eq!(loaded.profile, QualityProfile::Mastering, "{name}");
+            assert_eq!(
+                loaded.quality_thresholds.peak_clipping_db,
+                QualityThresholds::mastering().peak_clipping_db,
+                "{name}"
+            );
+        }
+
+        // 手写的YAML同样支持只指定预设
+        let yaml_path = dir.path().join("podcast.yaml");
+        std::fs::write(&yaml_path, "profile: podcast\nverbose: true\n").unwrap();
+        let loaded = AnalyzerConfig::from_file(&yaml_path).unwrap();
+        assert!(loaded.verbose);
+        assert_eq!(
+            loaded.quality_thresholds.lra_low_max,
+            QualityThresholds::podcast().lra_low_max
+        );
+
+        let ini_path = dir.path().join("config.ini");
+        std::fs::write(&ini_path, "verbose = true\n").unwrap();
+        assert!(matches!(
+            AnalyzerConfig::from_file(&ini_path),
+            Err(AnalyzerError::ConfigError(_))
+        ));
+        assert!(matches!(
+            config.save_to_file(&ini_path),
+            Err(AnalyzerError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_parse_error_keeps_source() {
+        use std::error::Error;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let toml_path = dir.path().join("broken.toml");
+        std::fs::write(&toml_path, "verbose = \n").unwrap();
+        let error = AnalyzerConfig::from_file(&toml_path).unwrap_err();
+        assert!(matches!(error, AnalyzerError::ConfigParseError { .. }));
+        assert!(error
+            .source()
+            .and_then(|source| source.downcast_ref::<toml::de::Error>())
+            .is_some());
+
+        // 字段类型错误在转换为配置时才发现，同样保留原始错误
+        let json_path = dir.path().join("config.json");
+        std::fs::write(&json_path, r#"{ "verbose": "yes" }"#).unwrap();
+        let error = AnalyzerConfig::from_file(&json_path).unwrap_err();
+        assert!(error
+            .source()
+            .and_then(|source| source.downcast_ref::<serde_json::Error>())
+            .is_some());
+    }
+
     #[test]
     fn test_effective_thread_count() {
         let mut config = AnalyzerConfig::default();
@@ -2,11 +2,16 @@
 //!
 //! 定义了音频分析器中使用的所有错误类型和错误处理机制。
 
+use crate::messages::{self, Locale};
+use crate::types::NotAudioReason;
 use std::fmt;
 
 /// 音频分析器的结果类型
 pub type Result<T> = std::result::Result<T, AnalyzerError>;
 
+/// 被包装的底层错误，通过 [`std::error::Error::source`] 返回
+pub type ErrorSource = Box<dyn std::error::Error + Send + Sync>;
+
 /// 音频分析器错误类型
 #[derive(Debug)]
 pub enum AnalyzerError {
@@ -15,6 +20,8 @@ pub enum AnalyzerError {
 
     /// FFmpeg 执行错误
     FfmpegError {
+        /// 失败的类型，重试与超时处理据此判断，不依赖消息文字
+        kind: FfmpegErrorKind,
         /// 错误消息
         message: String,
         /// 命令输出
@@ -29,17 +36,47 @@ pub enum AnalyzerError {
         extension: Option<String>,
     },
 
+    /// 文件为空或小于 `min_file_size_bytes`，不值得分析
+    FileTooSmall {
+        /// 文件路径
+        path: String,
+        /// 文件大小（字节）
+        size_bytes: u64,
+    },
+
+    /// 文件实际上不是音频（只有封面图片或时长过短），不值得分析
+    NotAudio {
+        /// 文件路径
+        path: String,
+        /// 原因
+        reason: NotAudioReason,
+    },
+
     /// 数据解析错误
+    ///
+    /// 底层错误只通过 [`std::error::Error::source`] 返回，不重复写入错误描述。
     ParseError {
         /// 错误描述
         message: String,
         /// 原始数据
         raw_data: Option<String>,
+        /// 导致解析失败的底层错误（如 JSON、WAV 解码或数值解析错误）
+        source: Option<ErrorSource>,
     },
 
-    /// 配置错误
+    /// 配置错误（配置项取值不合理等，没有底层错误）
     ConfigError(String),
 
+    /// 配置文件无法解析
+    ///
+    /// 与 `ParseError` 一样，解析器给出的行号等细节只通过 `source()` 返回。
+    ConfigParseError {
+        /// 配置文件路径
+        path: String,
+        /// 底层的 TOML/JSON/YAML 解析错误
+        source: ErrorSource,
+    },
+
     /// 依赖项设置错误
     DependencyError(String),
 
@@ -47,42 +84,98 @@ pub enum AnalyzerError {
     Other(String),
 }
 
-impl fmt::Display for AnalyzerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// FFmpeg（及其他子进程）执行失败的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfmpegErrorKind {
+    /// 超过设定的超时时间，被本程序终止
+    Timeout,
+    /// 被信号终止（通常是系统内存不足时被结束）
+    Signal,
+    /// 以非零退出码结束
+    Exit(i32),
+}
+
+impl AnalyzerError {
+    /// 指定语言的错误描述
+    ///
+    /// `Display` 使用当前线程的界面语言（见 [`crate::messages`]），
+    /// 需要固定语言时（如写入日志文件）可以直接调用此方法。
+    pub fn localized(&self, locale: Locale) -> String {
+        let t = |zh, en| locale.pick(zh, en);
         match self {
-            AnalyzerError::Io(err) => write!(f, "I/O 错误: {err}"),
-            AnalyzerError::FfmpegError { message, stderr } => {
-                write!(f, "FFmpeg 执行错误: {message}")?;
+            AnalyzerError::Io(err) => format!("{}: {err}", t("I/O 错误", "I/O error")),
+            AnalyzerError::FfmpegError {
+                message, stderr, ..
+            } => {
+                let mut text = format!("{}: {message}", t("FFmpeg 执行错误", "FFmpeg failed"));
                 if let Some(stderr) = stderr {
-                    write!(f, "\n详细信息: {stderr}")?;
+                    text.push_str(&format!("\n{}: {stderr}", t("详细信息", "Details")));
                 }
-                Ok(())
+                text
             }
             AnalyzerError::UnsupportedFormat { path, extension } => {
-                write!(f, "不支持的文件格式: {path}")?;
+                let mut text = format!(
+                    "{}: {path}",
+                    t("不支持的文件格式", "Unsupported file format")
+                );
                 if let Some(ext) = extension {
-                    write!(f, " (扩展名: {ext})")?;
+                    text.push_str(&format!(" ({}: {ext})", t("扩展名", "extension")));
                 }
-                Ok(())
+                text
             }
-            AnalyzerError::ParseError { message, raw_data } => {
-                write!(f, "数据解析错误: {message}")?;
+            AnalyzerError::FileTooSmall { path, size_bytes } => match locale {
+                Locale::Zh => format!("文件过小，已跳过: {path} ({size_bytes} 字节)"),
+                Locale::En => format!("File too small, skipped: {path} ({size_bytes} bytes)"),
+            },
+            AnalyzerError::NotAudio { path, reason } => match locale {
+                Locale::Zh => format!(
+                    "不是音频文件，已跳过: {path}（{}）",
+                    reason.localized(locale)
+                ),
+                Locale::En => format!(
+                    "Not really audio, skipped: {path} ({})",
+                    reason.localized(locale)
+                ),
+            },
+            AnalyzerError::ParseError {
+                message, raw_data, ..
+            } => {
+                let mut text = format!("{}: {message}", t("数据解析错误", "Parse error"));
                 if let Some(data) = raw_data {
-                    write!(f, "\n原始数据: {data}")?;
+                    text.push_str(&format!("\n{}: {data}", t("原始数据", "Raw data")));
                 }
-                Ok(())
+                text
+            }
+            AnalyzerError::ConfigError(msg) => {
+                format!("{}: {msg}", t("配置错误", "Configuration error"))
+            }
+            AnalyzerError::ConfigParseError { path, .. } => match locale {
+                Locale::Zh => format!("配置错误: 配置文件解析错误 ({path})"),
+                Locale::En => format!("Configuration error: failed to parse config file ({path})"),
+            },
+            AnalyzerError::DependencyError(msg) => {
+                format!("{}: {msg}", t("依赖项错误", "Dependency error"))
             }
-            AnalyzerError::ConfigError(msg) => write!(f, "配置错误: {msg}"),
-            AnalyzerError::DependencyError(msg) => write!(f, "依赖项错误: {msg}"),
-            AnalyzerError::Other(msg) => write!(f, "错误: {msg}"),
+            AnalyzerError::Other(msg) => format!("{}: {msg}", t("错误", "Error")),
         }
     }
 }
 
+impl fmt::Display for AnalyzerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized(messages::locale()))
+    }
+}
+
 impl std::error::Error for AnalyzerError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             AnalyzerError::Io(err) => Some(err),
+            AnalyzerError::ParseError {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            AnalyzerError::ConfigParseError { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
@@ -97,12 +190,42 @@ impl From<std::io::Error> for AnalyzerError {
 impl From<serde_json::Error> for AnalyzerError {
     fn from(err: serde_json::Error) -> Self {
         AnalyzerError::ParseError {
-            message: format!("JSON 解析错误: {err}"),
+            message: messages::text("JSON 解析错误", "JSON parse error").to_string(),
             raw_data: None,
+            source: Some(Box::new(err)),
         }
     }
 }
 
+impl From<csv::Error> for AnalyzerError {
+    fn from(err: csv::Error) -> Self {
+        AnalyzerError::Io(err.into())
+    }
+}
+
+#[cfg(feature = "native-wav")]
+impl From<hound::Error> for AnalyzerError {
+    fn from(err: hound::Error) -> Self {
+        match err {
+            hound::Error::IoError(err) => AnalyzerError::Io(err),
+            err => AnalyzerError::ParseError {
+                message: messages::text("WAV 解码错误", "WAV decoding error").to_string(),
+                raw_data: None,
+                source: Some(Box::new(err)),
+            },
+        }
+    }
+}
+
+impl From<rayon::ThreadPoolBuildError> for AnalyzerError {
+    fn from(err: rayon::ThreadPoolBuildError) -> Self {
+        AnalyzerError::Other(crate::tr!(
+            "创建线程池失败: {err}",
+            "Failed to create the thread pool: {err}"
+        ))
+    }
+}
+
 /// 便捷的错误创建宏
 #[macro_export]
 macro_rules! analyzer_error {
@@ -121,8 +244,70 @@ mod tests {
     #[test]
     fn test_error_display() {
         let err = AnalyzerError::ConfigError("测试错误".to_string());
-        assert!(err.to_string().contains("配置错误"));
-        assert!(err.to_string().contains("测试错误"));
+        let zh = err.localized(Locale::Zh);
+        assert!(zh.contains("配置错误"));
+        assert!(zh.contains("测试错误"));
+        assert_eq!(err.to_string(), err.localized(messages::locale()));
+    }
+
+    #[test]
+    fn test_error_display_english() {
+        let err = AnalyzerError::FileTooSmall {
+            path: "a.flac".to_string(),
+            size_bytes: 0,
+        };
+        assert_eq!(
+            err.localized(Locale::En),
+            "File too small, skipped: a.flac (0 bytes)"
+        );
+
+        let err = AnalyzerError::NotAudio {
+            path: "cover.m4a".to_string(),
+            reason: NotAudioReason::TooShort { duration_secs: 0.5 },
+        };
+        assert_eq!(
+            err.localized(Locale::En),
+            "Not really audio, skipped: cover.m4a (only 0.50 seconds long)"
+        );
+        assert!(err.localized(Locale::Zh).contains("0.50 秒"));
+
+        let err = AnalyzerError::FfmpegError {
+            kind: FfmpegErrorKind::Exit(1),
+            message: "exit 1".to_string(),
+            stderr: Some("boom".to_string()),
+        };
+        assert_eq!(
+            err.localized(Locale::En),
+            "FFmpeg failed: exit 1\nDetails: boom"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_source() {
+        use std::error::Error;
+
+        let json_err = serde_json::from_str::<u32>("x").unwrap_err();
+        let json_text = json_err.to_string();
+        let err: AnalyzerError = json_err.into();
+        assert!(err
+            .source()
+            .and_then(|source| source.downcast_ref::<serde_json::Error>())
+            .is_some());
+        // 底层错误不重复出现在错误描述中
+        assert!(!err.to_string().contains(&json_text));
+
+        let err = AnalyzerError::ConfigParseError {
+            path: "config.toml".to_string(),
+            source: Box::new(serde_json::from_str::<u32>("x").unwrap_err()),
+        };
+        assert_eq!(
+            err.localized(Locale::En),
+            "Configuration error: failed to parse config file (config.toml)"
+        );
+        assert!(!err.to_string().contains(&json_text));
+
+        let err = AnalyzerError::ConfigError("测试错误".to_string());
+        assert!(err.source().is_none());
     }
 
     #[test]
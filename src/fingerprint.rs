@@ -0,0 +1,220 @@
+//! # 声学指纹模块
+//!
+//! 解析FFmpeg `chromaprint` 复用器输出的原始指纹，并提供指纹之间的相似度比较，
+//! 用于在音乐库中找出同一首曲目的不同格式/码率版本。
+//!
+//! 指纹是一串 32 位整数，每个值描述约 0.12 秒音频的频谱特征。
+//! 在 [`crate::AudioMetrics`] 中以十六进制字符串保存（每个值 8 个字符），便于写入JSON。
+
+use crate::error::{AnalyzerError, Result};
+use std::collections::{HashMap, HashSet};
+
+/// 判定为重复文件的默认相似度（0.0-1.0，相同比特所占比例）
+///
+/// 无关的两段音频约有一半比特相同（相似度约 0.5），
+/// 同一录音的不同编码版本通常在 0.9 以上。
+pub const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.85;
+
+/// 比较时允许的最大错位（指纹值个数，约 1 秒），用于容忍片头静音长度的差异
+const MAX_ALIGNMENT_OFFSET: usize = 8;
+
+/// 重叠部分至少占较长指纹的比例，避免很短的片段与长曲目误判为重复
+const MIN_OVERLAP_RATIO: f64 = 0.5;
+
+/// 分桶时保留的高位比特数：同一录音的不同编码版本有大量高位完全相同的值，
+/// 无关音频的值偶然相同的概率很低
+const SUB_FINGERPRINT_BITS: u32 = 24;
+
+/// 两个指纹至少共有多少个子指纹才逐一比较相似度
+const MIN_SHARED_SUB_FINGERPRINTS: usize = 2;
+
+/// 出现在超过这么多个文件中的子指纹（多为静音片段）不参与分桶
+const MAX_BUCKET_FILES: usize = 64;
+
+/// 解析 `chromaprint` 复用器 `-fp_format raw` 输出的原始指纹（小端序 32 位整数）
+pub fn parse_raw(bytes: &[u8]) -> Result<Vec<u32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return Err(AnalyzerError::ParseError {
+            message: crate::tr!(
+                "原始指纹长度 ({} 字节) 不是 4 的倍数",
+                "Raw fingerprint length ({} bytes) is not a multiple of 4",
+                bytes.len()
+            ),
+            raw_data: None,
+            source: None,
+        });
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+/// 将指纹编码为十六进制字符串（每个值 8 个小写十六进制字符）
+pub fn encode(fingerprint: &[u32]) -> String {
+    fingerprint
+        .iter()
+        .map(|value| format!("{value:08x}"))
+        .collect()
+}
+
+/// 解码 [`encode`] 生成的十六进制字符串，格式不正确时返回 `None`
+pub fn decode(text: &str) -> Option<Vec<u32>> {
+    if !text.len().is_multiple_of(8) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(8)
+        .map(|start| u32::from_str_radix(&text[start..start + 8], 16).ok())
+        .collect()
+}
+
+/// 两个指纹的相似度 (0.0-1.0)
+///
+/// 在 ±[`MAX_ALIGNMENT_OFFSET`] 的错位范围内逐一对齐，取重叠部分相同比特比例的最大值。
+/// 任一指纹为空、或重叠部分不足较长指纹的一半时返回 0.0。
+pub fn similarity(a: &[u32], b: &[u32]) -> f64 {
+    let longer = a.len().max(b.len());
+    let min_overlap = ((longer as f64 * MIN_OVERLAP_RATIO).ceil() as usize).max(1);
+
+    let mut best = 0.0f64;
+    for offset in 0..=MAX_ALIGNMENT_OFFSET {
+        for (x, y) in [(a, b), (b, a)] {
+            let Some(x) = x.get(offset..) else {
+                continue;
+            };
+            let overlap = x.len().min(y.len());
+            if overlap < min_overlap {
+                continue;
+            }
+            let differing: u32 = x
+                .iter()
+                .zip(y)
+                .map(|(left, right)| (left ^ right).count_ones())
+                .sum();
+            best = best.max(1.0 - f64::from(differing) / (overlap as f64 * 32.0));
+        }
+    }
+    best
+}
+
+/// 找出值得逐一比较相似度的指纹对 `(i, j)`（`i < j`，按下标排序）
+///
+/// 按子指纹（每个值的高 [`SUB_FINGERPRINT_BITS`] 位）分桶，只保留至少共有
+/// [`MIN_SHARED_SUB_FINGERPRINTS`] 个子指纹、且长度接近的指纹对，避免对整个音乐库两两比较。
+pub(crate) fn candidate_pairs(fingerprints: &[&[u32]]) -> Vec<(usize, usize)> {
+    let mut buckets: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (i, fingerprint) in fingerprints.iter().enumerate() {
+        let keys: HashSet<u32> = fingerprint
+            .iter()
+            .map(|value| value >> (32 - SUB_FINGERPRINT_BITS))
+            .collect();
+        for key in keys {
+            buckets.entry(key).or_default().push(i);
+        }
+    }
+
+    let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+    for files in buckets
+        .values()
+        .filter(|files| files.len() <= MAX_BUCKET_FILES)
+    {
+        for (k, &i) in files.iter().enumerate() {
+            for &j in &files[k + 1..] {
+                *shared.entry((i, j)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = shared
+        .into_iter()
+        .filter(|&((i, j), count)| {
+            count >= MIN_SHARED_SUB_FINGERPRINTS
+                && comparable_lengths(fingerprints[i].len(), fingerprints[j].len())
+        })
+        .map(|(pair, _)| pair)
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+/// 指纹长度是否接近到值得比较（较短的至少为较长的 [`MIN_OVERLAP_RATIO`]）
+pub(crate) fn comparable_lengths(a: usize, b: usize) -> bool {
+    let (short, long) = (a.min(b), a.max(b));
+    short > 0 && short as f64 >= long as f64 * MIN_OVERLAP_RATIO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 可重复的伪随机指纹
+    fn pseudo_random(seed: u32, len: usize) -> Vec<u32> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_raw_and_hex_round_trip() {
+        let bytes = [0x78, 0x56, 0x34, 0x12, 0xff, 0x00, 0x00, 0x00];
+        let fingerprint = parse_raw(&bytes).unwrap();
+        assert_eq!(fingerprint, [0x1234_5678, 0xff]);
+        assert!(parse_raw(&bytes[..5]).is_err());
+
+        let text = encode(&fingerprint);
+        assert_eq!(text, "12345678000000ff");
+        assert_eq!(decode(&text).unwrap(), fingerprint);
+        assert_eq!(decode("1234567"), None);
+        assert_eq!(decode("1234567g"), None);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let track = pseudo_random(1, 200);
+        assert_eq!(similarity(&track, &track), 1.0);
+
+        // 片头多了 3 个值的静音，仍能对齐
+        let mut shifted = vec![0; 3];
+        shifted.extend_from_slice(&track);
+        assert_eq!(similarity(&track, &shifted), 1.0);
+
+        // 有损编码带来的少量比特差异
+        let noisy: Vec<u32> = track.iter().map(|v| v ^ 0b101).collect();
+        let score = similarity(&track, &noisy);
+        assert!(score > DEFAULT_DUPLICATE_THRESHOLD && score < 1.0);
+
+        let other = pseudo_random(2, 200);
+        assert!(similarity(&track, &other) < 0.6);
+
+        // 短片段不与完整曲目匹配
+        assert_eq!(similarity(&track, &track[..50]), 0.0);
+        assert_eq!(similarity(&track, &[]), 0.0);
+        assert!(!comparable_lengths(200, 50));
+        assert!(comparable_lengths(200, 180));
+    }
+
+    #[test]
+    fn test_candidate_pairs() {
+        let track = pseudo_random(1, 200);
+        let noisy: Vec<u32> = track.iter().map(|v| v ^ 0b101).collect();
+        let other = pseudo_random(2, 200);
+        // 所有文件共有的静音值不会使无关的文件成为候选
+        let silence = [0, 0x100];
+        let with_silence = |fingerprint: &[u32]| [fingerprint, &silence].concat();
+        let mut fingerprints = vec![track.clone(), other, noisy, track[..50].to_vec()];
+        fingerprints.extend((3..MAX_BUCKET_FILES as u32 + 3).map(|seed| pseudo_random(seed, 200)));
+        let fingerprints: Vec<Vec<u32>> = fingerprints.iter().map(|fp| with_silence(fp)).collect();
+        let slices: Vec<&[u32]> = fingerprints.iter().map(Vec::as_slice).collect();
+
+        // 与片段的长度相差太多，不作为候选
+        assert_eq!(candidate_pairs(&slices), [(0, 2)]);
+        assert!(candidate_pairs(&[]).is_empty());
+    }
+}
@@ -14,22 +14,65 @@
 //! 本库采用模块化设计，主要包含以下模块：
 //!
 //! - `analyzer`: 核心音频分析功能
+//! - `async_api`: 异步分析接口（`tokio` 特性）
+//! - `cache`: 分析结果缓存
+//! - `compare`: 文件对比
 //! - `config`: 配置管理
+//! - `fingerprint`: 声学指纹与重复文件检测
+//! - `messages`: 界面语言（中文/英文）
+//! - `native`: 原生 WAV 解码（`native-wav` 特性）
+//! - `pipeline`: 完整分析流程
+//! - `progress`: 进度报告
+//! - `quality`: 质量评分
+//! - `report`: 报告生成
+//! - `summary`: 批量统计
 //! - `utils`: 通用工具函数
 //! - `error`: 错误处理
 //! - `types`: 数据类型定义
+//!
+//! ## 日志
+//!
+//! 进度与诊断信息通过 [`log`](https://docs.rs/log) 门面输出，不直接写标准输出：
+//! 每个文件的进度为 `info`，详细诊断为 `debug`，可忽略的错误为 `warn`。
+//! 调用方可接入任意 `log` 后端来捕获或屏蔽这些信息；命令行程序使用 `env_logger`。
 
 pub mod analyzer;
+#[cfg(feature = "tokio")]
+pub mod async_api;
+pub mod cache;
+pub mod compare;
 pub mod config;
 pub mod error;
+pub mod fingerprint;
+pub mod messages;
+#[cfg(feature = "native-wav")]
+pub mod native;
+pub mod pipeline;
+pub mod progress;
+pub mod quality;
+pub mod report;
+pub mod summary;
 pub mod types;
 pub mod utils;
 
 // 重新导出主要的公共API
 pub use analyzer::AudioAnalyzer;
-pub use config::AnalyzerConfig;
-pub use error::{AnalyzerError, Result};
-pub use types::{AudioMetrics, QualityThresholds};
+pub use compare::MetricsDiff;
+pub use config::{
+    AnalyzerConfig, AnalyzerConfigBuilder, ConfigOverrides, OutputFormat, PathStyle,
+    ProfileMatcher, ProfileRule, ThresholdSource,
+};
+pub use error::{AnalyzerError, FfmpegErrorKind, Result};
+pub use messages::Locale;
+#[allow(deprecated)]
+pub use progress::StdoutObserver;
+pub use progress::{LogObserver, ProgressObserver};
+pub use quality::QualityVerdict;
+pub use summary::{AnalysisSummary, MetricKey};
+pub use types::{
+    AnalysisReport, AudioMetrics, EnvironmentReport, NotAudioReason, QualityThresholds,
+    SpectralVerdict,
+};
 
 /// 库版本信息
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,216 @@
+//! # 界面语言模块
+//!
+//! 面向用户的文字（进度、错误信息、命令行提示）通过这里选择中文或英文。
+//! 语言按以下优先级确定：[`with_locale`] 为当前线程临时指定的语言
+//! （分析器据此使用各自的 `AnalyzerConfig::locale`）、[`set_locale`] 设置的进程语言、
+//! 环境变量 `AUDIO_ANALYZER_LANG`、环境变量 `LANG`，都未指定时使用中文。
+//!
+//! 报告文件中的字段名与列名不随语言变化。
+
+use crate::error::AnalyzerError;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// 中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Locale {
+    /// 按语言在中文与英文文字中选择一个
+    pub fn pick<'a>(self, zh: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Locale::Zh => zh,
+            Locale::En => en,
+        }
+    }
+
+    /// 解析 `LANG` 风格的语言标签（如 `zh_CN.UTF-8`、`en_US`、`en`）
+    ///
+    /// 无法识别的值（包括 `C`、`POSIX` 和空字符串）返回 `None`。
+    pub fn from_lang_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::Zh),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    /// 从环境变量确定语言：先看 `AUDIO_ANALYZER_LANG`，再看 `LANG`
+    pub fn from_env() -> Option<Self> {
+        ["AUDIO_ANALYZER_LANG", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find_map(|value| Self::from_lang_tag(&value))
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_lang_tag(s).ok_or_else(|| {
+            AnalyzerError::ConfigError(crate::tr!(
+                "未知的界面语言: {s}（可选: zh, en）",
+                "Unknown language: {s} (expected: zh, en)"
+            ))
+        })
+    }
+}
+
+/// 尚未确定语言时的取值
+const LOCALE_UNSET: u8 = 0;
+
+/// 当前语言（`LOCALE_UNSET` 表示第一次使用时从环境变量确定）
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(LOCALE_UNSET);
+
+thread_local! {
+    /// 当前线程临时使用的语言，优先于进程语言
+    static THREAD_LOCALE: Cell<Option<Locale>> = const { Cell::new(None) };
+}
+
+impl Locale {
+    fn to_u8(self) -> u8 {
+        match self {
+            Locale::Zh => 1,
+            Locale::En => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Locale::Zh),
+            2 => Some(Locale::En),
+            _ => None,
+        }
+    }
+}
+
+/// 设置当前进程的界面语言
+pub fn set_locale(locale: Locale) {
+    CURRENT_LOCALE.store(locale.to_u8(), Ordering::Relaxed);
+}
+
+/// 在当前线程中使用指定语言执行 `f`，结束后恢复原来的语言
+///
+/// `None` 表示沿用原来的语言。分析器在各个接口中以 `AnalyzerConfig::locale` 调用此函数，
+/// 同一进程中不同语言的分析器互不影响。
+pub fn with_locale<R>(locale: Option<Locale>, f: impl FnOnce() -> R) -> R {
+    /// 离开作用域（包括 panic）时恢复原来的线程语言
+    struct Restore(Option<Locale>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            THREAD_LOCALE.set(self.0);
+        }
+    }
+
+    let Some(locale) = locale else {
+        return f();
+    };
+    let _restore = Restore(THREAD_LOCALE.replace(Some(locale)));
+    f()
+}
+
+/// 设置当前线程的语言（`None` 表示使用进程语言），用于分析器自己创建的工作线程
+pub(crate) fn set_thread_locale(locale: Option<Locale>) {
+    THREAD_LOCALE.set(locale);
+}
+
+/// 当前线程的界面语言
+///
+/// 没有通过 [`with_locale`] 指定时使用进程语言；未调用过 [`set_locale`] 时按环境变量确定，
+/// 默认为中文。
+pub fn locale() -> Locale {
+    if let Some(locale) = THREAD_LOCALE.get() {
+        return locale;
+    }
+    if let Some(locale) = Locale::from_u8(CURRENT_LOCALE.load(Ordering::Relaxed)) {
+        return locale;
+    }
+    let locale = Locale::from_env().unwrap_or_default();
+    // 并发时以先设置的为准
+    let _ = CURRENT_LOCALE.compare_exchange(
+        LOCALE_UNSET,
+        locale.to_u8(),
+        Ordering::Relaxed,
+        Ordering::Relaxed,
+    );
+    Locale::from_u8(CURRENT_LOCALE.load(Ordering::Relaxed)).unwrap_or(locale)
+}
+
+/// 按当前语言在中文与英文文字中选择一个
+pub fn text(zh: &'static str, en: &'static str) -> &'static str {
+    locale().pick(zh, en)
+}
+
+/// 按当前语言选择中文或英文的格式字符串并格式化
+///
+/// ```
+/// use audio_analyzer_ultimate::tr;
+///
+/// let count = 3;
+/// let message = tr!("成功分析 {count} 个文件", "Analyzed {count} files");
+/// assert!(message.contains('3'));
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($zh:literal, $en:literal $(, $arg:expr)* $(,)?) => {
+        match $crate::messages::locale() {
+            $crate::messages::Locale::Zh => format!($zh $(, $arg)*),
+            $crate::messages::Locale::En => format!($en $(, $arg)*),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_lang_tag() {
+        assert_eq!(Locale::from_lang_tag("zh_CN.UTF-8"), Some(Locale::Zh));
+        assert_eq!(Locale::from_lang_tag("en_US.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::from_lang_tag("EN"), Some(Locale::En));
+        assert_eq!(Locale::from_lang_tag("zh-TW"), Some(Locale::Zh));
+        assert_eq!(Locale::from_lang_tag("C"), None);
+        assert_eq!(Locale::from_lang_tag(""), None);
+        assert!("fr".parse::<Locale>().is_err());
+        assert_eq!("en".parse::<Locale>().unwrap(), Locale::En);
+    }
+
+    #[test]
+    fn test_pick() {
+        assert_eq!(Locale::Zh.pick("错误", "error"), "错误");
+        assert_eq!(Locale::En.pick("错误", "error"), "error");
+    }
+
+    #[test]
+    fn test_with_locale_is_scoped_to_thread() {
+        let outer = locale();
+        with_locale(Some(Locale::En), || {
+            assert_eq!(locale(), Locale::En);
+            assert_eq!(text("错误", "error"), "error");
+            with_locale(Some(Locale::Zh), || assert_eq!(locale(), Locale::Zh));
+            assert_eq!(locale(), Locale::En);
+            // 其他线程不受影响
+            std::thread::spawn(move || assert_eq!(locale(), outer))
+                .join()
+                .unwrap();
+        });
+        assert_eq!(locale(), outer);
+        with_locale(None, || assert_eq!(locale(), outer));
+    }
+}
@@ -0,0 +1,365 @@
+//! # 原生 WAV 解码模块
+//!
+//! 启用 `native-wav` 特性后，未压缩的 WAV 文件直接用 `hound` 读取样本，
+//! 在 Rust 中计算与 FFmpeg `astats` Overall 块一致的电平统计，
+//! 分析时FFmpeg的滤镜图因此省去整体 `astats` 分支。
+//!
+//! 原生路径只计算电平类统计（峰值、RMS、短时RMS范围、直流偏移、波峰因数、
+//! 削波估算所需的峰值计数与各声道统计）；LRA、综合响度、高频、静音与相位分析
+//! 以及格式信息仍由同一次FFmpeg调用得到，结果与不启用该特性时字段相同。
+
+use crate::error::{AnalyzerError, Result};
+use crate::types::{AudioStats, ChannelStats};
+use hound::{SampleFormat, WavReader};
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// 短时RMS的窗口长度（秒），与 astats 的默认 `length` 相同
+const RMS_WINDOW_SECS: f64 = 0.05;
+
+/// 判断文件是否可以走原生 WAV 路径（按扩展名）
+pub fn is_native_wav(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+}
+
+/// 直接解码 WAV 文件，计算与 astats Overall 块相同定义的电平统计
+///
+/// 解码失败（如 WAVE_FORMAT_EXTENSIBLE 中不支持的编码）时返回错误，
+/// 调用方应回退到FFmpeg的 `astats`。
+pub fn wav_level_stats(path: &Path) -> Result<AudioStats> {
+    let reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    let channels = usize::from(spec.channels);
+    if channels == 0 {
+        return Err(AnalyzerError::ParseError {
+            message: crate::messages::text("WAV 文件声道数为 0", "WAV file has 0 channels")
+                .to_string(),
+            raw_data: None,
+            source: None,
+        });
+    }
+
+    let window = ((f64::from(spec.sample_rate) * RMS_WINDOW_SECS).round() as usize).max(1);
+    let mut accumulator = LevelAccumulator::new(channels, window);
+    match spec.sample_format {
+        SampleFormat::Float => {
+            for sample in reader.into_samples::<f32>() {
+                accumulator.push(f64::from(sample?));
+            }
+        }
+        SampleFormat::Int => {
+            // 整数样本归一化到 [-1.0, 1.0)，与FFmpeg的满幅定义一致
+            let scale = 2f64.powi(i32::from(spec.bits_per_sample) - 1);
+            for sample in reader.into_samples::<i32>() {
+                accumulator.push(f64::from(sample?) / scale);
+            }
+        }
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// 声道中取得最大值（或最小值）的采样计数
+///
+/// 与 astats 一样分别跟踪最大值与最小值，遇到新的极值时重新计数；
+/// `runs` 为连续取得极值的段数，用于计算平顶系数。
+#[derive(Debug, Clone, Copy)]
+struct ExtremeCount {
+    value: f64,
+    count: u64,
+    runs: u64,
+}
+
+impl ExtremeCount {
+    fn new(value: f64) -> Self {
+        Self {
+            value,
+            count: 0,
+            runs: 0,
+        }
+    }
+
+    /// 记录一个采样；`previous` 为同一声道的上一个采样
+    fn push(&mut self, sample: f64, previous: Option<f64>, is_new_extreme: bool) {
+        if is_new_extreme {
+            *self = Self {
+                value: sample,
+                count: 1,
+                runs: 1,
+            };
+        } else if sample == self.value {
+            self.count += 1;
+            if previous != Some(sample) {
+                self.runs += 1;
+            }
+        }
+    }
+}
+
+/// 单个声道的累计值
+#[derive(Debug, Clone)]
+struct ChannelLevels {
+    max: ExtremeCount,
+    min: ExtremeCount,
+    sum: f64,
+    sum_squares: f64,
+    count: u64,
+    previous: Option<f64>,
+    /// 最近一个窗口内各采样的平方
+    window: VecDeque<f64>,
+    window_sum: f64,
+    window_rms_max: Option<f64>,
+    window_rms_min: Option<f64>,
+}
+
+impl ChannelLevels {
+    fn new(window_len: usize) -> Self {
+        Self {
+            max: ExtremeCount::new(f64::NEG_INFINITY),
+            min: ExtremeCount::new(f64::INFINITY),
+            sum: 0.0,
+            sum_squares: 0.0,
+            count: 0,
+            previous: None,
+            window: VecDeque::with_capacity(window_len),
+            window_sum: 0.0,
+            window_rms_max: None,
+            window_rms_min: None,
+        }
+    }
+
+    fn push(&mut self, sample: f64, window_len: usize) {
+        self.max
+            .push(sample, self.previous, sample > self.max.value);
+        self.min
+            .push(sample, self.previous, sample < self.min.value);
+        self.previous = Some(sample);
+
+        self.sum += sample;
+        self.sum_squares += sample * sample;
+        self.count += 1;
+
+        // 滑动窗口的短时RMS，窗口填满后才开始统计
+        self.window.push_back(sample * sample);
+        self.window_sum += sample * sample;
+        if self.window.len() > window_len {
+            self.window_sum -= self.window.pop_front().unwrap_or_default();
+        }
+        if self.window.len() == window_len {
+            let rms = (self.window_sum.max(0.0) / window_len as f64).sqrt();
+            self.window_rms_max = Some(self.window_rms_max.map_or(rms, |max| max.max(rms)));
+            self.window_rms_min = Some(self.window_rms_min.map_or(rms, |min| min.min(rms)));
+        }
+    }
+
+    fn peak(&self) -> f64 {
+        self.max.value.abs().max(self.min.value.abs())
+    }
+
+    fn rms(&self) -> f64 {
+        (self.sum_squares / self.count.max(1) as f64).sqrt()
+    }
+
+    /// 取得峰值电平（最大值或最小值）的采样数与连续段数
+    fn peak_count_and_runs(&self) -> (u64, u64) {
+        let peak = self.peak();
+        [self.max, self.min]
+            .iter()
+            .filter(|extreme| extreme.value.abs() == peak)
+            .fold((0, 0), |(count, runs), extreme| {
+                (count + extreme.count, runs + extreme.runs)
+            })
+    }
+}
+
+/// 按交错顺序逐个接收样本，累计各声道的峰值与能量
+struct LevelAccumulator {
+    channels: Vec<ChannelLevels>,
+    next_channel: usize,
+    window_len: usize,
+}
+
+impl LevelAccumulator {
+    fn new(channels: usize, window_len: usize) -> Self {
+        Self {
+            channels: vec![ChannelLevels::new(window_len); channels],
+            next_channel: 0,
+            window_len,
+        }
+    }
+
+    fn push(&mut self, sample: f64) {
+        self.channels[self.next_channel].push(sample, self.window_len);
+        self.next_channel = (self.next_channel + 1) % self.channels.len();
+    }
+
+    /// 计算与 astats Overall 块相同定义的统计值
+    fn finish(self) -> AudioStats {
+        let mut stats = AudioStats::new();
+        let total_count: u64 = self.channels.iter().map(|levels| levels.count).sum();
+        if total_count == 0 {
+            return stats;
+        }
+
+        let peak = self
+            .channels
+            .iter()
+            .map(ChannelLevels::peak)
+            .fold(0.0, f64::max);
+        let sum: f64 = self.channels.iter().map(|levels| levels.sum).sum();
+        let sum_squares: f64 = self.channels.iter().map(|levels| levels.sum_squares).sum();
+
+        stats.peak_db = Some(amplitude_db(peak));
+        stats.rms_db = Some(amplitude_db((sum_squares / total_count as f64).sqrt()));
+        stats.dc_offset = Some(sum / total_count as f64);
+        stats.crest_factor_db = stats.peak_db.zip(stats.rms_db).map(|(p, r)| p - r);
+        stats.sample_count = Some(total_count / self.channels.len() as u64);
+
+        // 短时RMS：整体最大值取各声道最大值，最小值取各声道最小值；
+        // 与FFmpeg一样，完全静音的窗口为 -inf，此时没有最小值
+        stats.rms_peak_db = self
+            .channels
+            .iter()
+            .filter_map(|levels| levels.window_rms_max)
+            .reduce(f64::max)
+            .map(amplitude_db);
+        stats.rms_trough_db = self
+            .channels
+            .iter()
+            .filter_map(|levels| levels.window_rms_min)
+            .reduce(f64::min)
+            .filter(|&rms| rms > 0.0)
+            .map(amplitude_db);
+
+        // 峰值计数取各声道平均；平顶系数为峰值处连续段的平均长度 (dB)，孤立的峰值为 0 dB
+        let (peak_count, peak_runs) = self
+            .channels
+            .iter()
+            .map(ChannelLevels::peak_count_and_runs)
+            .fold((0, 0), |(count, runs), (c, r)| (count + c, runs + r));
+        stats.peak_count = Some(peak_count as f64 / self.channels.len() as f64);
+        stats.flat_factor_db =
+            (peak_runs > 0).then(|| 20.0 * (peak_count as f64 / peak_runs as f64).log10());
+
+        stats.per_channel = self
+            .channels
+            .iter()
+            .zip(1..)
+            .map(|(levels, channel)| ChannelStats {
+                channel,
+                peak_db: Some(amplitude_db(levels.peak())),
+                rms_db: Some(amplitude_db(levels.rms())),
+            })
+            .collect();
+        stats
+    }
+}
+
+/// 线性幅度转换为 dB；完全静音时与FFmpeg一样为 `-inf`，这里以静音标记值代替
+fn amplitude_db(amplitude: f64) -> f64 {
+    if amplitude > 0.0 {
+        20.0 * amplitude.log10()
+    } else {
+        crate::types::SILENT_BAND_DB
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{WavSpec, WavWriter};
+
+    /// 写出幅度为 `amplitude` 的 1 kHz 正弦波立体声 WAV，右声道为左声道的一半
+    fn write_sine_wav(path: &Path, amplitude: f64) {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(path, spec).unwrap();
+        for n in 0..48000 {
+            let value =
+                amplitude * (2.0 * std::f64::consts::PI * 1000.0 * f64::from(n) / 48000.0).sin();
+            writer.write_sample((value * 32767.0) as i16).unwrap();
+            writer.write_sample((value * 0.5 * 32767.0) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_wav_level_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sine.wav");
+        write_sine_wav(&path, 0.5);
+
+        let stats = wav_level_stats(&path).unwrap();
+        assert_eq!(stats.sample_count, Some(48000));
+
+        // 左声道: 峰值 -6.02 dB，RMS -9.03 dB；右声道低 6.02 dB
+        let left = &stats.per_channel[0];
+        assert!((left.peak_db.unwrap() + 6.02).abs() < 0.01);
+        assert!((left.rms_db.unwrap() + 9.03).abs() < 0.01);
+        let right = &stats.per_channel[1];
+        assert!((right.peak_db.unwrap() + 12.04).abs() < 0.01);
+
+        // 整体峰值取两声道最大值，整体RMS为全部样本的均方根
+        assert!((stats.peak_db.unwrap() + 6.02).abs() < 0.01);
+        let overall_rms = 20.0 * ((0.125 + 0.03125) / 2.0f64).sqrt().log10();
+        assert!((stats.rms_db.unwrap() - overall_rms).abs() < 0.01);
+        assert!(stats.dc_offset.unwrap().abs() < 1e-4);
+
+        // 50 ms 窗口恰好包含整数个周期，短时RMS即各声道的RMS
+        assert!((stats.rms_peak_db.unwrap() + 9.03).abs() < 0.01);
+        assert!((stats.rms_trough_db.unwrap() + 15.05).abs() < 0.01);
+
+        // 正弦波每个周期只在波峰和波谷各取得一次峰值，不算削波
+        assert_eq!(stats.peak_count, Some(2000.0));
+        assert_eq!(stats.flat_factor_db, Some(0.0));
+        assert_eq!(stats.estimate_clipped_samples(-6.5), Some(0));
+    }
+
+    #[test]
+    fn test_wav_level_stats_clipping_and_silence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clipped.wav");
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 1000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        // 100 ms 静音，随后每 10 个采样中有 4 个连续削波在满幅
+        for _ in 0..100 {
+            writer.write_sample(0i16).unwrap();
+        }
+        for n in 0..1000 {
+            let value = if n % 10 < 4 { i16::MAX } else { 1000 };
+            writer.write_sample(value).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let stats = wav_level_stats(&path).unwrap();
+        assert!(stats.peak_db.unwrap() > -0.01);
+        assert_eq!(stats.peak_count, Some(400.0));
+        // 平均每段连续 4 个采样
+        assert!((stats.flat_factor_db.unwrap() - 20.0 * 4f64.log10()).abs() < 1e-9);
+        assert_eq!(stats.estimate_clipped_samples(-0.1), Some(400));
+        // 含完全静音的窗口时与FFmpeg一样没有短时RMS最小值
+        assert_eq!(stats.rms_trough_db, None);
+        assert!(stats.rms_peak_db.is_some());
+    }
+
+    #[test]
+    fn test_wav_level_stats_rejects_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.wav");
+        std::fs::write(&path, b"not a wav file").unwrap();
+        assert!(wav_level_stats(&path).is_err());
+
+        assert!(is_native_wav(Path::new("a.WAV")));
+        assert!(!is_native_wav(Path::new("a.flac")));
+    }
+}
@@ -0,0 +1,305 @@
+//! # 分析流程模块
+//!
+//! 将"扫描 → 分析 → 写出报告"的完整流程封装为 [`AudioAnalyzer::run`]，
+//! 命令行程序与集成测试共用同一实现。
+
+use crate::analyzer::AudioAnalyzer;
+use crate::config::{OutputFormat, PathStyle, PathStyler};
+use crate::error::Result;
+use crate::messages;
+use crate::report;
+use crate::summary::AnalysisSummary;
+use crate::types::{AnalysisReport, AudioMetrics};
+use crate::utils::fs_utils::{self, ScanReport};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+impl AudioAnalyzer {
+    /// 分析 `input`（单个文件或目录），并按 `output` 配置将报告写入 `output_dir`
+    ///
+    /// - `output.overwrite` 为 `false` 时，在分析开始前检查所有输出文件
+    /// - NDJSON 在每个文件完成后立即追加一行（按完成顺序）
+    /// - JSON 与 CSV 在全部文件完成后以原子方式写出，只包含分析成功的文件
+    /// - `resume` 为 `true` 时沿用已有JSON中的结果，只分析其余文件（见 [`crate::AnalyzerConfig::resume`]）
+    /// - 报告中的文件路径按 `output.path_style` 改写，相对路径以 `input` 为基准；返回的报告保留实际路径
+    ///
+    /// 输入为单个文件且格式不受支持时，该文件出现在报告的失败列表中。
+    /// 需要先调用 [`AudioAnalyzer::initialize_dependencies`]。
+    pub fn run(&self, input: &Path, output_dir: &Path) -> Result<AnalysisReport> {
+        self.run_pipeline(input, output_dir, None)
+    }
+
+    /// 可取消的 [`AudioAnalyzer::run`]
+    ///
+    /// `cancel` 置位后跳过尚未开始的文件，已完成的结果照常写出，
+    /// 被跳过的文件列在报告的 `skipped` 中。
+    pub fn run_cancellable(
+        &self,
+        input: &Path,
+        output_dir: &Path,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<AnalysisReport> {
+        self.run_pipeline(input, output_dir, Some(&cancel))
+    }
+
+    /// 列出 [`AudioAnalyzer::run`] 将会分析的文件及其总大小（字节），不调用FFmpeg
+    ///
+    /// 扫描规则与实际分析相同（扩展名、排除模式、递归深度等），
+    /// 可用于在长时间分析前确认范围，或排查某些文件为何没有被选中。
+    /// 没有匹配的文件时返回空列表；不需要初始化依赖项。
+    pub fn dry_run(&self, input: &Path) -> Result<(Vec<PathBuf>, u64)> {
+        messages::with_locale(self.config().locale, || {
+            let config = self.config();
+            let files = if input.is_file() {
+                if fs_utils::is_supported_audio_file(input, &config.supported_extensions) {
+                    vec![input.to_path_buf()]
+                } else {
+                    Vec::new()
+                }
+            } else {
+                fs_utils::scan_audio_files_with_options(
+                    input,
+                    &config.supported_extensions,
+                    &config.scan_options()?,
+                )?
+            };
+            let total_bytes = fs_utils::total_file_size(&files);
+            Ok((files, total_bytes))
+        })
+    }
+
+    /// 扫描目录并返回详细的扫描结果，不调用FFmpeg
+    ///
+    /// 选中的文件与 [`AudioAnalyzer::dry_run`] 相同，另外统计扩展名不受支持而跳过的文件数，
+    /// 并列出无法读取的条目及原因，用于排查权限问题或意料之外的扩展名过滤。
+    pub fn scan_report(&self, dir: &Path) -> Result<ScanReport> {
+        messages::with_locale(self.config().locale, || {
+            let config = self.config();
+            Ok(fs_utils::scan_audio_files_report(
+                dir,
+                &config.supported_extensions,
+                &config.scan_options()?,
+            ))
+        })
+    }
+
+    /// 分析给定的文件列表（不扫描目录），并按 `output` 配置将报告写入 `output_dir`
+    ///
+    /// 不存在或格式不受支持的路径出现在报告的失败列表中。
+    /// `cancel` 的含义与 [`AudioAnalyzer::run_cancellable`] 相同。
+    pub fn run_files(
+        &self,
+        files: &[PathBuf],
+        output_dir: &Path,
+        cancel: Option<Arc<AtomicBool>>,
+    ) -> Result<AnalysisReport> {
+        messages::with_locale(self.config().locale, || {
+            self.run_batch_with_reports(files, output_dir, None, cancel.as_deref())
+        })
+    }
+
+    fn run_pipeline(
+        &self,
+        input: &Path,
+        output_dir: &Path,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<AnalysisReport> {
+        messages::with_locale(self.config().locale, || {
+            let (files, root) = if input.is_file() {
+                (vec![input.to_path_buf()], input.parent())
+            } else {
+                (self.scan_directory(input)?, Some(input))
+            };
+            self.run_batch_with_reports(&files, output_dir, root, cancel)
+        })
+    }
+
+    /// `root` 为 `output.path_style` 计算相对路径的基准目录
+    fn run_batch_with_reports(
+        &self,
+        files: &[PathBuf],
+        output_dir: &Path,
+        root: Option<&Path>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<AnalysisReport> {
+        let config = self.config();
+        let styler = PathStyler::new(config.output.path_style, root);
+        let report_path = |path: &Path| styler.apply(path);
+
+        // 在分析开始前检查，避免长时间分析后才发现无法写出报告
+        fs_utils::ensure_dir_exists(output_dir)?;
+        let outputs = config.output.output_paths(output_dir);
+        for (_, path) in &outputs {
+            report::check_overwrite(path, config)?;
+        }
+
+        // 断点续跑：沿用已有结果中大小未变的完整结果，只分析其余文件。
+        // 须在重新创建NDJSON之前读取，中断的运行只有NDJSON中留有结果。
+        let ndjson_path = output_path(&outputs, OutputFormat::Ndjson);
+        let (previous, pending, resumed) = if config.resume {
+            let json_path = config.output.path_for(OutputFormat::Json, output_dir);
+            let previous = read_previous_results(&json_path, ndjson_path)?;
+            plan_resume(previous, files, &styler)
+        } else {
+            (Vec::new(), files.to_vec(), Vec::new())
+        };
+
+        let ndjson = ndjson_path
+            .map(|path| File::create(path).map(Mutex::new))
+            .transpose()?;
+        // 沿用的结果先写入新的NDJSON，使其与JSON一样完整
+        if let Some(writer) = &ndjson {
+            if let Ok(mut writer) = writer.lock() {
+                report::write_ndjson(&previous, config, &mut *writer)?;
+            }
+        }
+        let write_ndjson_line = |metrics: &AudioMetrics| {
+            let Some(writer) = &ndjson else {
+                return;
+            };
+            let metrics = styled_metrics(metrics, &styler);
+            if let Ok(mut writer) = writer.lock() {
+                if let Err(e) = report::write_ndjson(iter::once(metrics), config, &mut *writer) {
+                    log::warn!(
+                        "{}",
+                        crate::tr!(
+                            "警告: 写出NDJSON失败: {e}",
+                            "Warning: failed to write NDJSON: {e}"
+                        )
+                    );
+                }
+            }
+        };
+
+        let mut report = self.run_batch(&pending, cancel, Some(&write_ndjson_line))?;
+        report.succeeded.splice(0..0, previous);
+        report.resumed = resumed;
+
+        let succeeded: Cow<[AudioMetrics]> = match styler.style() {
+            PathStyle::Absolute => Cow::Borrowed(&report.succeeded),
+            _ => report
+                .succeeded
+                .iter()
+                .map(|metrics| styled_metrics(metrics, &styler).into_owned())
+                .collect(),
+        };
+        if let Some(path) = output_path(&outputs, OutputFormat::Json) {
+            report::write_json_file(path, &succeeded, config)?;
+        }
+        if let Some(path) = output_path(&outputs, OutputFormat::Csv) {
+            report::write_csv_file(path, &succeeded, config)?;
+        }
+        let summary_path = output_path(&outputs, OutputFormat::Summary);
+        let markdown_path = output_path(&outputs, OutputFormat::Markdown);
+        if summary_path.is_some() || markdown_path.is_some() {
+            let mut summary = AnalysisSummary::from_report(&report, self.profile_matcher());
+            for failure in &mut summary.failures {
+                failure.path = report_path(Path::new(&failure.path));
+            }
+            if let Some(path) = summary_path {
+                report::write_summary_file(path, &summary, config)?;
+            }
+            if let Some(path) = markdown_path {
+                report::write_markdown_file(path, &summary, &succeeded, config)?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// 读取上一次运行留下的结果：JSON（完整运行结束时写出）与NDJSON（每个文件完成后追加）
+///
+/// 两者都有同一文件的结果时以NDJSON为准，因为中断的运行只更新了NDJSON。
+/// 文件不存在时视为没有结果。
+fn read_previous_results(
+    json_path: &Path,
+    ndjson_path: Option<&Path>,
+) -> Result<Vec<AudioMetrics>> {
+    let mut previous = if json_path.is_file() {
+        report::read_json_file(json_path)?
+    } else {
+        Vec::new()
+    };
+
+    if let Some(path) = ndjson_path.filter(|path| path.is_file()) {
+        let mut positions: HashMap<String, usize> = previous
+            .iter()
+            .enumerate()
+            .map(|(index, metrics)| (metrics.file_path.clone(), index))
+            .collect();
+        for metrics in report::read_ndjson_file(path)? {
+            match positions.get(&metrics.file_path) {
+                Some(&index) => previous[index] = metrics,
+                None => {
+                    positions.insert(metrics.file_path.clone(), previous.len());
+                    previous.push(metrics);
+                }
+            }
+        }
+    }
+    Ok(previous)
+}
+
+/// 按断点续跑的规则划分文件
+///
+/// 返回 `(沿用的已有结果, 需要分析的文件, 沿用结果的文件)`。已有结果中不在 `files` 里的条目原样保留；
+/// 文件大小与记录不一致或结果不完整（超时得到的部分结果，或缺少核心指标）的文件重新分析，
+/// 其旧结果被丢弃。
+///
+/// 已有结果中的路径是报告中的写法，先由 `styler` 还原为实际路径再与 `files` 匹配，
+/// 沿用的结果因此同样通过 [`AudioMetrics::path`] 给出实际路径。
+fn plan_resume(
+    mut previous: Vec<AudioMetrics>,
+    files: &[PathBuf],
+    styler: &PathStyler,
+) -> (Vec<AudioMetrics>, Vec<PathBuf>, Vec<PathBuf>) {
+    for metrics in &mut previous {
+        if metrics.source_path.is_none() {
+            metrics.source_path = styler.restore(&metrics.file_path);
+        }
+    }
+
+    let recorded_sizes: HashMap<&Path, u64> = previous
+        .iter()
+        .filter(|metrics| !metrics.partial && metrics.is_complete())
+        .map(|metrics| (metrics.path(), metrics.file_size_bytes))
+        .collect();
+
+    let (resumed, pending): (Vec<PathBuf>, Vec<PathBuf>) =
+        files.iter().cloned().partition(|file| {
+            let current_size = fs::metadata(file).ok().map(|metadata| metadata.len());
+            current_size.is_some() && recorded_sizes.get(file.as_path()).copied() == current_size
+        });
+
+    let pending_paths: HashSet<&Path> = pending.iter().map(PathBuf::as_path).collect();
+    let previous = previous
+        .into_iter()
+        .filter(|metrics| !pending_paths.contains(metrics.path()))
+        .collect();
+
+    (previous, pending, resumed)
+}
+
+/// 按 `path_style` 改写报告中的文件路径，分析结果本身保持不变
+fn styled_metrics<'a>(metrics: &'a AudioMetrics, styler: &PathStyler) -> Cow<'a, AudioMetrics> {
+    if styler.style() == PathStyle::Absolute {
+        return Cow::Borrowed(metrics);
+    }
+    let mut styled = metrics.clone();
+    styled.file_path = styler.apply(metrics.path());
+    Cow::Owned(styled)
+}
+
+/// 查找指定格式的输出路径
+fn output_path(outputs: &[(OutputFormat, PathBuf)], format: OutputFormat) -> Option<&Path> {
+    outputs
+        .iter()
+        .find(|(candidate, _)| *candidate == format)
+        .map(|(_, path)| path.as_path())
+}
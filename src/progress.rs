@@ -0,0 +1,128 @@
+//! # 进度报告模块
+//!
+//! 定义批量分析过程中的进度回调接口，使库的调用方（命令行、GUI等）
+//! 可以自行决定如何展示进度。
+
+use crate::error::AnalyzerError;
+use crate::messages;
+use crate::types::{AnalysisProgress, AudioMetrics};
+use crate::utils::fs_utils;
+use log::info;
+use std::path::Path;
+
+/// 批量分析进度观察者
+///
+/// 回调在分析线程池中并发调用，实现需自行保证线程安全。
+/// 所有方法均有空的默认实现，只需覆盖关心的事件。
+pub trait ProgressObserver: Send + Sync {
+    /// 开始分析某个文件时调用
+    fn on_file_start(&self, _progress: &AnalysisProgress) {}
+
+    /// 文件分析成功后调用
+    fn on_file_done(&self, _progress: &AnalysisProgress, _metrics: &AudioMetrics) {}
+
+    /// 文件分析失败后调用
+    fn on_file_error(&self, _progress: &AnalysisProgress, _path: &Path, _error: &AnalyzerError) {}
+}
+
+/// 以 `info` 级别记录进度的观察者（`show_progress` 启用时的默认行为）
+///
+/// 输出去向由调用方初始化的 `log` 后端决定，未初始化任何后端时不输出。
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogObserver;
+
+/// [`LogObserver`] 的旧名称
+#[deprecated(note = "已改名为 LogObserver，进度改为通过 log 输出而不是直接写标准输出")]
+pub type StdoutObserver = LogObserver;
+
+impl ProgressObserver for LogObserver {
+    fn on_file_start(&self, progress: &AnalysisProgress) {
+        info!(
+            "[{}/{}] {}: {}",
+            progress.current_file,
+            progress.total_files,
+            messages::text("正在处理", "Processing"),
+            fs_utils::get_display_name(&progress.current_path)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl ProgressObserver for RecordingObserver {
+        fn on_file_start(&self, progress: &AnalysisProgress) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {}", progress.current_path));
+        }
+    }
+
+    /// 记录所有日志的后端（进程内只能设置一次）
+    struct CaptureLogger {
+        lines: Mutex<Vec<String>>,
+    }
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.lines
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger {
+        lines: Mutex::new(Vec::new()),
+    };
+
+    #[test]
+    fn test_log_observer() {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let progress = AnalysisProgress {
+            current_file: 3,
+            total_files: 7,
+            current_path: "/music/log_observer.flac".to_string(),
+            completed_files: 2,
+        };
+        LogObserver.on_file_start(&progress);
+
+        let lines = LOGGER.lines.lock().unwrap();
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("INFO [3/7]") && line.ends_with("log_observer.flac")));
+    }
+
+    #[test]
+    fn test_default_methods_are_optional() {
+        let observer = RecordingObserver::default();
+        let progress = AnalysisProgress {
+            current_file: 1,
+            total_files: 1,
+            current_path: "a.flac".to_string(),
+            completed_files: 1,
+        };
+        let metrics = AudioMetrics::new("a.flac".to_string(), 0);
+
+        observer.on_file_start(&progress);
+        observer.on_file_done(&progress, &metrics);
+
+        assert_eq!(*observer.events.lock().unwrap(), vec!["start a.flac"]);
+    }
+}
@@ -0,0 +1,308 @@
+//! # 质量评分模块
+//!
+//! 完全基于 [`QualityThresholds`] 计算 0-100 的质量分数，无需Python分析步骤。
+//!
+//! 分数由三部分加权组成：动态范围 (LRA)、峰值余量与频谱完整性。
+//! 每部分先按阈值分级得到 0.0-1.0 的得分系数，再乘以对应权重；
+//! 缺失的指标不得分。
+//!
+//! [`QualityVerdict`] 在分数之上给出可匹配的综合判定，供下游代码过滤与分类。
+
+use crate::error::AnalyzerError;
+use crate::types::{AudioMetrics, QualityThresholds, SpectralVerdict};
+use serde::{Deserialize, Serialize};
+
+/// 动态范围 (LRA) 在总分中的权重
+pub const LRA_WEIGHT: f64 = 40.0;
+
+/// 峰值余量（削波风险）在总分中的权重
+pub const PEAK_WEIGHT: f64 = 30.0;
+
+/// 频谱完整性（是否疑似转码）在总分中的权重
+pub const SPECTRUM_WEIGHT: f64 = 30.0;
+
+impl AudioMetrics {
+    /// 计算 0-100 的质量分数
+    ///
+    /// 总分 = LRA得分 × [`LRA_WEIGHT`] + 峰值得分 × [`PEAK_WEIGHT`]
+    /// + 频谱得分 × [`SPECTRUM_WEIGHT`]，四舍五入取整。
+    pub fn quality_score(&self, thresholds: &QualityThresholds) -> i32 {
+        let lra = self.lra.map_or(0.0, |lra| lra_factor(lra, thresholds));
+        let peak = self
+            .peak_amplitude_db
+            .map_or(0.0, |peak| peak_factor(peak, thresholds));
+        let spectrum = spectrum_factor(self.spectral_verdict(thresholds));
+
+        let score = lra * LRA_WEIGHT + peak * PEAK_WEIGHT + spectrum * SPECTRUM_WEIGHT;
+        (score.round() as i32).clamp(0, 100)
+    }
+
+    /// 判定所需的核心指标是否缺失过多
+    ///
+    /// 18kHz 以上RMS、LRA 与峰值三项中缺少两项及以上时成立，与Python分析模块一致，0.0 也视为缺失。
+    /// 与 [`AudioMetrics::is_complete`] 不同，这里只关心能否给出有意义的质量判定。
+    pub fn is_data_incomplete(&self) -> bool {
+        let missing = |value: Option<f64>| value.is_none_or(|v| v == 0.0);
+        [self.rms_db_above(18000), self.lra, self.peak_amplitude_db]
+            .into_iter()
+            .filter(|&value| missing(value))
+            .count()
+            >= 2
+    }
+
+    /// 综合质量判定
+    ///
+    /// 核心指标缺失过多（[`AudioMetrics::is_data_incomplete`]，近乎静音的文件除外）时为
+    /// [`QualityVerdict::Incomplete`]；解码时报告异常（[`AudioMetrics::is_suspect`]）或频谱判为伪造时为
+    /// [`QualityVerdict::Suspect`]，此时分数不可信；其余按 [`AudioMetrics::quality_score`]
+    /// 分级，综合了 LRA、峰值与频谱三项检查。
+    pub fn verdict(&self, thresholds: &QualityThresholds) -> QualityVerdict {
+        if !self.is_effectively_silent(thresholds) && self.is_data_incomplete() {
+            return QualityVerdict::Incomplete;
+        }
+        if self.is_suspect() || self.spectral_verdict(thresholds) == SpectralVerdict::Fake {
+            return QualityVerdict::Suspect;
+        }
+        QualityVerdict::from_score(self.quality_score(thresholds))
+    }
+}
+
+/// 综合质量判定，从低到高排序，可直接比较（如 `verdict >= QualityVerdict::Good`）
+///
+/// 序列化为小写英文（`excellent`、`good`、`acceptable`、`poor`、`suspect`、`incomplete`），
+/// JSON、CSV 与配置文件中使用相同的取值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityVerdict {
+    /// 核心指标缺失过多，无法判定（对应CSV状态"数据不完整"）
+    Incomplete,
+    /// 疑似有损转码或解码异常，指标不可信
+    Suspect,
+    /// 质量较差（分数低于 60）
+    Poor,
+    /// 质量一般（分数 60-74）
+    Acceptable,
+    /// 质量良好（分数 75-89）
+    Good,
+    /// 质量优秀（分数 90 及以上）
+    Excellent,
+}
+
+impl QualityVerdict {
+    /// 所有判定，从低到高
+    pub const ALL: [QualityVerdict; 6] = [
+        QualityVerdict::Incomplete,
+        QualityVerdict::Suspect,
+        QualityVerdict::Poor,
+        QualityVerdict::Acceptable,
+        QualityVerdict::Good,
+        QualityVerdict::Excellent,
+    ];
+
+    /// 按质量分数分级（不会得到 [`QualityVerdict::Suspect`] 与 [`QualityVerdict::Incomplete`]）
+    pub fn from_score(score: i32) -> Self {
+        match score {
+            90.. => QualityVerdict::Excellent,
+            75..=89 => QualityVerdict::Good,
+            60..=74 => QualityVerdict::Acceptable,
+            _ => QualityVerdict::Poor,
+        }
+    }
+
+    /// 序列化时使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QualityVerdict::Incomplete => "incomplete",
+            QualityVerdict::Suspect => "suspect",
+            QualityVerdict::Poor => "poor",
+            QualityVerdict::Acceptable => "acceptable",
+            QualityVerdict::Good => "good",
+            QualityVerdict::Excellent => "excellent",
+        }
+    }
+}
+
+impl std::fmt::Display for QualityVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for QualityVerdict {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|verdict| verdict.as_str() == name)
+            .ok_or_else(|| {
+                AnalyzerError::ConfigError(crate::tr!(
+                    "未知的质量判定: {s}（可选: incomplete, suspect, poor, acceptable, good, excellent）",
+                    "Unknown quality verdict: {s} (expected: incomplete, suspect, poor, acceptable, good, excellent)"
+                ))
+            })
+    }
+}
+
+/// 质量分数对应的等级描述
+pub fn quality_label(score: i32) -> &'static str {
+    match score {
+        90.. => "优秀",
+        75..=89 => "良好",
+        60..=74 => "一般",
+        40..=59 => "较差",
+        _ => "差",
+    }
+}
+
+/// LRA 得分系数
+///
+/// - 低于 `lra_poor_max`（严重压缩）: 0.0
+/// - 低于 `lra_low_max`（低动态）: 0.5
+/// - `lra_excellent_min` 至 `lra_excellent_max`: 1.0
+/// - 其余不超过 `lra_acceptable_max` 的范围: 0.8
+/// - 低于 `lra_too_high`: 0.6，更高: 0.4（动态过大）
+fn lra_factor(lra: f64, thresholds: &QualityThresholds) -> f64 {
+    if lra < thresholds.lra_poor_max {
+        0.0
+    } else if lra < thresholds.lra_low_max {
+        0.5
+    } else if (thresholds.lra_excellent_min..=thresholds.lra_excellent_max).contains(&lra) {
+        1.0
+    } else if lra <= thresholds.lra_acceptable_max {
+        0.8
+    } else if lra < thresholds.lra_too_high {
+        0.6
+    } else {
+        0.4
+    }
+}
+
+/// 峰值得分系数
+///
+/// - 达到 `peak_clipping_db`（已削波）: 0.0
+/// - 高于 `peak_medium_db`: 0.6
+/// - 高于 `peak_good_db`: 0.8
+/// - 其余（余量充足）: 1.0
+fn peak_factor(peak_db: f64, thresholds: &QualityThresholds) -> f64 {
+    if peak_db >= thresholds.peak_clipping_db {
+        0.0
+    } else if peak_db > thresholds.peak_medium_db {
+        0.6
+    } else if peak_db > thresholds.peak_good_db {
+        0.8
+    } else {
+        1.0
+    }
+}
+
+/// 频谱得分系数
+fn spectrum_factor(verdict: SpectralVerdict) -> f64 {
+    match verdict {
+        SpectralVerdict::Good => 1.0,
+        SpectralVerdict::Processed => 0.4,
+        SpectralVerdict::Fake | SpectralVerdict::Unknown => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(lra: f64, peak: f64, rms_18k: f64) -> AudioMetrics {
+        let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+        metrics.lra = Some(lra);
+        metrics.peak_amplitude_db = Some(peak);
+        metrics.highpass_rms.insert(18000, rms_18k);
+        metrics
+    }
+
+    #[test]
+    fn test_quality_score() {
+        let thresholds = QualityThresholds::default();
+
+        // 动态充足、余量充足、频谱完整
+        let excellent = metrics(10.0, -7.0, -65.0);
+        assert_eq!(excellent.quality_score(&thresholds), 100);
+
+        // 响亮的母带：低动态、接近削波
+        let loud = metrics(5.0, -1.0, -65.0);
+        assert_eq!(loud.quality_score(&thresholds), 68);
+
+        // 已削波且严重压缩的转码文件
+        let fake = metrics(2.0, 0.0, -100.0);
+        assert_eq!(fake.quality_score(&thresholds), 0);
+
+        // 频谱处理过、峰值中等
+        let processed = metrics(13.0, -4.0, -82.0);
+        assert_eq!(processed.quality_score(&thresholds), 68);
+
+        let empty = AudioMetrics::new("empty.flac".to_string(), 0);
+        assert_eq!(empty.quality_score(&thresholds), 0);
+    }
+
+    #[test]
+    fn test_quality_verdict() {
+        let thresholds = QualityThresholds::default();
+
+        assert_eq!(
+            metrics(10.0, -7.0, -65.0).verdict(&thresholds),
+            QualityVerdict::Excellent
+        );
+        assert_eq!(
+            metrics(5.0, -1.0, -65.0).verdict(&thresholds),
+            QualityVerdict::Acceptable
+        );
+        assert_eq!(
+            metrics(2.0, 0.0, -100.0).verdict(&thresholds),
+            QualityVerdict::Suspect
+        );
+
+        // 解码异常时分数不可信
+        let mut corrupt = metrics(10.0, -7.0, -65.0);
+        corrupt.decode_warnings.push("invalid residual".to_string());
+        assert_eq!(corrupt.verdict(&thresholds), QualityVerdict::Suspect);
+
+        // 缺少核心指标时不给出分数等级，与CSV状态"数据不完整"一致
+        let empty = AudioMetrics::new("empty.flac".to_string(), 0);
+        assert!(empty.is_data_incomplete());
+        assert_eq!(empty.verdict(&thresholds), QualityVerdict::Incomplete);
+        let mut peak_only = AudioMetrics::new("peak.flac".to_string(), 1024);
+        peak_only.peak_amplitude_db = Some(-3.0);
+        peak_only.lra = Some(0.0);
+        assert_eq!(peak_only.verdict(&thresholds), QualityVerdict::Incomplete);
+        let mut no_lra = metrics(10.0, -7.0, -65.0);
+        no_lra.lra = None;
+        assert!(!no_lra.is_data_incomplete());
+        assert_ne!(no_lra.verdict(&thresholds), QualityVerdict::Incomplete);
+
+        assert!(QualityVerdict::Excellent > QualityVerdict::Good);
+        assert!(QualityVerdict::Poor > QualityVerdict::Suspect);
+        assert!(QualityVerdict::Suspect > QualityVerdict::Incomplete);
+        assert_eq!(
+            "incomplete".parse::<QualityVerdict>().unwrap(),
+            QualityVerdict::Incomplete
+        );
+        assert_eq!(QualityVerdict::from_score(75), QualityVerdict::Good);
+        assert_eq!(
+            "Acceptable".parse::<QualityVerdict>().unwrap(),
+            QualityVerdict::Acceptable
+        );
+        assert!("great".parse::<QualityVerdict>().is_err());
+
+        let json = serde_json::to_string(&QualityVerdict::Excellent).unwrap();
+        assert_eq!(json, "\"excellent\"");
+        let parsed: QualityVerdict = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, QualityVerdict::Excellent);
+    }
+
+    #[test]
+    fn test_quality_label() {
+        assert_eq!(quality_label(100), "优秀");
+        assert_eq!(quality_label(80), "良好");
+        assert_eq!(quality_label(68), "一般");
+        assert_eq!(quality_label(45), "较差");
+        assert_eq!(quality_label(0), "差");
+    }
+}
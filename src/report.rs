@@ -0,0 +1,1086 @@
+//! # 报告生成模块
+//!
+//! 将分析结果直接导出为CSV报告与JSON数据文件，无需依赖嵌入的Python分析器；
+//! Markdown 报告面向人工阅读，可以直接交给艺人或混音师。
+
+use crate::config::{AnalyzerConfig, ThresholdSource};
+use crate::error::{AnalyzerError, Result};
+use crate::messages::text;
+use crate::quality::QualityVerdict;
+use crate::summary::{AnalysisSummary, ValueStats};
+use crate::types::{
+    highpass_field_name, parse_highpass_field_name, AudioMetrics, QualityThresholds,
+    SpectralVerdict,
+};
+use crate::utils::{fs_utils, string_utils};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// UTF-8 BOM，使 Excel 能正确识别CSV中的中文
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// CSV 报告中位于高通频段列之前的列标题
+const LEADING_COLUMNS: &[&str] = &[
+    "质量分",
+    "状态",
+    "filePath",
+    "fileSizeBytes",
+    "lra",
+    "peakAmplitudeDb",
+    "overallRmsDb",
+    "crestFactorDb",
+    "dcOffset",
+    "overcompressed",
+];
+
+/// 专辑汇总段落中逐列统计的频谱检测结果
+const ALBUM_VERDICTS: [SpectralVerdict; 4] = [
+    SpectralVerdict::Good,
+    SpectralVerdict::Processed,
+    SpectralVerdict::Fake,
+    SpectralVerdict::Unknown,
+];
+
+/// 处理时间字段名（CSV列名与JSON字段名相同）
+const TIMING_FIELD: &str = "processingTimeMs";
+
+/// 响度归一化所需增益的列名（仅在配置了 `target_lufs` 时输出）
+const GAIN_TO_TARGET_COLUMN: &str = "gainToTargetDb";
+
+/// 综合质量判定的列名（位于最后一列，在已有列之后追加，不打乱旧报告的列位置）
+const VERDICT_COLUMN: &str = "qualityVerdict";
+
+/// 高通频段以外的所有CSV列名，供配置验证时提示
+pub(crate) fn fixed_csv_columns() -> impl Iterator<Item = &'static str> {
+    LEADING_COLUMNS
+        .iter()
+        .copied()
+        .chain([GAIN_TO_TARGET_COLUMN, TIMING_FIELD, VERDICT_COLUMN])
+}
+
+/// 是否为CSV报告中的列名（含 `rmsDbAbove18k` 形式的高通频段列）
+pub(crate) fn is_csv_column(name: &str) -> bool {
+    fixed_csv_columns().any(|column| column == name) || parse_highpass_field_name(name).is_some()
+}
+
+impl AudioMetrics {
+    /// CSV 报告中固定列的列名，与 [`AudioMetrics::to_csv_record`] 的各项一一对应
+    ///
+    /// 内置的CSV报告在 `overcompressed` 与最后的 `qualityVerdict` 之间，按配置插入 `gainToTargetDb`、
+    /// 各高通频段列（如 `rmsDbAbove18k`）与 `processingTimeMs`；自行编写导出程序时可直接沿用这些列名。
+    pub fn csv_header() -> Vec<&'static str> {
+        let mut header = LEADING_COLUMNS.to_vec();
+        header.push(VERDICT_COLUMN);
+        header
+    }
+
+    /// 按 [`AudioMetrics::csv_header`] 的列顺序输出各项数值
+    ///
+    /// 包含按 `thresholds` 计算的质量分数、状态与综合判定；缺失的数值输出为空字符串，
+    /// 与内置CSV报告的写法相同。
+    pub fn to_csv_record(&self, thresholds: &QualityThresholds) -> Vec<String> {
+        let mut record = self.leading_csv_record(thresholds);
+        record.push(self.verdict(thresholds).to_string());
+        record
+    }
+
+    /// [`LEADING_COLUMNS`] 对应的各项数值
+    fn leading_csv_record(&self, thresholds: &QualityThresholds) -> Vec<String> {
+        vec![
+            self.quality_score(thresholds).to_string(),
+            quality_status(self, thresholds).to_string(),
+            self.file_path.clone(),
+            self.file_size_bytes.to_string(),
+            format_optional(self.lra),
+            format_optional(self.peak_amplitude_db),
+            format_optional(self.overall_rms_db),
+            format_optional(self.crest_factor_db),
+            format_optional(self.dc_offset),
+            self.is_overcompressed(thresholds).to_string(),
+        ]
+    }
+}
+
+/// 将分析结果写入CSV
+///
+/// 列与Python分析模块保持一致，并附带根据阈值计算出的质量状态。
+/// 高通频段列按所有结果中出现过的频率生成，列名与JSON字段名一致（如 `rmsDbAbove18k`）。
+/// 配置了 `target_lufs` 时，在高通频段列之前增加 `gainToTargetDb` 列；
+/// `output.include_timing` 为 `false` 时省略处理时间列；
+/// 设置了 `output.min_quality_score` / `output.min_quality_verdict` 时，
+/// 只输出质量分数与综合判定都不低于设定值的文件；`qualityVerdict` 列位于最后，按当前阈值计算
+/// （文件匹配 `profile_rules` 时使用该预设的阈值）。
+/// 设置了 `output.csv_columns` 时按给定顺序只输出这些列，本次没有数据的列（如未配置
+/// `target_lufs` 时的 `gainToTargetDb`）输出为空；字段分隔符由 `output.csv_delimiter` 决定。
+/// 开启 `output.csv_album_summary` 时，在空行之后追加按目录（专辑）汇总的段落。
+pub fn write_csv<W: Write>(metrics: &[AudioMetrics], config: &AnalyzerConfig, w: W) -> Result<()> {
+    let profiles = config.profile_matcher()?;
+    let include_timing = config.output.include_timing;
+
+    let frequencies: BTreeSet<u32> = metrics
+        .iter()
+        .flat_map(|m| m.highpass_rms.keys().copied())
+        .collect();
+
+    let mut header: Vec<String> = LEADING_COLUMNS.iter().map(|c| c.to_string()).collect();
+    if config.target_lufs.is_some() {
+        header.push(GAIN_TO_TARGET_COLUMN.to_string());
+    }
+    header.extend(frequencies.iter().map(|&f| highpass_field_name(f)));
+    if include_timing {
+        header.push(TIMING_FIELD.to_string());
+    }
+    header.push(VERDICT_COLUMN.to_string());
+
+    // 选中列在完整列中的位置
+    let selection: Option<Vec<Option<usize>>> = config.output.csv_columns.as_ref().map(|columns| {
+        columns
+            .iter()
+            .map(|column| header.iter().position(|h| h == column))
+            .collect()
+    });
+    let select = |record: Vec<String>| match &selection {
+        Some(indices) => indices
+            .iter()
+            .map(|index| index.map_or_else(String::new, |i| record[i].clone()))
+            .collect(),
+        None => record,
+    };
+
+    let delimiter = u8::try_from(config.output.csv_delimiter).map_err(|_| {
+        AnalyzerError::ConfigError(crate::tr!(
+            "无效的CSV分隔符 {:?}",
+            "Invalid CSV delimiter {:?}",
+            config.output.csv_delimiter
+        ))
+    })?;
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(w);
+    match &config.output.csv_columns {
+        Some(columns) => writer.write_record(columns)?,
+        None => writer.write_record(&header)?,
+    }
+
+    for m in metrics {
+        let thresholds = &*profiles.thresholds_for(m.path());
+        let score = m.quality_score(thresholds);
+        let verdict = m.verdict(thresholds);
+        if config
+            .output
+            .min_quality_score
+            .is_some_and(|min_score| score < min_score)
+            || config
+                .output
+                .min_quality_verdict
+                .is_some_and(|min_verdict| verdict < min_verdict)
+        {
+            continue;
+        }
+
+        let mut record = m.leading_csv_record(thresholds);
+        if let Some(target) = config.target_lufs {
+            record.push(format_optional(m.gain_to_target(target)));
+        }
+        record.extend(
+            frequencies
+                .iter()
+                .map(|&f| format_optional(m.rms_db_above(f))),
+        );
+        if include_timing {
+            record.push(m.processing_time_ms.to_string());
+        }
+        record.push(verdict.to_string());
+        writer.write_record(select(record))?;
+    }
+
+    writer.flush()?;
+    if config.output.csv_album_summary {
+        let mut w = writer.into_inner().map_err(|e| e.into_error())?;
+        w.write_all(b"\n")?;
+        write_album_summary(metrics, &profiles, delimiter, w)?;
+    }
+    Ok(())
+}
+
+/// 按目录（专辑）汇总的CSV段落，跟在逐文件结果之后
+///
+/// 汇总包含所有文件，不受 `min_quality_score`、`min_quality_verdict` 与 `csv_columns` 影响。
+/// 文件按实际路径分组，目录列沿用逐文件结果中的路径写法。
+fn write_album_summary<W: Write>(
+    metrics: &[AudioMetrics],
+    thresholds: &impl ThresholdSource,
+    delimiter: u8,
+    w: W,
+) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(w);
+    let mut header = vec![
+        "目录",
+        "文件数",
+        "lraMean",
+        "lraMin",
+        "lraMax",
+        "integratedLufsMin",
+        "integratedLufsMax",
+    ];
+    header.extend(ALBUM_VERDICTS.iter().map(SpectralVerdict::description));
+    writer.write_record(&header)?;
+
+    let mut labels: BTreeMap<&Path, String> = BTreeMap::new();
+    for m in metrics {
+        let dir = m.path().parent().unwrap_or(Path::new(""));
+        labels.entry(dir).or_insert_with(|| album_label(m, dir));
+    }
+
+    let round = |value: f64| (value * 100.0).round() / 100.0;
+    for (dir, summary) in AnalysisSummary::by_directory_with_thresholds(metrics, thresholds) {
+        let label = labels.get(dir.as_path()).cloned().unwrap_or_default();
+        let mut record = vec![
+            label,
+            summary.total_files.to_string(),
+            format_optional(summary.lra.map(|lra| round(lra.mean))),
+            format_optional(summary.lra.map(|lra| lra.min)),
+            format_optional(summary.lra.map(|lra| lra.max)),
+            format_optional(summary.integrated_lufs.map(|lufs| lufs.min)),
+            format_optional(summary.integrated_lufs.map(|lufs| lufs.max)),
+        ];
+        record.extend(
+            ALBUM_VERDICTS
+                .iter()
+                .map(|&verdict| summary.verdict_count(verdict).to_string()),
+        );
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// 汇总中目录的写法：取 `m` 在报告中的路径去掉文件名，只保留了文件名时改用目录名
+fn album_label(m: &AudioMetrics, dir: &Path) -> String {
+    match Path::new(&m.file_path)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        Some(parent) => parent.display().to_string(),
+        None => dir.file_name().map_or_else(
+            || dir.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+    }
+}
+
+/// 将分析结果写入CSV文件（带 UTF-8 BOM）
+///
+/// 文件以原子方式写入，见 [`fs_utils::write_atomic`]。
+pub fn write_csv_file<P: AsRef<Path>>(
+    path: P,
+    metrics: &[AudioMetrics],
+    config: &AnalyzerConfig,
+) -> Result<()> {
+    let path = path.as_ref();
+    check_overwrite(path, config)?;
+
+    let mut buffer = UTF8_BOM.to_vec();
+    write_csv(metrics, config, &mut buffer)?;
+    fs_utils::write_atomic(path, &buffer)
+}
+
+/// 将短期响度时间序列写入CSV，列为 `time_seconds,short_term_lufs`
+///
+/// 响度保留两位小数。
+/// 时间序列由 [`AudioAnalyzer::extract_loudness_timeseries`] 生成。
+///
+/// [`AudioAnalyzer::extract_loudness_timeseries`]: crate::AudioAnalyzer::extract_loudness_timeseries
+pub fn write_loudness_csv<W: Write>(series: &[(f64, f64)], w: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(w);
+    writer.write_record(["time_seconds", "short_term_lufs"])?;
+    for &(time, lufs) in series {
+        writer.write_record([time.to_string(), format!("{lufs:.2}")])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 将短期响度时间序列写入CSV文件
+///
+/// 文件以原子方式写入，见 [`fs_utils::write_atomic`]。
+pub fn write_loudness_csv_file<P: AsRef<Path>>(
+    path: P,
+    series: &[(f64, f64)],
+    config: &AnalyzerConfig,
+) -> Result<()> {
+    let path = path.as_ref();
+    check_overwrite(path, config)?;
+
+    let mut buffer = Vec::new();
+    write_loudness_csv(series, &mut buffer)?;
+    fs_utils::write_atomic(path, &buffer)
+}
+
+/// 将分析结果写入格式化的JSON
+///
+/// `output.include_timing` 为 `false` 时省略 `processingTimeMs` 字段。
+pub fn write_json<W: Write>(metrics: &[AudioMetrics], config: &AnalyzerConfig, w: W) -> Result<()> {
+    let entries = metrics
+        .iter()
+        .map(|m| metrics_value(m, config))
+        .collect::<Result<Vec<_>>>()?;
+
+    serde_json::to_writer_pretty(w, &entries)?;
+    Ok(())
+}
+
+/// 将分析结果写入NDJSON（每行一个JSON对象）
+///
+/// 每写完一行立即 flush，配合 [`AudioAnalyzer::analyze_directory_streaming`]
+/// 可以边分析边输出，供 `tail -f`、`jq` 等工具实时消费。
+/// 字段与 [`write_json`] 相同。
+///
+/// [`AudioAnalyzer::analyze_directory_streaming`]: crate::AudioAnalyzer::analyze_directory_streaming
+pub fn write_ndjson<W, I>(metrics: I, config: &AnalyzerConfig, mut w: W) -> Result<()>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: Borrow<AudioMetrics>,
+{
+    for m in metrics {
+        let value = metrics_value(m.borrow(), config)?;
+        serde_json::to_writer(&mut w, &value)?;
+        w.write_all(b"\n")?;
+        w.flush()?;
+    }
+    Ok(())
+}
+
+/// 将分析结果写入NDJSON文件
+///
+/// 为了能被实时读取，NDJSON 直接写入目标文件而不经过临时文件。
+pub fn write_ndjson_file<P, I>(path: P, metrics: I, config: &AnalyzerConfig) -> Result<()>
+where
+    P: AsRef<Path>,
+    I: IntoIterator,
+    I::Item: Borrow<AudioMetrics>,
+{
+    let path = path.as_ref();
+    check_overwrite(path, config)?;
+    write_ndjson(metrics, config, File::create(path)?)
+}
+
+/// `output.overwrite` 为 `false` 且文件已存在时返回 `AlreadyExists` 错误
+pub fn check_overwrite(path: &Path, config: &AnalyzerConfig) -> Result<()> {
+    if !config.output.overwrite && path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            crate::tr!(
+                "输出文件已存在: {}",
+                "Output file already exists: {}",
+                path.display()
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// 将单个结果转换为JSON值，按配置省略处理时间字段
+fn metrics_value(metrics: &AudioMetrics, config: &AnalyzerConfig) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(metrics)?;
+    if !config.output.include_timing {
+        if let Some(entry) = value.as_object_mut() {
+            entry.remove(TIMING_FIELD);
+        }
+    }
+    Ok(value)
+}
+
+/// 将分析结果写入JSON文件
+///
+/// 文件以原子方式写入，见 [`fs_utils::write_atomic`]。
+pub fn write_json_file<P: AsRef<Path>>(
+    path: P,
+    metrics: &[AudioMetrics],
+    config: &AnalyzerConfig,
+) -> Result<()> {
+    let path = path.as_ref();
+    check_overwrite(path, config)?;
+
+    let mut buffer = Vec::new();
+    write_json(metrics, config, &mut buffer)?;
+    fs_utils::write_atomic(path, &buffer)
+}
+
+/// 读取 [`write_json_file`] 写出的JSON结果
+///
+/// 缺少 `processingTimeMs` 的条目（`include_timing` 为 `false` 时写出）按 0 处理。
+pub fn read_json_file<P: AsRef<Path>>(path: P) -> Result<Vec<AudioMetrics>> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(io::BufReader::new(file))?)
+}
+
+/// 读取 [`write_ndjson`] 逐行写出的结果
+///
+/// 写出过程被中断时最后一行可能不完整，无法解析的行被忽略；空行同样忽略。
+pub fn read_ndjson_file<P: AsRef<Path>>(path: P) -> Result<Vec<AudioMetrics>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// 将批量汇总统计写入JSON文件
+///
+/// 与 [`write_json_file`] 一样以原子方式写入。
+pub fn write_summary_file<P: AsRef<Path>>(
+    path: P,
+    summary: &AnalysisSummary,
+    config: &AnalyzerConfig,
+) -> Result<()> {
+    let path = path.as_ref();
+    check_overwrite(path, config)?;
+
+    let buffer = serde_json::to_vec_pretty(summary)?;
+    fs_utils::write_atomic(path, &buffer)
+}
+
+/// 将汇总统计与逐文件结果写为 Markdown 报告
+///
+/// `# 汇总` 段落列出文件数、响度与 LRA 统计、频谱检测结果和失败的文件，
+/// `## 文件` 表格逐个列出综合判定与主要指标，判定为可疑的文件以粗体标出。
+/// 标题与表头随界面语言变化；综合判定使用结果中已计算的 `qualityVerdict`。
+pub fn write_markdown<W: Write>(
+    summary: &AnalysisSummary,
+    metrics: &[AudioMetrics],
+    mut w: W,
+) -> Result<()> {
+    writeln!(w, "# {}\n", text("汇总", "Summary"))?;
+    writeln!(
+        w,
+        "- {}: {}",
+        text("成功分析", "Analyzed"),
+        summary.total_files
+    )?;
+    writeln!(
+        w,
+        "- {}: {}",
+        text("分析失败", "Failed"),
+        summary.failed_files
+    )?;
+    if summary.skipped_files > 0 {
+        writeln!(
+            w,
+            "- {}: {}",
+            text("已取消", "Cancelled"),
+            summary.skipped_files
+        )?;
+    }
+    if summary.undersized_files > 0 {
+        writeln!(
+            w,
+            "- {}: {}",
+            text("空文件或过小", "Empty or undersized"),
+            summary.undersized_files
+        )?;
+    }
+    if summary.not_audio_files > 0 {
+        writeln!(
+            w,
+            "- {}: {}",
+            text("不是音频", "Not really audio"),
+            summary.not_audio_files
+        )?;
+    }
+    writeln!(
+        w,
+        "- {}: {} ({:.1}%)",
+        text("指标完整", "Complete metrics"),
+        summary.complete_files,
+        summary.complete_percentage()
+    )?;
+    writeln!(
+        w,
+        "- {}: {}",
+        text("解码异常", "Decode problems"),
+        summary.suspect_files
+    )?;
+    writeln!(
+        w,
+        "- {}: {}",
+        text("总大小", "Total size"),
+        string_utils::format_file_size(summary.total_size_bytes)
+    )?;
+    if let Some(lufs) = &summary.integrated_lufs {
+        writeln!(
+            w,
+            "- {}: {}",
+            text("综合响度 (LUFS)", "Integrated loudness (LUFS)"),
+            format_value_stats(lufs)
+        )?;
+    }
+    if let Some(lra) = &summary.lra {
+        writeln!(
+            w,
+            "- {}: {}",
+            text("响度范围 (LU)", "Loudness range (LU)"),
+            format_value_stats(lra)
+        )?;
+    }
+    if !summary.verdicts.is_empty() {
+        let verdicts: Vec<String> = summary
+            .verdicts
+            .iter()
+            .map(|(&verdict, count)| format!("{} {count}", spectral_verdict_label(verdict)))
+            .collect();
+        writeln!(
+            w,
+            "- {}: {}",
+            text("频谱检测", "Spectrum check"),
+            verdicts.join(", ")
+        )?;
+    }
+
+    if !summary.failures.is_empty() {
+        writeln!(w, "\n## {}\n", text("失败的文件", "Failed files"))?;
+        for failure in &summary.failures {
+            writeln!(
+                w,
+                "- `{}`: {}",
+                failure.path.replace('`', "'"),
+                failure.error.replace('\n', " ")
+            )?;
+        }
+    }
+
+    writeln!(w, "\n## {}\n", text("文件", "Files"))?;
+    let header = [
+        text("文件", "File"),
+        text("判定", "Verdict"),
+        "LRA (LU)",
+        text("综合响度 (LUFS)", "Integrated (LUFS)"),
+        text("峰值 (dB)", "Peak (dB)"),
+        text("真峰值 (dBTP)", "True peak (dBTP)"),
+        text("18kHz 以上 RMS (dB)", "RMS above 18 kHz (dB)"),
+    ];
+    writeln!(w, "| {} |", header.join(" | "))?;
+    writeln!(w, "|{}", "---|".repeat(header.len()))?;
+
+    let format_value =
+        |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{v:.1}"));
+    for m in metrics {
+        let suspect = m.quality_verdict == Some(QualityVerdict::Suspect) || m.is_suspect();
+        let emphasize = |cell: String| {
+            if suspect {
+                format!("**{cell}**")
+            } else {
+                cell
+            }
+        };
+        let verdict = m
+            .quality_verdict
+            .map_or_else(|| "-".to_string(), |verdict| verdict.to_string());
+        let row = [
+            emphasize(escape_markdown_cell(&m.file_path)),
+            emphasize(verdict),
+            format_value(m.lra),
+            format_value(m.integrated_lufs),
+            format_value(m.peak_amplitude_db),
+            format_value(m.true_peak_dbtp),
+            format_value(m.rms_db_above(18000)),
+        ];
+        writeln!(w, "| {} |", row.join(" | "))?;
+    }
+    Ok(())
+}
+
+/// 将 Markdown 报告写入文件
+///
+/// 文件以原子方式写入，见 [`fs_utils::write_atomic`]。
+pub fn write_markdown_file<P: AsRef<Path>>(
+    path: P,
+    summary: &AnalysisSummary,
+    metrics: &[AudioMetrics],
+    config: &AnalyzerConfig,
+) -> Result<()> {
+    let path = path.as_ref();
+    check_overwrite(path, config)?;
+
+    let mut buffer = Vec::new();
+    write_markdown(summary, metrics, &mut buffer)?;
+    fs_utils::write_atomic(path, &buffer)
+}
+
+/// 统计量的简短写法：平均值与范围
+fn format_value_stats(stats: &ValueStats) -> String {
+    format!(
+        "{} {:.1}, {} {:.1} – {:.1}",
+        text("平均", "mean"),
+        stats.mean,
+        text("范围", "range"),
+        stats.min,
+        stats.max
+    )
+}
+
+/// 频谱检测结果在 Markdown 报告中的名称
+fn spectral_verdict_label(verdict: SpectralVerdict) -> &'static str {
+    match verdict {
+        SpectralVerdict::Fake => text(verdict.description(), "likely transcoded"),
+        SpectralVerdict::Processed => text(verdict.description(), "likely processed"),
+        SpectralVerdict::Good => text(verdict.description(), "full spectrum"),
+        SpectralVerdict::Unknown => text(verdict.description(), "unknown"),
+    }
+}
+
+/// 转义 Markdown 表格单元格中的竖线与换行
+fn escape_markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// 根据阈值判定文件的质量状态
+///
+/// 判定顺序与Python分析模块一致：数据完整性 → 频谱截止 → 削波 → 动态范围，
+/// 后面的判定只在前面的状态不冲突时覆盖。
+fn quality_status(metrics: &AudioMetrics, thresholds: &QualityThresholds) -> &'static str {
+    // 空音轨的响度与频谱数值没有意义，优先报告
+    if metrics.is_effectively_silent(thresholds) {
+        return "近乎静音";
+    }
+
+    if metrics.is_data_incomplete() {
+        return "数据不完整";
+    }
+
+    // 与Python端一致，0.0 也视为缺失
+    let present = |value: Option<f64>| value.filter(|v| *v != 0.0);
+    let rms_18k = present(metrics.rms_db_above(18000));
+    let lra = present(metrics.lra);
+    let peak = present(metrics.peak_amplitude_db);
+
+    // 解码时报告损坏或截断，指标只反映部分数据
+    if metrics.is_suspect() {
+        return "可疑 (解码异常)";
+    }
+
+    let mut status = "质量良好";
+
+    if let Some(rms) = rms_18k {
+        if rms < thresholds.spectrum_fake_threshold {
+            return "可疑 (伪造)";
+        }
+        if rms < thresholds.spectrum_processed_threshold {
+            status = "疑似处理";
+        }
+    }
+
+    if peak.is_some_and(|p| p >= thresholds.peak_clipping_db) {
+        status = "已削波";
+    }
+
+    if let Some(lra) = lra.filter(|v| *v > 0.0) {
+        if lra < thresholds.lra_poor_max {
+            status = "严重压缩";
+        } else if lra < thresholds.lra_low_max && status != "已削波" {
+            status = "低动态";
+        }
+    }
+
+    status
+}
+
+/// 格式化可选数值，缺失时输出空字段
+fn format_optional(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AnalyzerError;
+    use crate::quality::QualityVerdict;
+
+    fn sample_metrics(path: &str, lra: f64, peak: f64, rms_18k: f64) -> AudioMetrics {
+        let mut metrics = AudioMetrics::new(path.to_string(), 2048);
+        metrics.lra = Some(lra);
+        metrics.peak_amplitude_db = Some(peak);
+        metrics.overall_rms_db = Some(-18.0);
+        metrics.highpass_rms.insert(16000, -60.0);
+        metrics.highpass_rms.insert(18000, rms_18k);
+        metrics.highpass_rms.insert(20000, -80.0);
+        metrics
+    }
+
+    #[test]
+    fn test_quality_status() {
+        let thresholds = QualityThresholds::default();
+
+        let good = sample_metrics("good.flac", 10.0, -6.5, -65.0);
+        assert_eq!(quality_status(&good, &thresholds), "质量良好");
+
+        let fake = sample_metrics("fake.flac", 10.0, -0.05, -90.0);
+        assert_eq!(quality_status(&fake, &thresholds), "可疑 (伪造)");
+
+        let clipped = sample_metrics("clipped.flac", 5.0, -0.05, -65.0);
+        assert_eq!(quality_status(&clipped, &thresholds), "已削波");
+
+        let squashed = sample_metrics("squashed.flac", 2.0, -0.05, -65.0);
+        assert_eq!(quality_status(&squashed, &thresholds), "严重压缩");
+
+        let incomplete = AudioMetrics::new("empty.flac".to_string(), 0);
+        assert_eq!(quality_status(&incomplete, &thresholds), "数据不完整");
+
+        let mut corrupt = sample_metrics("corrupt.flac", 10.0, -6.5, -65.0);
+        corrupt
+            .decode_warnings
+            .push("[flac @ 0x1] invalid residual".to_string());
+        assert_eq!(quality_status(&corrupt, &thresholds), "可疑 (解码异常)");
+
+        let mut silent = AudioMetrics::new("silent.flac".to_string(), 0);
+        silent.silent_fraction = Some(0.98);
+        assert_eq!(quality_status(&silent, &thresholds), "近乎静音");
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let metrics = vec![sample_metrics("/music/a.flac", 10.0, -6.5, -65.0)];
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &AnalyzerConfig::default(), &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "质量分,状态,filePath,fileSizeBytes,lra,peakAmplitudeDb,overallRmsDb,crestFactorDb,\
+             dcOffset,overcompressed,rmsDbAbove16k,rmsDbAbove18k,rmsDbAbove20k,processingTimeMs,qualityVerdict"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "100,质量良好,/music/a.flac,2048,10,-6.5,-18,,,false,-60,-65,-80,0,excellent"
+        );
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_csv_record_matches_header() {
+        let thresholds = QualityThresholds::default();
+        let metrics = sample_metrics("/music/a.flac", 2.0, -0.05, -90.0);
+        let header = AudioMetrics::csv_header();
+        let record = metrics.to_csv_record(&thresholds);
+        assert_eq!(header.len(), record.len());
+
+        let field = |name: &str| &record[header.iter().position(|h| *h == name).unwrap()];
+        assert_eq!(
+            field("质量分"),
+            &metrics.quality_score(&thresholds).to_string()
+        );
+        assert_eq!(field("状态"), "可疑 (伪造)");
+        assert_eq!(field("qualityVerdict"), "suspect");
+        assert_eq!(field("filePath"), "/music/a.flac");
+        assert_eq!(field("dcOffset"), "");
+
+        // 内置CSV报告的数据行以同样的数值开头，并以综合判定结尾
+        let config = AnalyzerConfig {
+            output: crate::config::OutputConfig {
+                include_timing: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut buffer = Vec::new();
+        write_csv(std::slice::from_ref(&metrics), &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let row = output.lines().nth(1).unwrap();
+        let (verdict, leading) = record.split_last().unwrap();
+        assert!(row.starts_with(&leading.join(",")));
+        assert!(row.ends_with(&format!(",{verdict}")));
+    }
+
+    #[test]
+    fn test_gain_to_target_column() {
+        let mut loud = sample_metrics("/music/loud.flac", 10.0, -6.5, -65.0);
+        loud.integrated_lufs = Some(-8.0);
+        let quiet = sample_metrics("/music/quiet.flac", 10.0, -6.5, -65.0);
+        let config = AnalyzerConfig {
+            target_lufs: Some(-14.0),
+            ..Default::default()
+        };
+
+        let mut buffer = Vec::new();
+        write_csv(&[loud, quiet], &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let mut lines = output.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .contains("dcOffset,overcompressed,gainToTargetDb,rmsDbAbove16k"));
+        assert!(lines.next().unwrap().contains(",,false,-6,-60,"));
+        // 缺少综合响度时为空
+        assert!(lines.next().unwrap().contains(",,false,,-60,"));
+    }
+
+    #[test]
+    fn test_csv_columns_and_delimiter() {
+        let metrics = vec![sample_metrics("/music/a.flac", 10.0, -6.5, -65.0)];
+        let mut config = AnalyzerConfig::default();
+        config.output.csv_columns = Some(
+            ["filePath", "rmsDbAbove18k", "lra", "gainToTargetDb"]
+                .map(String::from)
+                .to_vec(),
+        );
+        config.output.csv_delimiter = ';';
+
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "filePath;rmsDbAbove18k;lra;gainToTargetDb"
+        );
+        // 未配置 target_lufs 时增益列为空
+        assert_eq!(lines.next().unwrap(), "/music/a.flac;-65;10;");
+        assert!(lines.next().is_none());
+
+        assert!(is_csv_column("状态"));
+        assert!(is_csv_column("rmsDbAbove12500Hz"));
+        assert!(!is_csv_column("bitrate"));
+    }
+
+    #[test]
+    fn test_album_summary_section() {
+        let mut config = AnalyzerConfig::default();
+        config.output.csv_album_summary = true;
+        config.output.csv_columns = Some(vec!["filePath".to_string()]);
+        let metrics = vec![
+            sample_metrics("/music/a/01.flac", 8.0, -6.5, -65.0),
+            sample_metrics("/music/a/02.flac", 10.0, -6.5, -90.0),
+            sample_metrics("/music/b/01.flac", 9.0, -6.5, -65.0),
+        ];
+
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let (tracks, albums) = output.split_once("\n\n").unwrap();
+        assert_eq!(tracks.lines().count(), 4);
+
+        let mut lines = albums.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "目录,文件数,lraMean,lraMin,lraMax,integratedLufsMin,integratedLufsMax,\
+             频谱正常,疑似处理,可疑 (伪造),未知"
+        );
+        assert_eq!(lines.next().unwrap(), "/music/a,2,9,8,10,,,1,0,1,0");
+        assert_eq!(lines.next().unwrap(), "/music/b,1,9,9,9,,,1,0,0,0");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_album_summary_groups_by_real_path() {
+        let mut config = AnalyzerConfig::default();
+        config.output.csv_album_summary = true;
+        config.output.csv_columns = Some(vec!["filePath".to_string()]);
+        // 报告中只保留文件名，分组仍按实际所在目录
+        let styled = |path: &str, lra: f64| {
+            let mut metrics = sample_metrics(path, lra, -6.5, -65.0);
+            metrics.source_path = Some(path.into());
+            metrics.file_path = Path::new(path)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned();
+            metrics
+        };
+        let metrics = vec![
+            styled("/music/a/01.flac", 8.0),
+            styled("/music/a/02.flac", 10.0),
+            styled("/music/b/01.flac", 9.0),
+        ];
+
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let (_, albums) = output.split_once("\n\n").unwrap();
+
+        let mut lines = albums.lines().skip(1);
+        assert_eq!(lines.next().unwrap(), "a,2,9,8,10,,,2,0,0,0");
+        assert_eq!(lines.next().unwrap(), "b,1,9,9,9,,,1,0,0,0");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_write_loudness_csv() {
+        let mut buffer = Vec::new();
+        write_loudness_csv(&[(0.0, -20.0), (0.5, -12.596373)], &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "time_seconds,short_term_lufs\n0,-20.00\n0.5,-12.60\n"
+        );
+    }
+
+    #[test]
+    fn test_min_quality_score_filter() {
+        let metrics = vec![
+            sample_metrics("/music/good.flac", 10.0, -6.5, -65.0),
+            sample_metrics("/music/fake.flac", 2.0, -0.05, -90.0),
+        ];
+        let mut config = AnalyzerConfig::default();
+        config.output.min_quality_score = Some(50);
+
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("good.flac"));
+        assert!(!output.contains("fake.flac"));
+    }
+
+    #[test]
+    fn test_min_quality_verdict_filter() {
+        let metrics = vec![
+            sample_metrics("/music/excellent.flac", 10.0, -6.5, -65.0),
+            sample_metrics("/music/loud.flac", 5.0, -1.0, -65.0),
+            sample_metrics("/music/fake.flac", 10.0, -6.5, -90.0),
+        ];
+        let mut config = AnalyzerConfig::default();
+        config.output.min_quality_verdict = Some(QualityVerdict::Acceptable);
+
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let verdict_of = |name: &str| {
+            output
+                .lines()
+                .find(|line| line.contains(name))
+                .and_then(|line| line.rsplit(',').next())
+                .map(str::to_string)
+        };
+        assert_eq!(verdict_of("excellent.flac").as_deref(), Some("excellent"));
+        assert_eq!(verdict_of("loud.flac").as_deref(), Some("acceptable"));
+        assert!(!output.contains("fake.flac"));
+
+        config.output.min_quality_verdict = Some(QualityVerdict::Good);
+        let mut buffer = Vec::new();
+        write_csv(&metrics, &config, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("excellent.flac"));
+        assert!(!output.contains("loud.flac"));
+    }
+
+    #[test]
+    fn test_omit_timing() {
+        let metrics = vec![sample_metrics("/music/a.flac", 10.0, -6.5, -65.0)];
+        let mut config = AnalyzerConfig::default();
+        config.output.include_timing = false;
+
+        let mut csv_buffer = Vec::new();
+        write_csv(&metrics, &config, &mut csv_buffer).unwrap();
+        let csv_output = String::from_utf8(csv_buffer).unwrap();
+        assert!(!csv_output.contains(TIMING_FIELD));
+        assert!(csv_output
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with("rmsDbAbove20k,qualityVerdict"));
+
+        let mut json_buffer = Vec::new();
+        write_json(&metrics, &config, &mut json_buffer).unwrap();
+        let json_output = String::from_utf8(json_buffer).unwrap();
+        assert!(!json_output.contains(TIMING_FIELD));
+        assert!(json_output.contains("rmsDbAbove18k"));
+    }
+
+    #[test]
+    fn test_overwrite_protection() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let json_path = dir.path().join("report.json");
+        let metrics = vec![sample_metrics("/music/a.flac", 10.0, -6.5, -65.0)];
+
+        let mut config = AnalyzerConfig::default();
+        write_json_file(&json_path, &metrics, &config).unwrap();
+        let original = std::fs::read_to_string(&json_path).unwrap();
+        assert!(original.contains("/music/a.flac"));
+
+        // 禁止覆盖时报错，原文件保持不变
+        config.output.overwrite = false;
+        let error = write_json_file(&json_path, &[], &config).unwrap_err();
+        assert!(
+            matches!(error, AnalyzerError::Io(ref e) if e.kind() == io::ErrorKind::AlreadyExists)
+        );
+        assert_eq!(std::fs::read_to_string(&json_path).unwrap(), original);
+
+        // 默认允许覆盖，且不会残留临时文件
+        config.output.overwrite = true;
+        write_json_file(&json_path, &[], &config).unwrap();
+        assert_eq!(std::fs::read_to_string(&json_path).unwrap(), "[]");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_ndjson() {
+        let metrics = vec![
+            sample_metrics("/music/a.flac", 10.0, -6.5, -65.0),
+            sample_metrics("/music/b.flac", 5.0, -1.0, -70.0),
+        ];
+        let mut buffer = Vec::new();
+        write_ndjson(&metrics, &AnalyzerConfig::default(), &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: AudioMetrics = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.file_path, "/music/a.flac");
+        assert_eq!(first.rms_db_above(18000), Some(-65.0));
+
+        // 也可以直接消费拥有所有权的迭代器
+        let mut owned_buffer = Vec::new();
+        write_ndjson(metrics, &AnalyzerConfig::default(), &mut owned_buffer).unwrap();
+        assert_eq!(String::from_utf8(owned_buffer).unwrap(), output);
+
+        // 读回时忽略中断写出留下的不完整行
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("analysis_data.ndjson");
+        std::fs::write(&path, format!("{output}{{\"filePath\": \"/mus")).unwrap();
+        let read_back = read_ndjson_file(&path).unwrap();
+        assert_eq!(read_back.len(), 2);
+        assert_eq!(read_back[1].file_path, "/music/b.flac");
+    }
+
+    #[test]
+    fn test_write_markdown() {
+        let thresholds = QualityThresholds::default();
+        let mut good = sample_metrics("/music/a|b.flac", 10.0, -6.5, -65.0);
+        good.integrated_lufs = Some(-14.04);
+        let mut fake = sample_metrics("/music/fake.flac", 10.0, -0.05, -90.0);
+        for m in [&mut good, &mut fake] {
+            m.quality_verdict = Some(m.verdict(&thresholds));
+        }
+        let metrics = vec![good, fake];
+        let summary = AnalysisSummary::from_metrics_with_thresholds(&metrics, &thresholds);
+
+        let mut buffer = Vec::new();
+        write_markdown(&summary, &metrics, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        assert!(output.starts_with(&format!("# {}\n", text("汇总", "Summary"))));
+        assert!(output.contains(&format!("\n## {}\n", text("文件", "Files"))));
+        assert!(output.contains(&format!("- {}: 2\n", text("成功分析", "Analyzed"))));
+        let rows: Vec<&str> = output
+            .lines()
+            .skip_while(|line| !line.starts_with("|---"))
+            .skip(1)
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "| /music/a\\|b.flac | excellent | 10.0 | -14.0 | -6.5 | - | -65.0 |",
+                "| **/music/fake.flac** | **suspect** | 10.0 | - | -0.1 | - | -90.0 |",
+            ]
+        );
+    }
+}
@@ -0,0 +1,619 @@
+//! # 批量统计模块
+//!
+//! 汇总一批分析结果的文件数、大小、处理时间、响度分布与频谱检测结果，
+//! 供命令行、示例程序及库的调用方直接使用，也可序列化为 `summary.json`。
+
+use crate::config::ThresholdSource;
+use crate::error::AnalyzerError;
+use crate::fingerprint;
+use crate::types::{AnalysisReport, AudioMetrics, QualityThresholds, SpectralVerdict};
+use crate::utils::stats;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 吞吐量统计中 1 MB 对应的字节数
+const BYTES_PER_MEGABYTE: f64 = 1024.0 * 1024.0;
+
+/// 高频RMS排序使用的高通截止频率 (Hz)，与频谱检测一致
+const HIGH_BAND_FREQUENCY: u32 = 18000;
+
+/// [`AnalysisSummary::worst`] 的排序依据
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MetricKey {
+    /// 质量分数最低的在前
+    #[default]
+    QualityScore,
+    /// 峰值电平最高的在前（最接近削波）
+    PeakLevel,
+    /// 18kHz 以上RMS最低的在前（高频缺失最严重，疑似有损来源）
+    HighBandRms,
+}
+
+impl MetricKey {
+    /// 排序依据名称，与命令行中的写法一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricKey::QualityScore => "score",
+            MetricKey::PeakLevel => "peak",
+            MetricKey::HighBandRms => "highband",
+        }
+    }
+
+    /// 按此依据比较两个结果，越"差"的越靠前；缺少该指标的结果返回 `None`
+    fn sort_value(self, metrics: &AudioMetrics, thresholds: &QualityThresholds) -> Option<f64> {
+        match self {
+            MetricKey::QualityScore => Some(f64::from(metrics.quality_score(thresholds))),
+            // 取负值，使峰值越高越靠前
+            MetricKey::PeakLevel => metrics.peak_amplitude_db.map(|peak| -peak),
+            MetricKey::HighBandRms => metrics.rms_db_above(HIGH_BAND_FREQUENCY),
+        }
+    }
+}
+
+impl std::fmt::Display for MetricKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for MetricKey {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "score" | "quality" => Ok(MetricKey::QualityScore),
+            "peak" => Ok(MetricKey::PeakLevel),
+            "highband" | "hf" => Ok(MetricKey::HighBandRms),
+            _ => Err(AnalyzerError::ConfigError(crate::tr!(
+                "未知的排序依据: {s}（可选: score, peak, highband）",
+                "Unknown sort key: {s} (expected: score, peak, highband)"
+            ))),
+        }
+    }
+}
+
+/// 一组数值的统计量
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ValueStats {
+    /// 参与统计的数值个数
+    pub count: usize,
+    /// 最小值
+    pub min: f64,
+    /// 平均值
+    pub mean: f64,
+    /// 中位数
+    pub median: f64,
+    /// 最大值
+    pub max: f64,
+}
+
+impl ValueStats {
+    /// 计算统计量，没有有效数值时返回 `None`
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        Some(Self {
+            count: values.iter().filter(|v| !v.is_nan()).count(),
+            min: stats::min(values)?,
+            mean: stats::mean(values)?,
+            median: stats::median(values)?,
+            max: stats::max(values)?,
+        })
+    }
+}
+
+/// 分析失败的文件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FailedFile {
+    /// 文件路径
+    pub path: String,
+    /// 错误信息
+    pub error: String,
+}
+
+/// 批量分析结果的汇总统计
+///
+/// 序列化时字段名为 camelCase，与 `analysis_data.json` 保持一致。
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisSummary {
+    /// 成功分析的文件总数
+    pub total_files: usize,
+    /// 分析失败的文件数
+    pub failed_files: usize,
+    /// 因取消而未分析的文件数
+    pub skipped_files: usize,
+    /// 因文件为空或过小而跳过的文件数
+    pub undersized_files: usize,
+    /// 因实际上不是音频（只有封面图片或时长过短）而跳过的文件数
+    pub not_audio_files: usize,
+    /// 核心指标完整的文件数
+    pub complete_files: usize,
+    /// 解码时报告异常的文件数
+    pub suspect_files: usize,
+    /// 文件总大小（字节）
+    pub total_size_bytes: u64,
+    /// 平均文件大小（字节）
+    pub average_size_bytes: f64,
+    /// 总处理时间（毫秒）
+    pub total_processing_ms: u64,
+    /// 平均处理时间（毫秒/文件）
+    pub average_processing_ms: f64,
+    /// 批量分析的实际耗时（毫秒，墙钟时间）；并行分析时小于 `total_processing_ms`
+    pub wall_clock_ms: u64,
+    /// 吞吐量：每秒分析的文件数（没有墙钟时间时为 `None`）
+    pub files_per_second: Option<f64>,
+    /// 吞吐量：每秒分析的数据量（MB，按 1024×1024 字节计）
+    pub megabytes_per_second: Option<f64>,
+    /// 综合响度 (LUFS) 统计（没有任何文件得到综合响度时为 `None`）
+    pub integrated_lufs: Option<ValueStats>,
+    /// LRA 统计（没有任何文件得到 LRA 时为 `None`）
+    pub lra: Option<ValueStats>,
+    /// 各频谱检测结果的文件数
+    pub verdicts: BTreeMap<SpectralVerdict, usize>,
+    /// 分析失败的文件及错误信息
+    pub failures: Vec<FailedFile>,
+}
+
+impl AnalysisSummary {
+    /// 使用默认质量阈值汇总分析结果
+    pub fn from_metrics(metrics: &[AudioMetrics]) -> Self {
+        Self::from_metrics_with_thresholds(metrics, &QualityThresholds::default())
+    }
+
+    /// 使用指定的质量阈值汇总分析结果（阈值用于频谱检测）
+    ///
+    /// 传入 [`crate::ProfileMatcher`] 时，匹配 `profile_rules` 的文件按该预设的阈值检测。
+    pub fn from_metrics_with_thresholds(
+        metrics: &[AudioMetrics],
+        thresholds: &impl ThresholdSource,
+    ) -> Self {
+        let total_files = metrics.len();
+        let total_size_bytes = metrics.iter().map(|m| m.file_size_bytes).sum();
+        let total_processing_ms = metrics.iter().map(|m| m.processing_time_ms).sum();
+        let average = |total: u64| {
+            if total_files == 0 {
+                0.0
+            } else {
+                total as f64 / total_files as f64
+            }
+        };
+
+        let lufs_values: Vec<f64> = metrics.iter().filter_map(|m| m.integrated_lufs).collect();
+        let lra_values: Vec<f64> = metrics.iter().filter_map(|m| m.lra).collect();
+
+        let mut verdicts = BTreeMap::new();
+        for m in metrics {
+            let verdict = m.spectral_verdict(&thresholds.thresholds_for(m.path()));
+            *verdicts.entry(verdict).or_insert(0) += 1;
+        }
+
+        Self {
+            total_files,
+            complete_files: metrics.iter().filter(|m| m.is_complete()).count(),
+            suspect_files: metrics.iter().filter(|m| m.is_suspect()).count(),
+            total_size_bytes,
+            average_size_bytes: average(total_size_bytes),
+            total_processing_ms,
+            average_processing_ms: average(total_processing_ms),
+            integrated_lufs: ValueStats::from_values(&lufs_values),
+            lra: ValueStats::from_values(&lra_values),
+            verdicts,
+            ..Default::default()
+        }
+    }
+
+    /// 根据批量分析的墙钟时间计算吞吐量
+    ///
+    /// 用于比较不同 `num_threads` 等设置下的整体速度；`elapsed` 为零时吞吐量为 `None`。
+    pub fn with_wall_clock(self, elapsed: Duration) -> Self {
+        let (files, bytes) = (self.total_files, self.total_size_bytes);
+        self.with_throughput(files, bytes, elapsed)
+    }
+
+    /// 按实际分析的文件数与字节数计算吞吐量
+    fn with_throughput(mut self, files: usize, bytes: u64, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs_f64();
+        self.wall_clock_ms = elapsed.as_millis() as u64;
+        if secs > 0.0 {
+            self.files_per_second = Some(files as f64 / secs);
+            self.megabytes_per_second = Some(bytes as f64 / BYTES_PER_MEGABYTE / secs);
+        }
+        self
+    }
+
+    /// 按所在目录（通常即专辑）分组汇总，使用默认质量阈值
+    ///
+    /// 用于发现同一专辑中来源不一致的曲目，例如混入了一首有损转码的文件。
+    pub fn by_directory(metrics: &[AudioMetrics]) -> BTreeMap<PathBuf, Self> {
+        Self::by_directory_with_thresholds(metrics, &QualityThresholds::default())
+    }
+
+    /// 按所在目录分组汇总，使用指定的质量阈值
+    ///
+    /// 按实际路径（[`AudioMetrics::path`]）分组，不受报告中路径写法的影响；
+    /// 没有父目录的路径归入空路径一组。
+    pub fn by_directory_with_thresholds(
+        metrics: &[AudioMetrics],
+        thresholds: &impl ThresholdSource,
+    ) -> BTreeMap<PathBuf, Self> {
+        let mut groups: BTreeMap<PathBuf, Vec<AudioMetrics>> = BTreeMap::new();
+        for m in metrics {
+            let dir = m.path().parent().map(Path::to_path_buf).unwrap_or_default();
+            groups.entry(dir).or_default().push(m.clone());
+        }
+
+        groups
+            .into_iter()
+            .map(|(dir, group)| (dir, Self::from_metrics_with_thresholds(&group, thresholds)))
+            .collect()
+    }
+
+    /// 按声学指纹找出重复的文件，使用默认相似度阈值
+    ///
+    /// 只比较带有 `fingerprint` 的结果（分析时启用 `fingerprint`）。
+    pub fn duplicate_groups(metrics: &[AudioMetrics]) -> Vec<Vec<PathBuf>> {
+        Self::duplicate_groups_with_threshold(metrics, fingerprint::DEFAULT_DUPLICATE_THRESHOLD)
+    }
+
+    /// 按声学指纹找出重复的文件，相似度达到 `threshold` (0.0-1.0) 的文件归为一组
+    ///
+    /// 相似关系可传递：A 与 B、B 与 C 相似时三者在同一组。只返回至少包含两个文件的组，
+    /// 组内按路径排序，各组按第一个路径排序。只比较指纹长度相近的文件，
+    /// 长度相差一倍以上的（如完整曲目与其片段）不会归为一组。
+    /// 文件先按子指纹分桶，只有共有若干相同子指纹的文件才逐一比较，大型音乐库不必两两比较。
+    pub fn duplicate_groups_with_threshold(
+        metrics: &[AudioMetrics],
+        threshold: f64,
+    ) -> Vec<Vec<PathBuf>> {
+        let entries: Vec<(&Path, Vec<u32>)> = metrics
+            .iter()
+            .filter_map(|m| {
+                let fingerprint = fingerprint::decode(m.fingerprint.as_deref()?)?;
+                Some((m.path(), fingerprint))
+            })
+            .collect();
+        let fingerprints: Vec<&[u32]> = entries.iter().map(|(_, fp)| fp.as_slice()).collect();
+
+        // 并查集：parent[i] 指向同组的代表元素
+        let mut parent: Vec<usize> = (0..entries.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        for (i, j) in fingerprint::candidate_pairs(&fingerprints) {
+            if fingerprint::similarity(fingerprints[i], fingerprints[j]) >= threshold {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+
+        let mut groups: BTreeMap<usize, Vec<PathBuf>> = BTreeMap::new();
+        for (i, (path, _)) in entries.iter().enumerate() {
+            let r = root(&mut parent, i);
+            groups.entry(r).or_default().push(path.to_path_buf());
+        }
+        let mut groups: Vec<Vec<PathBuf>> = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                group.sort();
+                group
+            })
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    /// 按 `by` 找出问题最严重的 `n` 个文件，使用默认质量阈值
+    pub fn worst(metrics: &[AudioMetrics], n: usize, by: MetricKey) -> Vec<&AudioMetrics> {
+        Self::worst_with_thresholds(metrics, n, by, &QualityThresholds::default())
+    }
+
+    /// 按 `by` 找出问题最严重的 `n` 个文件，使用指定的质量阈值计算质量分数
+    ///
+    /// 缺少该指标的文件（如没有 18kHz 频段数据）不参与排序；数值相同时按路径排序。
+    pub fn worst_with_thresholds<'a>(
+        metrics: &'a [AudioMetrics],
+        n: usize,
+        by: MetricKey,
+        thresholds: &impl ThresholdSource,
+    ) -> Vec<&'a AudioMetrics> {
+        let mut ranked: Vec<(f64, &AudioMetrics)> = metrics
+            .iter()
+            .filter_map(|m| Some((by.sort_value(m, &thresholds.thresholds_for(m.path()))?, m)))
+            .collect();
+        ranked.sort_by(|(a, ma), (b, mb)| {
+            a.total_cmp(b).then_with(|| ma.file_path.cmp(&mb.file_path))
+        });
+        ranked.into_iter().take(n).map(|(_, m)| m).collect()
+    }
+
+    /// 汇总批量分析报告，包括失败与跳过的文件
+    ///
+    /// 吞吐量按报告的墙钟时间计算，断点续跑时沿用的结果不计入。
+    pub fn from_report(report: &AnalysisReport, thresholds: &impl ThresholdSource) -> Self {
+        let resumed: HashSet<&Path> = report.resumed.iter().map(PathBuf::as_path).collect();
+        let analyzed = report
+            .succeeded
+            .iter()
+            .filter(|m| !resumed.contains(m.path()));
+        let (analyzed_files, analyzed_bytes) = analyzed.fold((0, 0), |(files, bytes), m| {
+            (files + 1, bytes + m.file_size_bytes)
+        });
+
+        Self {
+            failed_files: report.failed.len(),
+            skipped_files: report.skipped.len(),
+            undersized_files: report.undersized.len(),
+            not_audio_files: report.not_audio.len(),
+            failures: report
+                .failed
+                .iter()
+                .map(|(path, error)| FailedFile {
+                    path: path.display().to_string(),
+                    error: error.to_string(),
+                })
+                .collect(),
+            ..Self::from_metrics_with_thresholds(&report.succeeded, thresholds)
+        }
+        .with_throughput(analyzed_files, analyzed_bytes, report.elapsed)
+    }
+
+    /// 指标完整的文件所占百分比
+    pub fn complete_percentage(&self) -> f64 {
+        if self.total_files == 0 {
+            0.0
+        } else {
+            self.complete_files as f64 / self.total_files as f64 * 100.0
+        }
+    }
+
+    /// 指定频谱检测结果的文件数
+    pub fn verdict_count(&self, verdict: SpectralVerdict) -> usize {
+        self.verdicts.get(&verdict).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(lra: Option<f64>, rms_18k: Option<f64>, size: u64, time_ms: u64) -> AudioMetrics {
+        let mut metrics = AudioMetrics::new("test.flac".to_string(), size);
+        metrics.lra = lra;
+        metrics.processing_time_ms = time_ms;
+        if let Some(rms) = rms_18k {
+            metrics.highpass_rms.insert(18000, rms);
+        }
+        metrics
+    }
+
+    #[test]
+    fn test_throughput() {
+        let results = vec![
+            metrics(Some(8.0), None, 3 * 1024 * 1024, 900),
+            metrics(Some(9.0), None, 1024 * 1024, 700),
+        ];
+        let summary = AnalysisSummary::from_metrics(&results);
+        assert_eq!(summary.files_per_second, None);
+
+        let summary = summary.with_wall_clock(Duration::from_millis(500));
+        assert_eq!(summary.wall_clock_ms, 500);
+        assert_eq!(summary.files_per_second, Some(4.0));
+        assert_eq!(summary.megabytes_per_second, Some(8.0));
+
+        // 断点续跑沿用的结果不计入吞吐量
+        let mut resumed = metrics(Some(7.0), None, 1024 * 1024, 0);
+        resumed.file_path = "/music/done.flac".to_string();
+        let report = AnalysisReport {
+            succeeded: vec![results[0].clone(), resumed],
+            resumed: vec!["/music/done.flac".into()],
+            elapsed: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let summary = AnalysisSummary::from_report(&report, &QualityThresholds::default());
+        assert_eq!(summary.total_files, 2);
+        assert_eq!(summary.files_per_second, Some(1.0));
+        assert_eq!(summary.megabytes_per_second, Some(3.0));
+    }
+
+    #[test]
+    fn test_duplicate_groups() {
+        let with_fingerprint = |path: &str, values: &[u32]| {
+            let mut m = metrics(Some(8.0), None, 1000, 100);
+            m.file_path = path.to_string();
+            m.fingerprint = Some(fingerprint::encode(values));
+            m
+        };
+        let track: Vec<u32> = (0..40u32).map(|i| i.wrapping_mul(0x9e37_79b9)).collect();
+        let lossy: Vec<u32> = track.iter().map(|v| v ^ 1).collect();
+        let other: Vec<u32> = track.iter().map(|v| !v).collect();
+
+        let results = vec![
+            with_fingerprint("/music/b.mp3", &lossy),
+            with_fingerprint("/music/other.flac", &other),
+            with_fingerprint("/music/a.flac", &track),
+            with_fingerprint("/music/clip.flac", &track[..10]),
+            metrics(Some(8.0), None, 1000, 100),
+        ];
+        assert_eq!(
+            AnalysisSummary::duplicate_groups(&results),
+            vec![vec![
+                PathBuf::from("/music/a.flac"),
+                PathBuf::from("/music/b.mp3")
+            ]]
+        );
+        assert!(AnalysisSummary::duplicate_groups_with_threshold(&results, 1.0).is_empty());
+        assert!(AnalysisSummary::duplicate_groups(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_worst() {
+        let file = |path: &str, peak: f64, rms_18k: Option<f64>| {
+            let mut m = metrics(Some(9.0), rms_18k, 1000, 100);
+            m.file_path = path.to_string();
+            m.peak_amplitude_db = Some(peak);
+            m
+        };
+        let results = vec![
+            file("/music/a.flac", -6.0, Some(-60.0)),
+            file("/music/b.flac", 0.0, Some(-90.0)),
+            file("/music/c.flac", -1.0, None),
+            file("/music/d.flac", -3.0, Some(-75.0)),
+        ];
+        let paths = |worst: Vec<&AudioMetrics>| -> Vec<String> {
+            worst.into_iter().map(|m| m.file_path.clone()).collect()
+        };
+
+        assert_eq!(
+            paths(AnalysisSummary::worst(&results, 2, MetricKey::PeakLevel)),
+            ["/music/b.flac", "/music/c.flac"]
+        );
+        // 没有 18kHz 数据的文件不参与高频排序
+        assert_eq!(
+            paths(AnalysisSummary::worst(&results, 10, MetricKey::HighBandRms)),
+            ["/music/b.flac", "/music/d.flac", "/music/a.flac"]
+        );
+        let by_score = AnalysisSummary::worst(&results, 4, MetricKey::QualityScore);
+        let thresholds = QualityThresholds::default();
+        assert!(by_score
+            .windows(2)
+            .all(|pair| pair[0].quality_score(&thresholds) <= pair[1].quality_score(&thresholds)));
+        assert!(AnalysisSummary::worst(&results, 0, MetricKey::QualityScore).is_empty());
+
+        assert_eq!(
+            "HighBand".parse::<MetricKey>().unwrap(),
+            MetricKey::HighBandRms
+        );
+        assert!("loudness".parse::<MetricKey>().is_err());
+    }
+
+    #[test]
+    fn test_summary_from_metrics() {
+        let results = vec![
+            metrics(Some(4.0), Some(-65.0), 1000, 100),
+            metrics(Some(10.0), Some(-90.0), 3000, 300),
+            metrics(Some(7.0), Some(-65.0), 2000, 200),
+            metrics(None, None, 2000, 200),
+        ];
+
+        let summary = AnalysisSummary::from_metrics(&results);
+        assert_eq!(summary.total_files, 4);
+        assert_eq!(summary.total_size_bytes, 8000);
+        assert_eq!(summary.average_size_bytes, 2000.0);
+        assert_eq!(summary.total_processing_ms, 800);
+        assert_eq!(summary.average_processing_ms, 200.0);
+
+        let lra = summary.lra.unwrap();
+        assert_eq!(lra.count, 3);
+        assert_eq!(
+            (lra.min, lra.mean, lra.median, lra.max),
+            (4.0, 7.0, 7.0, 10.0)
+        );
+
+        assert_eq!(summary.verdict_count(SpectralVerdict::Good), 2);
+        assert_eq!(summary.verdict_count(SpectralVerdict::Fake), 1);
+        assert_eq!(summary.verdict_count(SpectralVerdict::Unknown), 1);
+        assert_eq!(summary.verdict_count(SpectralVerdict::Processed), 0);
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let summary = AnalysisSummary::from_metrics(&[]);
+        assert_eq!(summary.total_files, 0);
+        assert_eq!(summary.average_processing_ms, 0.0);
+        assert_eq!(summary.complete_percentage(), 0.0);
+        assert!(summary.lra.is_none());
+        assert!(summary.verdicts.is_empty());
+    }
+
+    #[test]
+    fn test_summary_by_directory() {
+        let track = |path: &str, rms_18k: f64| {
+            let mut m = metrics(Some(8.0), Some(rms_18k), 1000, 100);
+            m.file_path = path.to_string();
+            m
+        };
+        let results = vec![
+            track("/music/album-a/01.flac", -65.0),
+            track("/music/album-b/01.flac", -65.0),
+            track("/music/album-a/02.flac", -90.0),
+            track("loose.flac", -65.0),
+        ];
+
+        let albums = AnalysisSummary::by_directory(&results);
+        let dirs: Vec<&Path> = albums.keys().map(PathBuf::as_path).collect();
+        assert_eq!(
+            dirs,
+            [
+                Path::new(""),
+                Path::new("/music/album-a"),
+                Path::new("/music/album-b")
+            ]
+        );
+
+        let album_a = &albums[Path::new("/music/album-a")];
+        assert_eq!(album_a.total_files, 2);
+        assert_eq!(album_a.verdict_count(SpectralVerdict::Fake), 1);
+        assert_eq!(albums[Path::new("/music/album-b")].total_files, 1);
+        assert!(AnalysisSummary::by_directory(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_summary_from_report() {
+        let mut loud = metrics(Some(6.0), Some(-65.0), 1000, 100);
+        loud.integrated_lufs = Some(-8.0);
+        let mut quiet = metrics(Some(8.0), Some(-65.0), 1000, 100);
+        quiet.integrated_lufs = Some(-14.0);
+        let report = AnalysisReport {
+            succeeded: vec![loud, quiet],
+            failed: vec![(
+                "/music/broken.flac".into(),
+                AnalyzerError::Other("broken.flac 无法解码".to_string()),
+            )],
+            skipped: vec!["/music/later.flac".into()],
+            undersized: vec!["/music/empty.flac".into()],
+            not_audio: vec![(
+                "/music/cover.m4a".into(),
+                crate::types::NotAudioReason::AttachedPictureOnly,
+            )],
+            resumed: Vec::new(),
+            elapsed: Duration::from_secs(4),
+        };
+
+        let summary = AnalysisSummary::from_report(&report, &QualityThresholds::default());
+        assert_eq!(
+            (
+                summary.total_files,
+                summary.failed_files,
+                summary.skipped_files
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!(summary.undersized_files, 1);
+        assert_eq!(summary.not_audio_files, 1);
+        assert_eq!(summary.failures[0].path, "/music/broken.flac");
+        let lufs = summary.integrated_lufs.unwrap();
+        assert_eq!((lufs.min, lufs.mean, lufs.max), (-14.0, -11.0, -8.0));
+        assert_eq!(summary.wall_clock_ms, 4000);
+        assert_eq!(summary.files_per_second, Some(0.5));
+        assert_eq!(
+            summary.megabytes_per_second,
+            Some(2000.0 / BYTES_PER_MEGABYTE / 4.0)
+        );
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["failedFiles"], 1);
+        assert_eq!(json["integratedLufs"]["median"], -11.0);
+        assert_eq!(json["verdicts"]["good"], 2);
+        assert!(json["failures"][0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("broken.flac"));
+    }
+}
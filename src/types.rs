@@ -2,16 +2,45 @@
 //!
 //! 定义了音频分析器中使用的所有数据结构和类型。
 
+use crate::error::AnalyzerError;
+use crate::messages::{self, Locale};
+use crate::quality::QualityVerdict;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 静音频段的RMS标记值（dB）
+///
+/// 高通滤波后的频段完全没有能量时，ffmpeg 输出 `-inf`；
+/// 此时以该值记录，以便与解析失败（字段缺失）区分开。
+pub const SILENT_BAND_DB: f64 = -144.0;
+
+/// 判定双单声道时允许的声道间电平差 (dB)
+pub const DUAL_MONO_TOLERANCE_DB: f64 = 0.01;
+
+/// 有损编码格式（FFmpeg 编码名称）
+pub const LOSSY_CODECS: &[&str] = &[
+    "mp3", "mp2", "aac", "vorbis", "opus", "wmav1", "wmav2", "ac3", "eac3", "dts",
+];
+
+/// 检查是否为转码时关注的最低码率 (kbps)：标称码率达到此值的有损文件应保留完整的高频
+pub const TRANSCODE_CHECK_MIN_KBPS: u32 = 256;
 
 /// 音频文件的分析指标
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioMetrics {
-    /// 文件路径
+    /// 文件路径（用于序列化与显示，非UTF-8字符会被替换）
     #[serde(rename = "filePath")]
     pub file_path: String,
 
+    /// 原始文件路径（不经过有损转换，不参与序列化）
+    ///
+    /// 由 [`AudioMetrics::from_path`] 设置；反序列化得到的实例为 `None`，
+    /// 此时 [`AudioMetrics::path`] 回退到 `file_path`。
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+
     /// 文件大小（字节）
     #[serde(rename = "fileSizeBytes")]
     pub file_size_bytes: u64,
@@ -20,28 +49,181 @@ pub struct AudioMetrics {
     #[serde(rename = "lra")]
     pub lra: Option<f64>,
 
+    /// 综合响度 (Integrated Loudness, LUFS) - EBU R128 标准
+    #[serde(rename = "integratedLufs")]
+    pub integrated_lufs: Option<f64>,
+
     /// 峰值振幅 (dB)
     #[serde(rename = "peakAmplitudeDb")]
     pub peak_amplitude_db: Option<f64>,
 
+    /// 真峰值 (dBTP) - 过采样后的峰值，可能高于 0 dBTP
+    #[serde(rename = "truePeakDbtp")]
+    pub true_peak_dbtp: Option<f64>,
+
     /// 整体RMS电平 (dB)
     #[serde(rename = "overallRmsDb")]
     pub overall_rms_db: Option<f64>,
 
-    /// 16kHz以上频段的RMS电平 (dB)
-    #[serde(rename = "rmsDbAbove16k")]
-    pub rms_db_above_16k: Option<f64>,
+    /// 短时RMS最大值 (dB)：astats 各测量窗口中最响的一段
+    #[serde(rename = "rmsPeakDb")]
+    pub rms_peak_db: Option<f64>,
+
+    /// 短时RMS最小值 (dB)：最安静的一段，含完全静音的窗口时为空
+    #[serde(rename = "rmsTroughDb")]
+    pub rms_trough_db: Option<f64>,
+
+    /// 短时RMS范围 (dB)：最大值与最小值之差，适用于过短而无法计算 LRA 的内容
+    #[serde(rename = "rmsRangeDb")]
+    pub rms_range_db: Option<f64>,
+
+    /// 直流偏移（线性，满幅为1.0）
+    #[serde(rename = "dcOffset")]
+    pub dc_offset: Option<f64>,
+
+    /// 波峰因数 (dB)：峰值与RMS之差，可作为动态的简单参考
+    #[serde(rename = "crestFactorDb")]
+    pub crest_factor_db: Option<f64>,
+
+    /// 各声道的统计信息（整体数值仍以 Overall 块为准）
+    #[serde(rename = "channelStats", default)]
+    pub channel_stats: Vec<ChannelStats>,
+
+    /// 音频编码格式（如 `flac`、`mp3`）
+    #[serde(rename = "codec")]
+    pub codec: Option<String>,
+
+    /// 采样率 (Hz)
+    #[serde(rename = "sampleRateHz")]
+    pub sample_rate_hz: Option<u32>,
+
+    /// 声道数
+    #[serde(rename = "channels")]
+    pub channels: Option<u32>,
+
+    /// 位深度（有损编码等浮点格式为空）
+    #[serde(rename = "bitDepth")]
+    pub bit_depth: Option<u32>,
+
+    /// 文件中的音频流数量（只分析第一条音频流）
+    #[serde(rename = "audioStreamCount")]
+    pub audio_stream_count: Option<u32>,
+
+    /// 码率 (kbps)：优先取音频流的码率，音频流没有给出（多见于 VBR）时为容器的平均码率
+    #[serde(
+        rename = "bitrateKbps",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitrate_kbps: Option<u32>,
+
+    /// 编码规格（如 AAC 的 `LC`、`HE-AAC`），FFmpeg 没有给出时为空
+    #[serde(
+        rename = "codecProfile",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub codec_profile: Option<String>,
+
+    /// 文件时长（秒），无法确定时为空；只分析片段时仍为整个文件的时长
+    #[serde(
+        rename = "durationSecs",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_secs: Option<f64>,
+
+    /// 各高通截止频率 (Hz) 以上频段的RMS电平 (dB)
+    ///
+    /// 序列化时每个频率展开为独立字段（如 `rmsDbAbove18k`），
+    /// 16k/18k/20k 与旧版的字段名保持一致。
+    #[serde(flatten, with = "highpass_serde")]
+    pub highpass_rms: BTreeMap<u32, f64>,
+
+    /// 整体 astats Overall 块中的全部数值（键为FFmpeg输出的原始名称，如 `Noise floor dB`）
+    ///
+    /// 只在启用 `capture_raw_astats` 时填充；非数值与非有限值（如 `-inf`）不会收录。
+    #[serde(rename = "rawAstats", default, skip_serializing_if = "Option::is_none")]
+    pub raw_astats: Option<BTreeMap<String, f64>>,
+
+    /// 各指标对应的FFmpeg原始输出，用于排查解析异常的文件
+    ///
+    /// 只在启用 `keep_raw_output` 时填充。键为 `format`（输入流信息）、`ebur128`
+    /// （LRA、综合响度与真峰值）、`astats`（峰值、RMS 等整体统计），以及 `phaseCorrelation`、
+    /// `silentFraction` 与各高通频段字段名（如 `rmsDbAbove18k`）。
+    #[serde(rename = "rawOutput", default, skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<BTreeMap<String, String>>,
+
+    /// 静音部分占总时长的比例 (0.0-1.0)，无法确定时长时为空
+    #[serde(rename = "silentFraction", default)]
+    pub silent_fraction: Option<f64>,
+
+    /// 估算的削波采样数（每声道平均），见 [`AudioStats::estimate_clipped_samples`]
+    #[serde(rename = "clippedSamples", default)]
+    pub clipped_samples: Option<u64>,
+
+    /// 削波采样占总采样数的比例 (0.0-1.0)
+    #[serde(rename = "clippingRatio", default)]
+    pub clipping_ratio: Option<f64>,
+
+    /// 左右声道的相位相关系数 (-1.0-1.0)，只对立体声文件计算
+    ///
+    /// 1 表示左右相同（单声道兼容），0 表示互不相关，负值表示反相成分占优，
+    /// 混缩为单声道时会相互抵消。
+    #[serde(rename = "phaseCorrelation", default)]
+    pub phase_correlation: Option<f64>,
+
+    /// FFmpeg 解码时报告的损坏/截断警告（去重后的原始日志行）
+    #[serde(rename = "decodeWarnings", default)]
+    pub decode_warnings: Vec<String>,
+
+    /// FFmpeg 分析超时，指标只包含超时前已输出的部分，缺失的字段为空
+    #[serde(default)]
+    pub partial: bool,
+
+    /// 只分析了文件中的一段（`ffmpeg.analyze_start_secs`/`analyze_duration_secs`），
+    /// 此时 LRA、响度等指标只代表该片段而非整个文件；分析整个文件时为空
+    #[serde(
+        rename = "analysisWindow",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub analysis_window: Option<AnalysisWindow>,
+
+    /// 声学指纹（十六进制字符串，见 [`crate::fingerprint`]），只在启用 `fingerprint` 时计算
+    #[serde(
+        rename = "fingerprint",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fingerprint: Option<String>,
+
+    /// 频谱平坦度 (0.0-1.0)：越接近 1 越像白噪声，启用 `spectral_flatness` 时计算
+    #[serde(
+        rename = "spectralFlatness",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub spectral_flatness: Option<f64>,
 
-    /// 18kHz以上频段的RMS电平 (dB)
-    #[serde(rename = "rmsDbAbove18k")]
-    pub rms_db_above_18k: Option<f64>,
+    /// 本底噪声 (dB)，与 `spectralFlatness` 一同计算
+    #[serde(
+        rename = "noiseFloorDb",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub noise_floor_db: Option<f64>,
 
-    /// 20kHz以上频段的RMS电平 (dB)
-    #[serde(rename = "rmsDbAbove20k")]
-    pub rms_db_above_20k: Option<f64>,
+    /// 综合质量判定，分析完成后按分析器配置的质量阈值计算（见 [`AudioMetrics::verdict`]）
+    #[serde(
+        rename = "qualityVerdict",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub quality_verdict: Option<QualityVerdict>,
 
     /// 处理时间（毫秒）
-    #[serde(rename = "processingTimeMs")]
+    #[serde(rename = "processingTimeMs", default)]
     pub processing_time_ms: u64,
 }
 
@@ -50,32 +232,406 @@ impl AudioMetrics {
     pub fn new(file_path: String, file_size_bytes: u64) -> Self {
         Self {
             file_path,
+            source_path: None,
             file_size_bytes,
             lra: None,
+            integrated_lufs: None,
             peak_amplitude_db: None,
+            true_peak_dbtp: None,
             overall_rms_db: None,
-            rms_db_above_16k: None,
-            rms_db_above_18k: None,
-            rms_db_above_20k: None,
+            rms_peak_db: None,
+            rms_trough_db: None,
+            rms_range_db: None,
+            dc_offset: None,
+            crest_factor_db: None,
+            channel_stats: Vec::new(),
+            codec: None,
+            sample_rate_hz: None,
+            channels: None,
+            bit_depth: None,
+            audio_stream_count: None,
+            bitrate_kbps: None,
+            codec_profile: None,
+            duration_secs: None,
+            highpass_rms: BTreeMap::new(),
+            raw_astats: None,
+            raw_output: None,
+            silent_fraction: None,
+            clipped_samples: None,
+            clipping_ratio: None,
+            phase_correlation: None,
+            decode_warnings: Vec::new(),
+            partial: false,
+            analysis_window: None,
+            fingerprint: None,
+            spectral_flatness: None,
+            noise_floor_db: None,
+            quality_verdict: None,
             processing_time_ms: 0,
         }
     }
 
-    /// 检查数据完整性
+    /// 根据文件路径创建音频指标实例，保留原始路径
+    pub fn from_path(path: &Path, file_size_bytes: u64) -> Self {
+        let mut metrics = Self::new(path.to_string_lossy().to_string(), file_size_bytes);
+        metrics.source_path = Some(path.to_path_buf());
+        metrics
+    }
+
+    /// 获取文件路径，优先使用未经转换的原始路径
+    pub fn path(&self) -> &Path {
+        self.source_path
+            .as_deref()
+            .unwrap_or_else(|| Path::new(&self.file_path))
+    }
+
+    /// 检查数据完整性：[`AudioMetrics::missing_metrics`] 为空时视为完整
     pub fn is_complete(&self) -> bool {
-        self.lra.is_some() && self.peak_amplitude_db.is_some() && self.rms_db_above_18k.is_some()
+        self.missing_metrics().is_empty()
+    }
+
+    /// 缺失的核心指标（JSON字段名），按报告中的顺序排列
+    ///
+    /// 核心指标为 LRA、综合响度、峰值、整体RMS 与 18kHz 以上RMS（频谱判定所需）。
+    /// 位深度、相位相关等只对部分文件有意义的字段不在检查范围内。
+    pub fn missing_metrics(&self) -> Vec<&'static str> {
+        [
+            ("lra", self.lra.is_some()),
+            ("integratedLufs", self.integrated_lufs.is_some()),
+            ("peakAmplitudeDb", self.peak_amplitude_db.is_some()),
+            ("overallRmsDb", self.overall_rms_db.is_some()),
+            ("rmsDbAbove18k", self.rms_db_above(18000).is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, present)| (!present).then_some(name))
+        .collect()
+    }
+
+    /// 是否至少得到了一项响度、电平或频谱测量值
+    pub fn has_any_measurement(&self) -> bool {
+        self.lra.is_some()
+            || self.integrated_lufs.is_some()
+            || self.peak_amplitude_db.is_some()
+            || self.overall_rms_db.is_some()
+            || !self.highpass_rms.is_empty()
+    }
+
+    /// 获取指定高通截止频率 (Hz) 以上频段的RMS电平
+    pub fn rms_db_above(&self, frequency: u32) -> Option<f64> {
+        self.highpass_rms.get(&frequency).copied()
+    }
+
+    /// 根据高频能量判断频谱是否被截断（疑似有损转码）
+    ///
+    /// 以18kHz以上频段的RMS为主要依据：低于伪造阈值判为 [`SpectralVerdict::Fake`]，
+    /// 低于处理阈值判为 [`SpectralVerdict::Processed`]，达到良好阈值判为
+    /// [`SpectralVerdict::Good`]。介于处理阈值与良好阈值之间时，
+    /// 若20kHz以上频段低于伪造阈值（典型的有损编码低通截止），判为处理过。
+    pub fn spectral_verdict(&self, thresholds: &QualityThresholds) -> SpectralVerdict {
+        let Some(rms_18k) = self.rms_db_above(18000) else {
+            return SpectralVerdict::Unknown;
+        };
+
+        if rms_18k < thresholds.spectrum_fake_threshold {
+            SpectralVerdict::Fake
+        } else if rms_18k < thresholds.spectrum_processed_threshold {
+            SpectralVerdict::Processed
+        } else if rms_18k >= thresholds.spectrum_good_threshold {
+            SpectralVerdict::Good
+        } else {
+            match self.rms_db_above(20000) {
+                Some(rms_20k) if rms_20k < thresholds.spectrum_fake_threshold => {
+                    SpectralVerdict::Processed
+                }
+                _ => SpectralVerdict::Good,
+            }
+        }
+    }
+
+    /// 将综合响度调整到 `target_lufs` 所需的增益 (dB)
+    ///
+    /// 结果不做截断：正值表示需要提升，负值表示需要衰减；缺少综合响度时为 `None`。
+    pub fn gain_to_target(&self, target_lufs: f64) -> Option<f64> {
+        self.integrated_lufs.map(|lufs| target_lufs - lufs)
+    }
+
+    /// 直流偏移是否超过阈值
+    pub fn has_dc_offset(&self, thresholds: &QualityThresholds) -> bool {
+        self.dc_offset
+            .is_some_and(|offset| offset.abs() > thresholds.dc_offset_max)
+    }
+
+    /// 是否为"响且平"的过度压缩母带
+    ///
+    /// LRA 低于 `lra_low_max` 且整体 RMS 高于 `rms_loud_db` 时成立；
+    /// 单独的低 LRA 也可能是正常的安静录音，因此需要两者同时满足。
+    pub fn is_overcompressed(&self, thresholds: &QualityThresholds) -> bool {
+        matches!(
+            (self.lra, self.overall_rms_db),
+            (Some(lra), Some(rms)) if lra < thresholds.lra_low_max && rms > thresholds.rms_loud_db
+        )
+    }
+
+    /// 峰值是否达到削波阈值 `peak_clipping_db`
+    pub fn is_clipping(&self, thresholds: &QualityThresholds) -> bool {
+        self.peak_amplitude_db
+            .is_some_and(|peak| peak >= thresholds.peak_clipping_db)
+    }
+
+    /// 是否存在相位问题（相位相关系数低于 `phase_correlation_min`），单声道文件始终为 `false`
+    pub fn has_phase_issues(&self, thresholds: &QualityThresholds) -> bool {
+        self.phase_correlation
+            .is_some_and(|correlation| correlation < thresholds.phase_correlation_min)
+    }
+
+    /// 是否疑似由低采样率升频而来
+    ///
+    /// 声明采样率不低于 `upsampled_rate_min_hz`（如 96k/192k）的真高解析度文件在 20kHz 以上应有内容；
+    /// 20kHz 以上的 RMS 低于 `upsampled_rms_20k_max` 时，高采样率只是"虚标"。
+    /// 缺少采样率或 20kHz 频段数据时为 `false`。
+    pub fn looks_upsampled(&self, thresholds: &QualityThresholds) -> bool {
+        self.sample_rate_hz
+            .is_some_and(|rate| rate >= thresholds.upsampled_rate_min_hz)
+            && self
+                .rms_db_above(20000)
+                .is_some_and(|rms| rms < thresholds.upsampled_rms_20k_max)
+    }
+
+    /// 是否为有损编码（编码格式属于 [`LOSSY_CODECS`]）
+    pub fn is_lossy(&self) -> bool {
+        self.codec
+            .as_deref()
+            .is_some_and(|codec| LOSSY_CODECS.contains(&codec))
+    }
+
+    /// 是否疑似由低码率的有损文件转码而来
+    ///
+    /// 码率不低于 [`TRANSCODE_CHECK_MIN_KBPS`] 的有损文件（如标称 320k 的 MP3）应保留 18kHz 以上的内容；
+    /// 频谱判定为伪造或疑似处理时，说明高频早已被低码率编码截掉，高码率只是"虚标"。
+    /// 缺少码率或频谱数据时为 `false`。
+    pub fn looks_transcoded(&self, thresholds: &QualityThresholds) -> bool {
+        self.is_lossy()
+            && self
+                .bitrate_kbps
+                .is_some_and(|bitrate| bitrate >= TRANSCODE_CHECK_MIN_KBPS)
+            && matches!(
+                self.spectral_verdict(thresholds),
+                SpectralVerdict::Fake | SpectralVerdict::Processed
+            )
+    }
+
+    /// 是否几乎全部为静音（静音比例超过 `silent_fraction_max`）
+    ///
+    /// 用于发现空音轨和抓轨失败的文件，这类文件的响度数值没有参考意义。
+    pub fn is_effectively_silent(&self, thresholds: &QualityThresholds) -> bool {
+        self.silent_fraction
+            .is_some_and(|fraction| fraction > thresholds.silent_fraction_max)
+    }
+
+    /// 是否为伪立体声（左右声道完全相同的双单声道）
+    ///
+    /// 两个声道的峰值与RMS差异均小于 [`DUAL_MONO_TOLERANCE_DB`] 时判定为双单声道。
+    pub fn is_dual_mono(&self) -> bool {
+        let [left, right] = self.channel_stats.as_slice() else {
+            return false;
+        };
+
+        let close = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => (a - b).abs() < DUAL_MONO_TOLERANCE_DB,
+            _ => false,
+        };
+
+        close(left.peak_db, right.peak_db) && close(left.rms_db, right.rms_db)
+    }
+
+    /// 文件是否可疑：FFmpeg 能够解码但报告了数据损坏或截断
+    ///
+    /// 此时各项指标只反映成功解码的部分，不能代表完整文件。
+    pub fn is_suspect(&self) -> bool {
+        !self.decode_warnings.is_empty()
+    }
+
+    /// 文件是否实际上不是音频：时长短于 `min_duration_secs` 秒
+    ///
+    /// 多见于只有封面图片或几乎为空的音频容器，这类文件的各项指标没有意义。
+    /// 时长未知时返回 `false`；只有封面、没有音频流的文件无法得到指标，
+    /// 分析时直接报告为 [`NotAudioReason::AttachedPictureOnly`]。
+    pub fn is_not_really_audio(&self, min_duration_secs: f64) -> bool {
+        self.not_audio_reason(min_duration_secs).is_some()
+    }
+
+    /// 文件不是音频的原因，见 [`AudioMetrics::is_not_really_audio`]
+    pub fn not_audio_reason(&self, min_duration_secs: f64) -> Option<NotAudioReason> {
+        self.duration_secs
+            .filter(|&duration| duration < min_duration_secs)
+            .map(|duration_secs| NotAudioReason::TooShort { duration_secs })
+    }
+
+    /// 写入音频格式信息
+    pub fn set_format_info(&mut self, info: FormatInfo) {
+        self.codec = info.codec;
+        self.sample_rate_hz = info.sample_rate_hz;
+        self.channels = info.channels;
+        self.bit_depth = info.bit_depth;
+        self.audio_stream_count = Some(info.audio_stream_count);
+        self.bitrate_kbps = info.bitrate_kbps;
+        self.codec_profile = info.codec_profile;
+        self.duration_secs = info.duration_secs;
     }
 
     /// 获取文件名（不含路径）
     pub fn filename(&self) -> String {
-        PathBuf::from(&self.file_path)
+        self.path()
             .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("未知文件")
-            .to_string()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "未知文件".to_string())
     }
 }
 
+pub(crate) use highpass_serde::{
+    field_name as highpass_field_name, parse_field_name as parse_highpass_field_name,
+};
+
+/// 高通频段RMS的序列化支持
+///
+/// 将 `BTreeMap<u32, f64>` 展开为 `rmsDbAbove{N}k`（整千赫兹）或
+/// `rmsDbAbove{N}Hz` 字段；反序列化时忽略其他无关字段。
+mod highpass_serde {
+    use serde::de::{IgnoredAny, MapAccess, Visitor};
+    use serde::ser::SerializeMap;
+    use serde::{Deserializer, Serializer};
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    const FIELD_PREFIX: &str = "rmsDbAbove";
+
+    /// 频率对应的字段名
+    pub(crate) fn field_name(frequency: u32) -> String {
+        if frequency.is_multiple_of(1000) {
+            format!("{FIELD_PREFIX}{}k", frequency / 1000)
+        } else {
+            format!("{FIELD_PREFIX}{frequency}Hz")
+        }
+    }
+
+    /// 从字段名解析频率
+    pub(crate) fn parse_field_name(name: &str) -> Option<u32> {
+        let suffix = name.strip_prefix(FIELD_PREFIX)?;
+        if let Some(khz) = suffix.strip_suffix('k') {
+            khz.parse::<u32>().ok().and_then(|k| k.checked_mul(1000))
+        } else {
+            suffix.strip_suffix("Hz")?.parse::<u32>().ok()
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<u32, f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut out = serializer.serialize_map(Some(map.len()))?;
+        for (&frequency, value) in map {
+            out.serialize_entry(&field_name(frequency), value)?;
+        }
+        out.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<u32, f64>, D::Error> {
+        struct HighpassVisitor;
+
+        impl<'de> Visitor<'de> for HighpassVisitor {
+            type Value = BTreeMap<u32, f64>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("rmsDbAbove* 字段")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = BTreeMap::new();
+                while let Some(key) = access.next_key::<String>()? {
+                    match parse_field_name(&key) {
+                        Some(frequency) => {
+                            if let Some(value) = access.next_value::<Option<f64>>()? {
+                                map.insert(frequency, value);
+                            }
+                        }
+                        None => {
+                            access.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(HighpassVisitor)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_field_name_round_trip() {
+            assert_eq!(field_name(18000), "rmsDbAbove18k");
+            assert_eq!(field_name(19500), "rmsDbAbove19500Hz");
+            assert_eq!(parse_field_name("rmsDbAbove18k"), Some(18000));
+            assert_eq!(parse_field_name("rmsDbAbove19500Hz"), Some(19500));
+            assert_eq!(parse_field_name("overallRmsDb"), None);
+        }
+    }
+}
+
+/// 频谱截止检测结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpectralVerdict {
+    /// 高频几乎完全缺失，疑似由有损格式转码而来
+    Fake,
+    /// 高频明显衰减，疑似经过处理或低码率编码
+    Processed,
+    /// 高频能量正常
+    Good,
+    /// 缺少高频数据，无法判断
+    Unknown,
+}
+
+impl SpectralVerdict {
+    /// 获取检测结果的中文描述
+    pub fn description(&self) -> &'static str {
+        match self {
+            SpectralVerdict::Fake => "可疑 (伪造)",
+            SpectralVerdict::Processed => "疑似处理",
+            SpectralVerdict::Good => "频谱正常",
+            SpectralVerdict::Unknown => "未知",
+        }
+    }
+}
+
+/// 单个声道的统计信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    /// 声道序号（从1开始）
+    #[serde(rename = "channel")]
+    pub channel: u32,
+    /// 峰值电平 (dB)
+    #[serde(rename = "peakDb")]
+    pub peak_db: Option<f64>,
+    /// RMS电平 (dB)
+    #[serde(rename = "rmsDb")]
+    pub rms_db: Option<f64>,
+}
+
+/// 频谱平坦度与本底噪声（见 [`crate::AudioAnalyzer::extract_spectral_flatness`]）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralFlatness {
+    /// 各分析窗口、各声道频谱平坦度的平均值 (0.0-1.0)
+    pub flatness: f64,
+    /// astats 估计的本底噪声 (dB)，旧版FFmpeg不输出或为 `-inf` 时为 `None`
+    pub noise_floor_db: Option<f64>,
+}
+
 /// 音频统计信息（用于FFmpeg astats输出解析）
 #[derive(Debug, Clone)]
 pub struct AudioStats {
@@ -83,6 +639,22 @@ pub struct AudioStats {
     pub peak_db: Option<f64>,
     /// RMS电平 (dB)
     pub rms_db: Option<f64>,
+    /// 短时RMS最大值 (dB)（astats `RMS peak dB`）
+    pub rms_peak_db: Option<f64>,
+    /// 短时RMS最小值 (dB)（astats `RMS trough dB`）
+    pub rms_trough_db: Option<f64>,
+    /// 直流偏移（线性，满幅为1.0）
+    pub dc_offset: Option<f64>,
+    /// 波峰因数 (dB)
+    pub crest_factor_db: Option<f64>,
+    /// 处于峰值电平的采样数（astats `Peak count`，各声道平均）
+    pub peak_count: Option<f64>,
+    /// 每声道采样数（astats `Number of samples`）
+    pub sample_count: Option<u64>,
+    /// 平顶系数 (dB)，峰值处连续重复的采样越多越大（astats `Flat factor`）
+    pub flat_factor_db: Option<f64>,
+    /// 各声道统计信息
+    pub per_channel: Vec<ChannelStats>,
 }
 
 impl AudioStats {
@@ -91,6 +663,14 @@ impl AudioStats {
         Self {
             peak_db: None,
             rms_db: None,
+            rms_peak_db: None,
+            rms_trough_db: None,
+            dc_offset: None,
+            crest_factor_db: None,
+            peak_count: None,
+            sample_count: None,
+            flat_factor_db: None,
+            per_channel: Vec::new(),
         }
     }
 
@@ -98,6 +678,25 @@ impl AudioStats {
     pub fn has_data(&self) -> bool {
         self.peak_db.is_some() || self.rms_db.is_some()
     }
+
+    /// 短时RMS范围 (dB)：短时RMS最大值与最小值之差
+    pub fn rms_range_db(&self) -> Option<f64> {
+        Some(self.rms_peak_db? - self.rms_trough_db?)
+    }
+
+    /// 估算削波采样数
+    ///
+    /// 峰值低于 `clipping_db` 时为 0；否则峰值处的采样都视为削波，
+    /// 但平顶系数为 0（峰值只是孤立的采样点，常见于归一化到满幅的母带）时不计入。
+    pub fn estimate_clipped_samples(&self, clipping_db: f64) -> Option<u64> {
+        if self.peak_db? < clipping_db {
+            return Some(0);
+        }
+        if self.flat_factor_db.is_some_and(|flat| flat <= 0.0) {
+            return Some(0);
+        }
+        self.peak_count.map(|count| count.round() as u64)
+    }
 }
 
 impl Default for AudioStats {
@@ -106,8 +705,101 @@ impl Default for AudioStats {
     }
 }
 
+/// 文件不是真正的音频、因而跳过分析的原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotAudioReason {
+    /// 没有音频流，只有作为封面的图片
+    AttachedPictureOnly,
+    /// 时长短于配置的 `min_audio_duration_secs`
+    TooShort {
+        /// 文件时长（秒）
+        duration_secs: f64,
+    },
+}
+
+impl NotAudioReason {
+    /// 指定语言的原因描述
+    pub fn localized(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (NotAudioReason::AttachedPictureOnly, Locale::Zh) => {
+                "只有封面图片，没有音频流".to_string()
+            }
+            (NotAudioReason::AttachedPictureOnly, Locale::En) => {
+                "only cover art, no audio stream".to_string()
+            }
+            (NotAudioReason::TooShort { duration_secs }, Locale::Zh) => {
+                format!("时长只有 {duration_secs:.2} 秒")
+            }
+            (NotAudioReason::TooShort { duration_secs }, Locale::En) => {
+                format!("only {duration_secs:.2} seconds long")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for NotAudioReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.localized(messages::locale()))
+    }
+}
+
+/// 音频格式信息（用于FFmpeg输入流信息解析）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatInfo {
+    /// 编码格式
+    pub codec: Option<String>,
+    /// 采样率 (Hz)
+    pub sample_rate_hz: Option<u32>,
+    /// 声道数
+    pub channels: Option<u32>,
+    /// 位深度
+    pub bit_depth: Option<u32>,
+    /// 音频流数量
+    pub audio_stream_count: u32,
+    /// 码率 (kbps)
+    pub bitrate_kbps: Option<u32>,
+    /// 编码规格（如 `LC`、`HE-AAC`）
+    pub codec_profile: Option<String>,
+    /// 文件时长（秒），只有原生 WAV 解码时在此给出，FFmpeg分析时另行解析
+    pub duration_secs: Option<f64>,
+}
+
+/// EBU R128 响度汇总信息（用于FFmpeg ebur128输出解析）
+#[derive(Debug, Clone)]
+pub struct Ebur128Summary {
+    /// 综合响度 (LUFS)
+    pub integrated_lufs: Option<f64>,
+    /// 响度范围 (LU)
+    pub lra: Option<f64>,
+    /// 真峰值 (dBTP)
+    pub true_peak_dbtp: Option<f64>,
+}
+
+impl Ebur128Summary {
+    /// 创建新的响度汇总实例
+    pub fn new() -> Self {
+        Self {
+            integrated_lufs: None,
+            lra: None,
+            true_peak_dbtp: None,
+        }
+    }
+
+    /// 检查是否有有效数据
+    pub fn has_data(&self) -> bool {
+        self.integrated_lufs.is_some() || self.lra.is_some() || self.true_peak_dbtp.is_some()
+    }
+}
+
+impl Default for Ebur128Summary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// 质量评估阈值配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct QualityThresholds {
     /// 频谱伪造检测阈值 (dB)
     pub spectrum_fake_threshold: f64,
@@ -137,10 +829,34 @@ pub struct QualityThresholds {
     pub peak_good_db: f64,
     /// 峰值中等阈值 (dB)
     pub peak_medium_db: f64,
+
+    /// 直流偏移最大允许值（线性绝对值）
+    pub dc_offset_max: f64,
+
+    /// 整体 RMS 响亮阈值 (dB)，与低 LRA 同时出现时视为过度压缩
+    pub rms_loud_db: f64,
+
+    /// 静音比例上限 (0.0-1.0)，超过时视为近乎静音的文件
+    pub silent_fraction_max: f64,
+
+    /// 相位相关系数下限 (-1.0-1.0)，低于时提示单声道兼容性问题
+    pub phase_correlation_min: f64,
+
+    /// 高采样率下限 (Hz)，达到此采样率的文件应在 20kHz 以上有能量
+    pub upsampled_rate_min_hz: u32,
+    /// 高采样率文件 20kHz 以上 RMS 的上限 (dB)，低于时视为升频
+    pub upsampled_rms_20k_max: f64,
 }
 
 impl Default for QualityThresholds {
     fn default() -> Self {
+        Self::music()
+    }
+}
+
+impl QualityThresholds {
+    /// 音乐（默认）：LRA 8-12 LU 为优秀
+    pub fn music() -> Self {
         Self {
             spectrum_fake_threshold: -85.0,
             spectrum_processed_threshold: -80.0,
@@ -155,8 +871,241 @@ impl Default for QualityThresholds {
             peak_clipping_linear: 0.999,
             peak_good_db: -6.0,
             peak_medium_db: -3.0,
+            dc_offset_max: 0.01,
+            rms_loud_db: -12.0,
+            silent_fraction_max: 0.9,
+            phase_correlation_min: 0.0,
+            upsampled_rate_min_hz: 88200,
+            upsampled_rms_20k_max: -85.0,
+        }
+    }
+
+    /// 播客/有声书等语音内容
+    ///
+    /// 语音本身动态较小且通常经过压缩，LRA 3-8 LU 即为优秀；
+    /// 语音高频能量较少，频谱阈值相应放宽。
+    pub fn podcast() -> Self {
+        Self {
+            spectrum_fake_threshold: -100.0,
+            spectrum_processed_threshold: -95.0,
+            spectrum_good_threshold: -85.0,
+            lra_poor_max: 1.0,
+            lra_low_max: 2.0,
+            lra_excellent_min: 3.0,
+            lra_excellent_max: 8.0,
+            lra_acceptable_max: 12.0,
+            lra_too_high: 15.0,
+            peak_good_db: -3.0,
+            peak_medium_db: -1.5,
+            ..Self::music()
         }
     }
+
+    /// 母带交付检查：更严格的削波余量与动态要求
+    pub fn mastering() -> Self {
+        Self {
+            spectrum_fake_threshold: -80.0,
+            spectrum_processed_threshold: -75.0,
+            spectrum_good_threshold: -65.0,
+            lra_poor_max: 4.0,
+            lra_low_max: 7.0,
+            lra_excellent_min: 9.0,
+            lra_excellent_max: 14.0,
+            lra_acceptable_max: 16.0,
+            peak_clipping_db: -1.0,
+            peak_clipping_linear: 0.891,
+            peak_good_db: -6.0,
+            peak_medium_db: -3.0,
+            dc_offset_max: 0.001,
+            ..Self::music()
+        }
+    }
+
+    /// 验证阈值之间的关系
+    ///
+    /// 频谱阈值、LRA 分级与峰值分级都必须严格递增，否则判定结果没有意义；
+    /// 比例、相关系数等取值必须落在各自的有效范围内。手动构造或修改阈值后，
+    /// 可以在创建分析器之前先调用此方法检查，[`crate::AnalyzerConfig::validate`] 也会调用它。
+    pub fn validate(&self) -> Result<(), AnalyzerError> {
+        let chains: [(&str, &[(&str, f64)]); 3] = [
+            (
+                messages::text("频谱", "Spectrum"),
+                &[
+                    ("spectrum_fake_threshold", self.spectrum_fake_threshold),
+                    (
+                        "spectrum_processed_threshold",
+                        self.spectrum_processed_threshold,
+                    ),
+                    ("spectrum_good_threshold", self.spectrum_good_threshold),
+                ],
+            ),
+            (
+                "LRA",
+                &[
+                    ("lra_poor_max", self.lra_poor_max),
+                    ("lra_low_max", self.lra_low_max),
+                    ("lra_excellent_min", self.lra_excellent_min),
+                    ("lra_excellent_max", self.lra_excellent_max),
+                    ("lra_acceptable_max", self.lra_acceptable_max),
+                    ("lra_too_high", self.lra_too_high),
+                ],
+            ),
+            (
+                messages::text("峰值", "Peak"),
+                &[
+                    ("peak_good_db", self.peak_good_db),
+                    ("peak_medium_db", self.peak_medium_db),
+                    ("peak_clipping_db", self.peak_clipping_db),
+                ],
+            ),
+        ];
+
+        for (category, chain) in chains {
+            for pair in chain.windows(2) {
+                let ((lower_name, lower), (upper_name, upper)) = (pair[0], pair[1]);
+                // NaN 无法比较，同样视为顺序错误
+                if lower.partial_cmp(&upper) != Some(std::cmp::Ordering::Less) {
+                    return Err(AnalyzerError::ConfigError(crate::tr!(
+                        "{category}阈值配置不合理: {lower_name} ({lower}) 应小于 {upper_name} ({upper})",
+                        "Invalid {category} thresholds: {lower_name} ({lower}) must be less than {upper_name} ({upper})"
+                    )));
+                }
+            }
+        }
+
+        let ranges: [(&str, f64, f64, f64); 4] = [
+            ("peak_clipping_linear", self.peak_clipping_linear, 0.0, 1.0),
+            ("dc_offset_max", self.dc_offset_max, 0.0, 1.0),
+            ("silent_fraction_max", self.silent_fraction_max, 0.0, 1.0),
+            (
+                "phase_correlation_min",
+                self.phase_correlation_min,
+                -1.0,
+                1.0,
+            ),
+        ];
+        for (name, value, min, max) in ranges {
+            if !(min..=max).contains(&value) {
+                return Err(AnalyzerError::ConfigError(crate::tr!(
+                    "阈值配置不合理: {name} ({value}) 应在 {min} 到 {max} 之间",
+                    "Invalid threshold: {name} ({value}) must be between {min} and {max}"
+                )));
+            }
+        }
+
+        for (name, value) in [
+            ("rms_loud_db", self.rms_loud_db),
+            ("upsampled_rms_20k_max", self.upsampled_rms_20k_max),
+        ] {
+            if !value.is_finite() {
+                return Err(AnalyzerError::ConfigError(crate::tr!(
+                    "阈值配置不合理: {name} 必须是有限的数值",
+                    "Invalid threshold: {name} must be a finite number"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 质量评估预设
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityProfile {
+    /// 音乐
+    #[default]
+    Music,
+    /// 播客/语音
+    Podcast,
+    /// 母带交付检查
+    Mastering,
+}
+
+impl QualityProfile {
+    /// 预设对应的质量阈值
+    pub fn thresholds(&self) -> QualityThresholds {
+        match self {
+            QualityProfile::Music => QualityThresholds::music(),
+            QualityProfile::Podcast => QualityThresholds::podcast(),
+            QualityProfile::Mastering => QualityThresholds::mastering(),
+        }
+    }
+}
+
+impl std::str::FromStr for QualityProfile {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "music" => Ok(QualityProfile::Music),
+            "podcast" | "speech" => Ok(QualityProfile::Podcast),
+            "mastering" => Ok(QualityProfile::Mastering),
+            _ => Err(AnalyzerError::ConfigError(crate::tr!(
+                "未知的质量预设: {s}（可选: music, podcast, mastering）",
+                "Unknown quality profile: {s} (expected: music, podcast, mastering)"
+            ))),
+        }
+    }
+}
+
+/// 分析的时间片段
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisWindow {
+    /// 片段起点（秒）
+    pub start_secs: f64,
+    /// 片段长度上限（秒），为空表示一直分析到文件末尾
+    pub duration_secs: Option<f64>,
+}
+
+impl AnalysisWindow {
+    /// 总时长为 `file_duration` 秒的文件中实际被分析的长度（秒）
+    pub fn analyzed_length(&self, file_duration: f64) -> f64 {
+        let remaining = (file_duration - self.start_secs).max(0.0);
+        self.duration_secs
+            .map_or(remaining, |duration| duration.min(remaining))
+    }
+}
+
+/// 批量分析结果，包含成功的指标与失败文件的错误
+#[derive(Debug, Default)]
+pub struct AnalysisReport {
+    /// 成功分析的文件指标
+    pub succeeded: Vec<AudioMetrics>,
+    /// 分析失败的文件及其错误
+    pub failed: Vec<(PathBuf, AnalyzerError)>,
+    /// 因取消而未分析的文件
+    pub skipped: Vec<PathBuf>,
+    /// 因文件为空或小于 `min_file_size_bytes` 而跳过的文件（不算作失败）
+    pub undersized: Vec<PathBuf>,
+    /// 实际上不是音频（只有封面图片或时长过短）而跳过的文件及原因（不算作失败）
+    pub not_audio: Vec<(PathBuf, NotAudioReason)>,
+    /// 断点续跑（`resume`）时直接沿用已有结果的文件，其指标包含在 `succeeded` 中
+    pub resumed: Vec<PathBuf>,
+    /// 批量分析的实际耗时（墙钟时间）
+    pub elapsed: Duration,
+}
+
+impl AnalysisReport {
+    /// 提交分析的文件总数（含跳过的文件）
+    pub fn total(&self) -> usize {
+        self.succeeded.len()
+            + self.failed.len()
+            + self.skipped.len()
+            + self.undersized.len()
+            + self.not_audio.len()
+    }
+
+    /// 是否所有文件均分析成功（过小或不是音频而跳过的文件不影响结果）
+    pub fn is_all_succeeded(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+
+    /// 失败文件的路径列表，便于重试
+    pub fn failed_paths(&self) -> Vec<PathBuf> {
+        self.failed.iter().map(|(path, _)| path.clone()).collect()
+    }
 }
 
 /// 分析进度信息
@@ -183,6 +1132,71 @@ impl AnalysisProgress {
     }
 }
 
+/// 运行环境信息，用于反馈问题时附上
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentReport {
+    /// 库版本（[`crate::VERSION`]）
+    pub crate_version: String,
+    /// 操作系统与架构，如 `linux/x86_64`
+    pub platform: String,
+    /// 所用FFmpeg的路径（依赖项未初始化时为 `None`）
+    pub ffmpeg_path: Option<PathBuf>,
+    /// FFmpeg 版本信息（无法获取时为 `None`，原因见 `ffmpeg_error`）
+    pub ffmpeg_version: Option<String>,
+    /// 获取 FFmpeg 版本失败的原因
+    pub ffmpeg_error: Option<String>,
+    /// 检测到的逻辑CPU数
+    pub cpu_count: usize,
+    /// 批量分析实际使用的线程数
+    pub thread_count: usize,
+    /// Python分析模块是否可用
+    pub python_analyzer_available: bool,
+}
+
+impl std::fmt::Display for EnvironmentReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use crate::messages::text;
+
+        let unknown = text("未知", "unknown");
+        writeln!(f, "{}: {}", text("版本", "Version"), self.crate_version)?;
+        writeln!(f, "{}: {}", text("平台", "Platform"), self.platform)?;
+        writeln!(
+            f,
+            "FFmpeg: {}",
+            self.ffmpeg_path
+                .as_ref()
+                .map_or_else(|| unknown.to_string(), |path| path.display().to_string())
+        )?;
+        match (&self.ffmpeg_version, &self.ffmpeg_error) {
+            (Some(version), _) => {
+                writeln!(f, "{}: {version}", text("FFmpeg 版本", "FFmpeg version"))?
+            }
+            (None, Some(error)) => {
+                writeln!(f, "{}: {error}", text("FFmpeg 版本", "FFmpeg version"))?
+            }
+            (None, None) => writeln!(f, "{}: {unknown}", text("FFmpeg 版本", "FFmpeg version"))?,
+        }
+        writeln!(f, "{}: {}", text("CPU 数", "CPUs"), self.cpu_count)?;
+        writeln!(
+            f,
+            "{}: {}",
+            text("分析线程数", "Analysis threads"),
+            self.thread_count
+        )?;
+        write!(
+            f,
+            "{}: {}",
+            text("Python分析模块", "Python analyzer"),
+            if self.python_analyzer_available {
+                text("可用", "available")
+            } else {
+                text("不可用", "not available")
+            }
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,12 +1209,79 @@ mod tests {
         assert!(!metrics.is_complete());
     }
 
+    #[test]
+    fn test_highpass_rms_legacy_keys() {
+        let mut metrics = AudioMetrics::new("test.wav".to_string(), 1024);
+        metrics.highpass_rms.insert(18000, -72.5);
+        metrics.highpass_rms.insert(21000, -95.0);
+
+        let json = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(json["rmsDbAbove18k"], -72.5);
+        assert_eq!(json["rmsDbAbove21k"], -95.0);
+
+        let legacy = r#"{"filePath":"a.wav","fileSizeBytes":1,"lra":null,"peakAmplitudeDb":null,
+            "overallRmsDb":null,"rmsDbAbove16k":-60.0,"rmsDbAbove18k":null,"processingTimeMs":0}"#;
+        let parsed: AudioMetrics = serde_json::from_str(legacy).unwrap();
+        assert_eq!(parsed.rms_db_above(16000), Some(-60.0));
+        assert_eq!(parsed.rms_db_above(18000), None);
+    }
+
+    #[test]
+    fn test_has_dc_offset() {
+        let thresholds = QualityThresholds::default();
+        let mut metrics = AudioMetrics::new("test.wav".to_string(), 1024);
+        assert!(!metrics.has_dc_offset(&thresholds));
+
+        metrics.dc_offset = Some(-0.0001);
+        assert!(!metrics.has_dc_offset(&thresholds));
+
+        metrics.dc_offset = Some(-0.05);
+        assert!(metrics.has_dc_offset(&thresholds));
+    }
+
+    #[test]
+    fn test_is_dual_mono() {
+        let channel = |channel, peak_db, rms_db| ChannelStats {
+            channel,
+            peak_db: Some(peak_db),
+            rms_db: Some(rms_db),
+        };
+        let mut metrics = AudioMetrics::new("test.wav".to_string(), 1024);
+        assert!(!metrics.is_dual_mono());
+
+        metrics.channel_stats = vec![channel(1, -1.0, -18.0), channel(2, -1.0, -18.0)];
+        assert!(metrics.is_dual_mono());
+
+        metrics.channel_stats = vec![channel(1, -1.0, -18.0), channel(2, -1.4, -18.6)];
+        assert!(!metrics.is_dual_mono());
+    }
+
     #[test]
     fn test_audio_metrics_filename() {
         let metrics = AudioMetrics::new("/path/to/test.wav".to_string(), 1024);
         assert_eq!(metrics.filename(), "test.wav");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_is_preserved() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new("/music").join(OsStr::from_bytes(b"caf\xe9.flac"));
+        let metrics = AudioMetrics::from_path(&path, 1024);
+
+        assert_eq!(metrics.path(), path.as_path());
+        assert_eq!(metrics.file_path, "/music/caf\u{FFFD}.flac");
+        assert_eq!(metrics.filename(), "caf\u{FFFD}.flac");
+
+        // 序列化后原始路径不可恢复，回退到 file_path
+        let json = serde_json::to_string(&metrics).unwrap();
+        let parsed: AudioMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.source_path, None);
+        assert_eq!(parsed.path(), Path::new("/music/caf\u{FFFD}.flac"));
+    }
+
     #[test]
     fn test_quality_thresholds_default() {
         let thresholds = QualityThresholds::default();
@@ -208,6 +1289,53 @@ mod tests {
         assert_eq!(thresholds.lra_excellent_min, 8.0);
     }
 
+    #[test]
+    fn test_quality_profiles() {
+        let default = QualityThresholds::default();
+        let music = QualityThresholds::music();
+        assert_eq!(default.lra_excellent_min, music.lra_excellent_min);
+        assert_eq!(default.peak_clipping_db, music.peak_clipping_db);
+
+        let podcast: QualityProfile = "podcast".parse().unwrap();
+        assert!(podcast.thresholds().lra_low_max < music.lra_low_max);
+        assert_eq!(
+            "Mastering".parse::<QualityProfile>().unwrap(),
+            QualityProfile::Mastering
+        );
+        assert!("jazz".parse::<QualityProfile>().is_err());
+    }
+
+    #[test]
+    fn test_quality_thresholds_validate() {
+        for profile in [
+            QualityProfile::Music,
+            QualityProfile::Podcast,
+            QualityProfile::Mastering,
+        ] {
+            assert!(profile.thresholds().validate().is_ok());
+        }
+
+        let mut lra = QualityThresholds::music();
+        lra.lra_acceptable_max = 25.0;
+        let message = messages::with_locale(Some(Locale::Zh), || lra.validate())
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("lra_acceptable_max (25) 应小于 lra_too_high (20)"));
+
+        let mut silent = QualityThresholds::music();
+        silent.silent_fraction_max = 1.5;
+        let message = silent.validate().unwrap_err().to_string();
+        assert!(message.contains("silent_fraction_max"));
+
+        let mut phase = QualityThresholds::music();
+        phase.phase_correlation_min = f64::NAN;
+        assert!(phase.validate().is_err());
+
+        let mut loud = QualityThresholds::music();
+        loud.rms_loud_db = f64::INFINITY;
+        assert!(loud.validate().is_err());
+    }
+
     #[test]
     fn test_analysis_progress_percentage() {
         let progress = AnalysisProgress {
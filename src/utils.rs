@@ -2,36 +2,291 @@
 //!
 //! 提供音频分析器中使用的各种通用工具函数。
 
-use crate::error::{AnalyzerError, Result};
+use crate::error::{AnalyzerError, FfmpegErrorKind, Result};
+use crate::messages;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// 文件系统相关工具
 pub mod fs_utils {
     use super::*;
+    use rayon::prelude::*;
     use walkdir::WalkDir;
 
+    /// 目录扫描选项
+    #[derive(Debug, Clone, Default)]
+    pub struct ScanOptions {
+        /// 排除的 glob 模式，匹配规则见 [`scan_audio_files_filtered`]
+        pub exclude: Vec<glob::Pattern>,
+        /// 最大递归深度（`dir` 本身为 0，`Some(1)` 只扫描其直接包含的文件）
+        pub max_depth: Option<usize>,
+        /// 是否跟随符号链接；启用时 WalkDir 会检测链接循环，循环的目录将被跳过
+        pub follow_symlinks: bool,
+        /// 是否用 rayon 并行读取各个子目录（适合高延迟的网络文件系统）
+        ///
+        /// 选中的文件与顺序遍历相同，但顺序不确定，且会先遍历完整个目录树再产出结果。
+        pub parallel_scan: bool,
+    }
+
+    /// 目录扫描的详细结果，用于了解文件为何没有被选中
+    #[derive(Debug, Default)]
+    pub struct ScanReport {
+        /// 支持的音频文件
+        pub matched: Vec<PathBuf>,
+        /// 扩展名不受支持而跳过的文件数
+        pub skipped_unsupported: usize,
+        /// 无法读取而跳过的条目（如没有权限的目录、失效的符号链接）及对应的错误
+        pub skipped_errors: Vec<(PathBuf, std::io::Error)>,
+    }
+
     /// 递归扫描目录，查找支持的音频文件
     pub fn scan_audio_files<P: AsRef<Path>>(
         dir: P,
         supported_extensions: &[String],
     ) -> Result<Vec<PathBuf>> {
-        let mut audio_files = Vec::new();
+        audio_files_iter(dir, supported_extensions).collect()
+    }
 
-        for entry in WalkDir::new(dir) {
-            let entry = entry.map_err(|e| AnalyzerError::Io(e.into()))?;
+    /// 递归扫描目录，跳过匹配任一排除模式的文件和目录
+    ///
+    /// 模式同时与相对于 `dir` 的路径和条目名称比较，因此 `@eaDir` 可以排除
+    /// 任意层级的同名目录，而 `lossy/*` 只排除顶层的 `lossy` 目录中的内容。
+    /// 被排除的目录不会被遍历。
+    pub fn scan_audio_files_filtered<P: AsRef<Path>>(
+        dir: P,
+        supported_extensions: &[String],
+        exclude: &[glob::Pattern],
+    ) -> Result<Vec<PathBuf>> {
+        let options = ScanOptions {
+            exclude: exclude.to_vec(),
+            ..Default::default()
+        };
+        scan_audio_files_with_options(dir, supported_extensions, &options)
+    }
 
-            if entry.file_type().is_file() {
-                let path = entry.path();
-                if is_supported_audio_file(path, supported_extensions) {
-                    audio_files.push(path.to_path_buf());
+    /// 按扫描选项递归扫描目录，查找支持的音频文件
+    pub fn scan_audio_files_with_options<P: AsRef<Path>>(
+        dir: P,
+        supported_extensions: &[String],
+        options: &ScanOptions,
+    ) -> Result<Vec<PathBuf>> {
+        audio_files_iter_with_options(dir, supported_extensions, options.clone()).collect()
+    }
+
+    /// 惰性地递归扫描目录，逐个产出支持的音频文件
+    ///
+    /// 与 [`scan_audio_files`] 相同，但不会预先收集全部路径，适合超大目录。
+    pub fn audio_files_iter<'a, P: AsRef<Path>>(
+        dir: P,
+        supported_extensions: &'a [String],
+    ) -> impl Iterator<Item = Result<PathBuf>> + 'a {
+        audio_files_iter_with_options(dir, supported_extensions, ScanOptions::default())
+    }
+
+    /// 按扫描选项递归扫描目录，并统计被跳过的文件
+    ///
+    /// 与 [`scan_audio_files_with_options`] 选中的文件相同，但遇到无法读取的条目时不会中止，
+    /// 而是连同其路径记录在 [`ScanReport::skipped_errors`] 中继续扫描。
+    /// 被排除模式排除的条目不计入任何一项；符号链接循环直接跳过。
+    pub fn scan_audio_files_report<P: AsRef<Path>>(
+        dir: P,
+        supported_extensions: &[String],
+        options: &ScanOptions,
+    ) -> ScanReport {
+        let root = dir.as_ref().to_path_buf();
+        let mut report = ScanReport::default();
+        if options.parallel_scan {
+            for entry in walk_parallel(&root, options) {
+                match entry {
+                    Ok(path) if is_supported_audio_file(&path, supported_extensions) => {
+                        report.matched.push(path);
+                    }
+                    Ok(_) => report.skipped_unsupported += 1,
+                    Err(skipped) => report.skipped_errors.push(skipped),
                 }
             }
+            return report;
         }
+        for entry in walk(root.clone(), options.clone()) {
+            match entry {
+                Ok(entry) if !entry.file_type().is_file() => {}
+                Ok(entry) if is_supported_audio_file(entry.path(), supported_extensions) => {
+                    report.matched.push(entry.into_path());
+                }
+                Ok(_) => report.skipped_unsupported += 1,
+                Err(e) if e.loop_ancestor().is_some() => {}
+                Err(e) => {
+                    let path = e.path().map_or_else(|| root.clone(), Path::to_path_buf);
+                    let error = e.into_io_error().unwrap_or_else(|| {
+                        std::io::Error::other(messages::text(
+                            "无法读取目录条目",
+                            "Cannot read the directory entry",
+                        ))
+                    });
+                    report.skipped_errors.push((path, error));
+                }
+            }
+        }
+        report
+    }
+
+    /// 按扫描选项惰性地递归扫描目录
+    pub fn audio_files_iter_with_options<'a, P: AsRef<Path>>(
+        dir: P,
+        supported_extensions: &'a [String],
+        options: ScanOptions,
+    ) -> impl Iterator<Item = Result<PathBuf>> + 'a {
+        let root = dir.as_ref().to_path_buf();
+        let parallel = options
+            .parallel_scan
+            .then(|| walk_parallel(&root, &options))
+            .into_iter()
+            .flatten()
+            .filter_map(move |entry| match entry {
+                Ok(path) => {
+                    is_supported_audio_file(&path, supported_extensions).then_some(Ok(path))
+                }
+                Err((path, e)) => Some(Err(AnalyzerError::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("{}: {e}", path.display()),
+                )))),
+            });
+        let sequential = (!options.parallel_scan).then(|| {
+            walk(root, options).filter_map(move |entry| match entry {
+                Ok(entry) => (entry.file_type().is_file()
+                    && is_supported_audio_file(entry.path(), supported_extensions))
+                .then(|| Ok(entry.into_path())),
+                // 符号链接循环只需跳过，不影响其余文件的扫描
+                Err(e) if e.loop_ancestor().is_some() => None,
+                Err(e) => Some(Err(AnalyzerError::Io(e.into()))),
+            })
+        });
+        // 两种遍历方式只有一种生效，串联起来以保持返回类型不变
+        parallel.chain(sequential.into_iter().flatten())
+    }
+
+    /// 按扫描选项遍历目录，跳过被排除的条目（被排除的目录不会被遍历）
+    fn walk(
+        root: PathBuf,
+        options: ScanOptions,
+    ) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
+        let mut walker = WalkDir::new(&root).follow_links(options.follow_symlinks);
+        if let Some(max_depth) = options.max_depth {
+            walker = walker.max_depth(max_depth);
+        }
+
+        let exclude = options.exclude;
+        walker.into_iter().filter_entry(move |entry| {
+            entry.depth() == 0 || !is_excluded(entry.path(), &root, &exclude)
+        })
+    }
+
+    /// 并行遍历得到的条目：文件（含不支持的格式），或无法读取的条目及对应的错误
+    type ParallelEntry = std::result::Result<PathBuf, (PathBuf, std::io::Error)>;
+
+    /// 用 rayon 并行遍历目录树，每个子目录作为一个任务读取
+    ///
+    /// 排除模式、最大深度和符号链接（包括循环检测）的处理与 [`walk`] 相同，
+    /// 因此得到的文件集合一致，只是顺序不确定。
+    fn walk_parallel(root: &Path, options: &ScanOptions) -> Vec<ParallelEntry> {
+        let metadata = match fs::metadata(root) {
+            Ok(metadata) => metadata,
+            Err(e) => return vec![Err((root.to_path_buf(), e))],
+        };
+        if !metadata.is_dir() {
+            return vec![Ok(root.to_path_buf())];
+        }
+        if options.max_depth == Some(0) {
+            return Vec::new();
+        }
+        let ancestors = if options.follow_symlinks {
+            fs::canonicalize(root).into_iter().collect()
+        } else {
+            Vec::new()
+        };
+        walk_dir_parallel(root, root, 1, &ancestors, options)
+    }
+
+    /// 读取 `dir` 中深度为 `depth` 的条目，再并行遍历其中的子目录
+    ///
+    /// `ancestors` 是跟随符号链接时 `dir` 及其上级目录的规范路径，用于跳过链接循环。
+    fn walk_dir_parallel(
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        ancestors: &[PathBuf],
+        options: &ScanOptions,
+    ) -> Vec<ParallelEntry> {
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => return vec![Err((dir.to_path_buf(), e))],
+        };
+
+        let mut entries = Vec::new();
+        let mut subdirs = Vec::new();
+        for entry in read_dir {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    entries.push(Err((dir.to_path_buf(), e)));
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let file_type = if options.follow_symlinks {
+                fs::metadata(&path).map(|metadata| metadata.file_type())
+            } else {
+                entry.file_type()
+            };
+            let file_type = match file_type {
+                Ok(file_type) => file_type,
+                Err(e) => {
+                    entries.push(Err((path, e)));
+                    continue;
+                }
+            };
+            if is_excluded(&path, root, &options.exclude) {
+                continue;
+            }
+            if file_type.is_file() {
+                entries.push(Ok(path));
+            } else if file_type.is_dir() && options.max_depth.is_none_or(|max| depth < max) {
+                subdirs.push(path);
+            }
+        }
+
+        let nested: Vec<Vec<ParallelEntry>> = subdirs
+            .into_par_iter()
+            .map(|subdir| {
+                if !options.follow_symlinks {
+                    return walk_dir_parallel(root, &subdir, depth + 1, ancestors, options);
+                }
+                match fs::canonicalize(&subdir) {
+                    // 符号链接循环直接跳过
+                    Ok(canonical) if ancestors.contains(&canonical) => Vec::new(),
+                    Ok(canonical) => {
+                        let mut ancestors = ancestors.to_vec();
+                        ancestors.push(canonical);
+                        walk_dir_parallel(root, &subdir, depth + 1, &ancestors, options)
+                    }
+                    Err(e) => vec![Err((subdir, e))],
+                }
+            })
+            .collect();
+        entries.extend(nested.into_iter().flatten());
+        entries
+    }
 
-        Ok(audio_files)
+    /// 条目的相对路径或名称是否匹配任一排除模式
+    fn is_excluded(path: &Path, root: &Path, exclude: &[glob::Pattern]) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let name = path.file_name().map(Path::new).unwrap_or(relative);
+        exclude
+            .iter()
+            .any(|pattern| pattern.matches_path(relative) || pattern.matches_path(name))
     }
 
     /// 检查文件是否为支持的音频格式
@@ -46,12 +301,35 @@ pub mod fs_utils {
             .unwrap_or(false)
     }
 
+    /// 读取文件列表：每行一个路径，忽略空行与行尾的 `\r`
+    ///
+    /// 路径按原样返回，不检查是否存在或格式是否受支持，交由分析阶段报告为失败。
+    pub fn read_file_list<R: std::io::BufRead>(reader: R) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if !line.trim().is_empty() {
+                paths.push(PathBuf::from(line));
+            }
+        }
+        Ok(paths)
+    }
+
     /// 获取文件大小
     pub fn get_file_size<P: AsRef<Path>>(path: P) -> Result<u64> {
         let metadata = fs::metadata(path)?;
         Ok(metadata.len())
     }
 
+    /// 多个文件的总大小（字节），无法读取大小的文件按 0 计算
+    pub fn total_file_size(paths: &[PathBuf]) -> u64 {
+        paths
+            .iter()
+            .map(|path| get_file_size(path).unwrap_or(0))
+            .sum()
+    }
+
     /// 确保目录存在，如果不存在则创建
     pub fn ensure_dir_exists<P: AsRef<Path>>(dir: P) -> Result<()> {
         let path = dir.as_ref();
@@ -61,6 +339,24 @@ pub mod fs_utils {
         Ok(())
     }
 
+    /// 原子地写入文件
+    ///
+    /// 先写入同一目录下的临时文件并同步到磁盘，再 `rename` 到目标路径，
+    /// 因此写入中断时不会留下截断的文件，也不会破坏已有的文件。
+    pub fn write_atomic<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()> {
+        let path = path.as_ref();
+        let dir = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+
+        let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+        temp.write_all(bytes)?;
+        temp.as_file().sync_all()?;
+        temp.persist(path).map_err(|e| AnalyzerError::Io(e.error))?;
+        Ok(())
+    }
+
     /// 获取文件的显示名称（不含路径）
     pub fn get_display_name<P: AsRef<Path>>(path: P) -> String {
         path.as_ref()
@@ -75,24 +371,379 @@ pub mod fs_utils {
 pub mod process_utils {
     use super::*;
 
+    use std::io::Read;
+    use std::ops::{Deref, DerefMut};
+    use std::process::Child;
+    use std::sync::{Condvar, Mutex};
+    use std::thread;
+
+    /// 超时轮询间隔
+    const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+    /// 错误是否可能是暂时性的，重试同一命令有机会成功
+    ///
+    /// 包括资源暂时不足导致的启动失败（`EAGAIN`、内存不足、被信号中断），
+    /// 以及子进程被信号终止（通常是系统内存不足时被结束）。
+    /// 超时、非零退出码与解析错误说明文件本身有问题，不属于暂时性错误。
+    pub fn is_transient_error(error: &AnalyzerError) -> bool {
+        match error {
+            AnalyzerError::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::OutOfMemory
+            ),
+            AnalyzerError::FfmpegError { kind, .. } => *kind == FfmpegErrorKind::Signal,
+            _ => false,
+        }
+    }
+
+    /// 错误是否由子进程超时导致，此时错误中携带超时前已捕获的stderr
+    pub fn is_timeout_error(error: &AnalyzerError) -> bool {
+        matches!(
+            error,
+            AnalyzerError::FfmpegError {
+                kind: FfmpegErrorKind::Timeout,
+                ..
+            }
+        )
+    }
+
+    /// 由 [`ChildGuard`] 持有、尚未回收的子进程ID
+    static RUNNING_CHILDREN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+    /// 终止本模块启动且仍在运行的全部子进程
+    ///
+    /// 子进程位于独立的进程组，不会随终端的 Ctrl-C 一起结束；调用 `std::process::exit`
+    /// 立即退出前（此时守卫不会被析构）应先调用此函数，避免子进程在后台继续运行。
+    pub fn terminate_children() {
+        let children = RUNNING_CHILDREN.lock().unwrap_or_else(|e| e.into_inner());
+        for &pid in children.iter() {
+            #[cfg(unix)]
+            // SAFETY: kill 只发送信号，不涉及内存安全；PID 来自仍由守卫持有的子进程
+            unsafe {
+                libc::kill(pid as libc::pid_t, libc::SIGKILL);
+            }
+            #[cfg(not(unix))]
+            let _ = pid;
+        }
+    }
+
+    /// 低优先级子进程增加的 nice 值
+    pub const LOW_PRIORITY_NICE: i32 = 10;
+
+    /// 降低命令所启动子进程的调度优先级
+    ///
+    /// Unix 上在子进程中调用 `nice`，使其 nice 值增加 [`LOW_PRIORITY_NICE`]；
+    /// 其他平台暂不处理。[`clone_command`] 不会复制此设置，需要对复制后的命令重新调用。
+    pub fn set_low_priority(command: &mut Command) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+
+            // SAFETY: 闭包在 fork 之后、exec 之前执行，只调用异步信号安全的 nice，
+            // 不分配内存也不获取锁。调整失败（如受资源限制）时按原优先级继续运行。
+            unsafe {
+                command.pre_exec(|| {
+                    libc::nice(LOW_PRIORITY_NICE);
+                    Ok(())
+                });
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = command;
+    }
+
+    /// 复制命令的程序、参数、环境变量与工作目录，用于重新执行同一命令
+    pub fn clone_command(command: &Command) -> Command {
+        let mut clone = Command::new(command.get_program());
+        clone.args(command.get_args());
+        for (key, value) in command.get_envs() {
+            match value {
+                Some(value) => clone.env(key, value),
+                None => clone.env_remove(key),
+            };
+        }
+        if let Some(dir) = command.get_current_dir() {
+            clone.current_dir(dir);
+        }
+        clone
+    }
+
     /// 执行命令并获取stderr输出
-    pub fn run_command_capture_stderr(mut command: Command) -> Result<String> {
-        let output = command
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::piped())
-            .output()?;
+    pub fn run_command_capture_stderr(command: Command) -> Result<String> {
+        run_command_capture_stderr_timeout(command, None)
+    }
+
+    /// 执行命令并获取stderr输出，超过 `timeout` 仍未结束时终止子进程
+    ///
+    /// 超时与异常退出的处理见 [`run_command_capture_output_timeout`]。
+    pub fn run_command_capture_stderr_timeout(
+        command: Command,
+        timeout: Option<Duration>,
+    ) -> Result<String> {
+        run_command_capture_output_timeout(command, timeout).map(|(_, stderr)| stderr)
+    }
+
+    /// 执行命令并获取 `(stdout, stderr)` 输出，超过 `timeout` 仍未结束时终止子进程
+    ///
+    /// stdout 与 stderr 各在独立线程中持续读取，避免管道写满导致子进程阻塞。
+    /// 超时后子进程会被 kill 并回收，返回携带已捕获输出的 `FfmpegError`；
+    /// 进程以非零状态退出时同样返回 `FfmpegError`，并附带完整的stderr。
+    pub fn run_command_capture_output_timeout(
+        command: Command,
+        timeout: Option<Duration>,
+    ) -> Result<(String, String)> {
+        run_command_capture_raw_output_timeout(command, timeout)
+            .map(|(stdout, stderr)| (String::from_utf8_lossy(&stdout).to_string(), stderr))
+    }
+
+    /// 与 [`run_command_capture_output_timeout`] 相同，但stdout按原始字节返回
+    ///
+    /// 用于读取二进制输出（如 `chromaprint` 复用器的原始指纹）。
+    pub fn run_command_capture_raw_output_timeout(
+        mut command: Command,
+        timeout: Option<Duration>,
+    ) -> Result<(Vec<u8>, String)> {
+        // 任何提前返回（包括读取线程创建失败、等待出错）都会由守卫终止并回收子进程
+        let mut child = ChildGuard::spawn(
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )?;
+
+        let stdout_reader = spawn_pipe_reader(child.stdout.take(), "stdout")?;
+        let stderr_reader = spawn_pipe_reader(child.stderr.take(), "stderr")?;
+
+        let (status, timed_out) = match timeout {
+            Some(timeout) => {
+                let deadline = Instant::now() + timeout;
+                loop {
+                    if let Some(status) = child.try_wait()? {
+                        break (status, false);
+                    }
+                    if Instant::now() >= deadline {
+                        // 进程可能恰好在此刻退出，kill 失败可以忽略
+                        let _ = child.kill();
+                        break (child.wait()?, true);
+                    }
+                    thread::sleep(WAIT_POLL_INTERVAL);
+                }
+            }
+            None => (child.wait()?, false),
+        };
+
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+        let stderr = String::from_utf8_lossy(&stderr).to_string();
+
+        if timed_out {
+            let seconds = timeout.map(|t| t.as_secs_f64()).unwrap_or_default();
+            return Err(AnalyzerError::FfmpegError {
+                kind: FfmpegErrorKind::Timeout,
+                message: crate::tr!(
+                    "执行超时（{seconds:.1} 秒），已终止进程",
+                    "timed out after {seconds:.1} seconds, process killed"
+                ),
+                stderr: Some(stderr),
+            });
+        }
 
-        Ok(String::from_utf8_lossy(&output.stderr).to_string())
+        if !status.success() {
+            // Unix 上被信号终止的进程没有退出码
+            let (kind, message) = match status.code() {
+                None => (
+                    FfmpegErrorKind::Signal,
+                    messages::text(
+                        "进程被信号终止（可能因内存不足被系统结束）",
+                        "process killed by a signal (possibly by the system when out of memory)",
+                    )
+                    .to_string(),
+                ),
+                Some(code) => (
+                    FfmpegErrorKind::Exit(code),
+                    crate::tr!(
+                        "进程异常退出，退出代码: {code}",
+                        "process exited with code {code}"
+                    ),
+                ),
+            };
+            return Err(AnalyzerError::FfmpegError {
+                kind,
+                message,
+                stderr: Some(stderr.trim().to_string()),
+            });
+        }
+
+        Ok((stdout, stderr))
+    }
+
+    /// 在独立线程中读取子进程管道的全部输出
+    fn spawn_pipe_reader<R: Read + Send + 'static>(
+        pipe: Option<R>,
+        name: &str,
+    ) -> Result<thread::JoinHandle<Vec<u8>>> {
+        let mut pipe = pipe.ok_or_else(|| {
+            AnalyzerError::Other(crate::tr!(
+                "无法获取子进程{name}",
+                "Cannot capture the child process {name}"
+            ))
+        })?;
+        Ok(thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        }))
+    }
+
+    /// 在 `PATH` 环境变量列出的目录中查找可执行文件
+    pub fn find_executable(name: &str) -> Option<PathBuf> {
+        find_executable_in(name, &std::env::var_os("PATH")?)
+    }
+
+    /// 在 `search_path`（格式同 `PATH`）列出的目录中查找可执行文件，返回第一个匹配项
+    pub fn find_executable_in(name: &str, search_path: &std::ffi::OsStr) -> Option<PathBuf> {
+        std::env::split_paths(search_path)
+            .map(|dir| dir.join(name))
+            .find(|candidate| is_executable(candidate))
+    }
+
+    /// 路径是否为可执行的普通文件
+    fn is_executable(path: &Path) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(path)
+                .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        }
+        #[cfg(not(unix))]
+        {
+            path.is_file()
+        }
+    }
+
+    /// 计数信号量，用于限制同时运行的子进程数量
+    #[derive(Debug)]
+    pub struct Semaphore {
+        available: Mutex<usize>,
+        released: Condvar,
+    }
+
+    /// 信号量许可，离开作用域时自动归还
+    #[derive(Debug)]
+    pub struct SemaphorePermit<'a> {
+        semaphore: &'a Semaphore,
+    }
+
+    impl Semaphore {
+        /// 创建拥有 `permits` 个许可的信号量
+        pub fn new(permits: usize) -> Self {
+            Self {
+                available: Mutex::new(permits),
+                released: Condvar::new(),
+            }
+        }
+
+        /// 获取一个许可，没有可用许可时阻塞等待
+        pub fn acquire(&self) -> SemaphorePermit<'_> {
+            let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+            while *available == 0 {
+                available = self
+                    .released
+                    .wait(available)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+            *available -= 1;
+            SemaphorePermit { semaphore: self }
+        }
+    }
+
+    impl Drop for SemaphorePermit<'_> {
+        fn drop(&mut self) {
+            let mut available = self
+                .semaphore
+                .available
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *available += 1;
+            self.semaphore.released.notify_one();
+        }
+    }
+
+    /// 子进程守卫，离开作用域时终止仍在运行的子进程并回收
+    ///
+    /// 本模块启动的子进程都由守卫持有：调用方提前返回、线程 panic 时子进程不会成为孤儿进程，
+    /// 已结束的子进程也总会被 `wait` 回收，不会留下僵尸进程。
+    #[derive(Debug)]
+    pub struct ChildGuard {
+        child: Child,
+    }
+
+    impl ChildGuard {
+        /// 启动命令并由守卫持有子进程
+        ///
+        /// Unix 上子进程放入独立的进程组：终端的 Ctrl-C 只发给本程序，
+        /// 正在分析的文件可以正常完成，需要立即退出时由 [`terminate_children`] 终止子进程。
+        pub fn spawn(command: &mut Command) -> Result<Self> {
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::CommandExt;
+                command.process_group(0);
+            }
+            let child = command.spawn()?;
+            RUNNING_CHILDREN
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(child.id());
+            Ok(Self { child })
+        }
+
+        /// 子进程ID
+        pub fn id(&self) -> u32 {
+            self.child.id()
+        }
+    }
+
+    impl Deref for ChildGuard {
+        type Target = Child;
+
+        fn deref(&self) -> &Child {
+            &self.child
+        }
+    }
+
+    impl DerefMut for ChildGuard {
+        fn deref_mut(&mut self) -> &mut Child {
+            &mut self.child
+        }
+    }
+
+    impl Drop for ChildGuard {
+        fn drop(&mut self) {
+            // 已回收的子进程 try_wait 返回 Some，此时不再发送信号，避免误杀复用了该PID的进程
+            if let Ok(None) = self.child.try_wait() {
+                let _ = self.child.kill();
+            }
+            let _ = self.child.wait();
+
+            let pid = self.child.id();
+            RUNNING_CHILDREN
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|&running| running != pid);
+        }
     }
 
     /// 检查命令是否执行成功
     pub fn check_command_success(mut command: Command) -> Result<bool> {
-        let status = command
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()?;
+        let status = ChildGuard::spawn(
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null()),
+        )?
+        .wait()?;
 
         Ok(status.success())
     }
@@ -153,6 +804,53 @@ pub mod string_utils {
     }
 }
 
+/// 基础统计工具
+///
+/// 所有函数在输入为空时返回 `None`；NaN 会被忽略。
+pub mod stats {
+    /// 算术平均值
+    pub fn mean(values: &[f64]) -> Option<f64> {
+        let values = sorted(values);
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    }
+
+    /// 中位数（偶数个值时取中间两个值的平均）
+    pub fn median(values: &[f64]) -> Option<f64> {
+        percentile(values, 50.0)
+    }
+
+    /// 百分位数（`p` 取 0-100，超出范围时截断），相邻值之间线性插值
+    pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+        let values = sorted(values);
+        if values.is_empty() {
+            return None;
+        }
+
+        let rank = p.clamp(0.0, 100.0) / 100.0 * (values.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let weight = rank - lower as f64;
+        Some(values[lower] + (values[upper] - values[lower]) * weight)
+    }
+
+    /// 最小值
+    pub fn min(values: &[f64]) -> Option<f64> {
+        sorted(values).first().copied()
+    }
+
+    /// 最大值
+    pub fn max(values: &[f64]) -> Option<f64> {
+        sorted(values).last().copied()
+    }
+
+    /// 去掉 NaN 并升序排列
+    fn sorted(values: &[f64]) -> Vec<f64> {
+        let mut values: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+        values.sort_by(f64::total_cmp);
+        values
+    }
+}
+
 /// 性能测量工具
 pub struct Timer {
     start: Instant,
@@ -191,6 +889,15 @@ impl Timer {
             string_utils::format_duration(self.elapsed())
         );
     }
+
+    /// 以 `debug` 级别记录经过的时间
+    pub fn log_elapsed(&self) {
+        log::debug!(
+            "{}: {}",
+            self.name,
+            string_utils::format_duration(self.elapsed())
+        );
+    }
 }
 
 /// 用户输入工具
@@ -201,7 +908,13 @@ pub mod input_utils {
     /// 从用户获取文件夹路径
     pub fn get_folder_path_from_user() -> Result<PathBuf> {
         loop {
-            print!("请输入要递归处理的音乐顶层文件夹路径: ");
+            print!(
+                "{}",
+                messages::text(
+                    "请输入要递归处理的音乐顶层文件夹路径: ",
+                    "Enter the top-level music folder to scan recursively: "
+                )
+            );
             io::stdout().flush()?;
 
             let mut input = String::new();
@@ -213,8 +926,12 @@ pub mod input_utils {
                 return Ok(path.canonicalize()?);
             } else {
                 eprintln!(
-                    "错误: \"{}\" 不是一个有效的文件夹路径或不存在，请重新输入。",
-                    path.display()
+                    "{}",
+                    crate::tr!(
+                        "错误: \"{}\" 不是一个有效的文件夹路径或不存在，请重新输入。",
+                        "Error: \"{}\" is not an existing folder, please try again.",
+                        path.display()
+                    )
                 );
             }
         }
@@ -238,6 +955,25 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_stats() {
+        let values = [4.0, 1.0, 3.0, 2.0, f64::NAN];
+        assert_eq!(stats::mean(&values), Some(2.5));
+        assert_eq!(stats::median(&values), Some(2.5));
+        assert_eq!(stats::median(&[5.0, 1.0, 3.0]), Some(3.0));
+        assert_eq!(stats::min(&values), Some(1.0));
+        assert_eq!(stats::max(&values), Some(4.0));
+
+        assert_eq!(stats::percentile(&values, 0.0), Some(1.0));
+        assert_eq!(stats::percentile(&values, 100.0), Some(4.0));
+        assert_eq!(stats::percentile(&values, 25.0), Some(1.75));
+        assert_eq!(stats::percentile(&values, 150.0), Some(4.0));
+        assert_eq!(stats::percentile(&[7.0], 90.0), Some(7.0));
+
+        assert_eq!(stats::mean(&[]), None);
+        assert_eq!(stats::median(&[f64::NAN]), None);
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(string_utils::format_file_size(0), "0 B");
@@ -272,6 +1008,92 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_run_command_capture_stderr() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("echo captured >&2");
+        let stderr = process_utils::run_command_capture_stderr(command).unwrap();
+        assert_eq!(stderr.trim(), "captured");
+    }
+
+    #[test]
+    fn test_run_command_nonzero_exit() {
+        let run = |locale| {
+            messages::with_locale(Some(locale), || {
+                let mut command = Command::new("sh");
+                command
+                    .arg("-c")
+                    .arg("echo 'Invalid data found' >&2; exit 3");
+                process_utils::run_command_capture_stderr(command)
+            })
+        };
+
+        match run(messages::Locale::Zh) {
+            Err(AnalyzerError::FfmpegError {
+                kind,
+                message,
+                stderr,
+            }) => {
+                assert_eq!(kind, FfmpegErrorKind::Exit(3));
+                assert!(message.ends_with("退出代码: 3"));
+                assert_eq!(stderr.as_deref(), Some("Invalid data found"));
+            }
+            other => panic!("应该返回 FfmpegError: {other:?}"),
+        }
+
+        // 错误描述使用调用时的语言
+        match run(messages::Locale::En) {
+            Err(AnalyzerError::FfmpegError { message, .. }) => {
+                assert_eq!(message, "process exited with code 3")
+            }
+            other => panic!("应该返回 FfmpegError: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_timeout_kills_process() {
+        let mut command = Command::new("sleep");
+        command.arg("5");
+
+        let start = Instant::now();
+        let result = process_utils::run_command_capture_stderr_timeout(
+            command,
+            Some(Duration::from_millis(100)),
+        );
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        match result {
+            Err(AnalyzerError::FfmpegError { kind, .. }) => {
+                assert_eq!(kind, FfmpegErrorKind::Timeout)
+            }
+            other => panic!("应该返回超时错误: {other:?}"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_child_guard_kills_on_drop() {
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let guard = process_utils::ChildGuard::spawn(&mut command).unwrap();
+        let pid = guard.id() as libc::pid_t;
+        // 子进程位于独立的进程组，终端的 Ctrl-C 不会直接发给它
+        // SAFETY: getpgid 只查询进程信息
+        assert_eq!(unsafe { libc::getpgid(pid) }, pid);
+
+        let start = Instant::now();
+        drop(guard);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        // 子进程已被终止并回收，PID 不再存在（也不是僵尸进程）
+        // SAFETY: 信号 0 只检查进程是否存在，不会发送任何信号
+        assert_eq!(unsafe { libc::kill(pid, 0) }, -1);
+
+        // 已自行结束的子进程同样会被回收
+        let mut command = Command::new("true");
+        let mut guard = process_utils::ChildGuard::spawn(&mut command).unwrap();
+        assert!(guard.wait().unwrap().success());
+    }
+
     #[test]
     fn test_timer() {
         let timer = Timer::new("test");
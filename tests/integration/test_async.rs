@@ -0,0 +1,51 @@
+//! # 异步分析接口集成测试
+//!
+//! 需要启用 `tokio` 特性：`cargo test --features tokio`
+
+#![cfg(all(unix, feature = "tokio"))]
+
+use super::test_pipeline::{config_with_fake_ffmpeg, initialized};
+use futures_util::StreamExt;
+use std::fs;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_analyze_file_async() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let track = music.path().join("track.flac");
+    fs::write(&track, b"audio").unwrap();
+
+    let analyzer = Arc::new(initialized(config_with_fake_ffmpeg(tools.path())));
+    let metrics = analyzer.analyze_file_async(track).await.unwrap();
+    assert_eq!(metrics.peak_amplitude_db, Some(-7.0));
+}
+
+#[tokio::test]
+async fn test_analyze_files_async_keeps_input_order() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let mut paths = Vec::new();
+    for name in ["c.flac", "a.flac", "missing.flac", "b.flac"] {
+        let path = music.path().join(name);
+        if name != "missing.flac" {
+            fs::write(&path, b"audio").unwrap();
+        }
+        paths.push(path);
+    }
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.num_threads = Some(2);
+    let analyzer = Arc::new(initialized(config));
+    let results: Vec<_> = analyzer.analyze_files_async(paths).collect().await;
+
+    assert_eq!(results.len(), 4);
+    assert!(results[2].is_err());
+    let names: Vec<String> = results
+        .iter()
+        .filter_map(|result| result.as_ref().ok())
+        .map(|metrics| metrics.filename())
+        .collect();
+    assert_eq!(names, ["c.flac", "a.flac", "b.flac"]);
+}
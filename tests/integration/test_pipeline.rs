@@ -0,0 +1,625 @@
+//! # 完整分析流程集成测试
+//!
+//! 使用模拟的FFmpeg脚本，测试从扫描目录到写出报告的完整流程
+
+#![cfg(unix)]
+
+use audio_analyzer_ultimate::types::QualityProfile;
+use audio_analyzer_ultimate::{
+    AnalysisSummary, AnalyzerConfig, AudioAnalyzer, Locale, MetricKey, OutputFormat, PathStyle,
+    ProfileRule,
+};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use tempfile::TempDir;
+
+/// 写出一个模拟的FFmpeg脚本：`-version` 输出版本号，其余调用输出固定的分析日志
+fn fake_ffmpeg(dir: &Path) -> PathBuf {
+    let script = "#!/bin/sh
+[ \"$1\" = -version ] && { echo 'ffmpeg version fake'; exit 0; }
+cat >&2 <<'LOG'
+Input #0, flac, from 'input.flac':
+  Duration: 00:00:10.00, start: 0.000000, bitrate: 900 kb/s
+  Stream #0:0: Audio: flac, 44100 Hz, stereo, s16
+[Parsed_ebur128_0 @ 0x1] Summary:
+
+  Integrated loudness:
+    I:         -12.0 LUFS
+
+  Loudness range:
+    LRA:         9.0 LU
+[astats@overall @ 0x2] Overall
+[astats@overall @ 0x2] Peak level dB: -7.000000
+[astats@overall @ 0x2] RMS level dB: -18.000000
+[astats@hp18000 @ 0x3] Overall
+[astats@hp18000 @ 0x3] RMS level dB: -65.000000
+LOG
+";
+    let path = dir.join("ffmpeg");
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+pub(super) fn config_with_fake_ffmpeg(tools: &Path) -> AnalyzerConfig {
+    let mut config = AnalyzerConfig {
+        show_progress: false,
+        ..Default::default()
+    };
+    config.ffmpeg.binary_path = Some(fake_ffmpeg(tools));
+    config
+}
+
+pub(super) fn initialized(config: AnalyzerConfig) -> AudioAnalyzer {
+    let mut analyzer = AudioAnalyzer::new(config).unwrap();
+    analyzer.set_progress_observer(None);
+    analyzer.initialize_dependencies().unwrap();
+    analyzer
+}
+
+#[test]
+fn test_run_writes_all_reports() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::create_dir(music.path().join("album")).unwrap();
+    fs::write(music.path().join("album/01.flac"), b"one").unwrap();
+    fs::write(music.path().join("album/02.flac"), b"two").unwrap();
+    fs::write(music.path().join("cover.jpg"), b"jpg").unwrap();
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = vec![OutputFormat::Json, OutputFormat::Ndjson, OutputFormat::Csv];
+    let report = initialized(config)
+        .run(music.path(), output.path())
+        .unwrap();
+
+    assert_eq!(report.succeeded.len(), 2);
+    assert!(report.is_all_succeeded());
+    assert!(report.succeeded.iter().all(|m| m.lra == Some(9.0)));
+
+    let json = fs::read_to_string(output.path().join("analysis_data.json")).unwrap();
+    let entries: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+
+    let ndjson = fs::read_to_string(output.path().join("analysis_data.ndjson")).unwrap();
+    assert_eq!(ndjson.lines().count(), 2);
+
+    let csv = fs::read_to_string(output.path().join("audio_quality_report.csv")).unwrap();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains("01.flac") && csv.contains("02.flac"));
+    assert!(!output.path().join("summary.json").exists());
+}
+
+#[test]
+fn test_run_writes_summary_with_failures() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    let track = music.path().join("track.flac");
+    fs::write(&track, b"audio").unwrap();
+    let files = vec![track, music.path().join("missing.flac")];
+
+    let report = initialized(config_with_fake_ffmpeg(tools.path()))
+        .run_files(&files, output.path(), None)
+        .unwrap();
+    assert_eq!(report.failed.len(), 1);
+
+    // 默认格式包含汇总统计，与 analysis_data.json 写在同一目录
+    assert!(output.path().join("analysis_data.json").exists());
+    let summary = fs::read_to_string(output.path().join("summary.json")).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&summary).unwrap();
+    assert_eq!(summary["totalFiles"], 1);
+    assert_eq!(summary["failedFiles"], 1);
+    assert_eq!(summary["integratedLufs"]["mean"], -12.0);
+    assert_eq!(summary["lra"]["max"], 9.0);
+    assert_eq!(summary["verdicts"]["good"], 1);
+    assert!(summary["failures"][0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("missing.flac"));
+}
+
+#[test]
+fn test_run_writes_markdown_report() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    let track = music.path().join("track.flac");
+    fs::write(&track, b"audio").unwrap();
+    let files = vec![track, music.path().join("missing.flac")];
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = OutputFormat::parse_list(["md"]).unwrap();
+    initialized(config)
+        .run_files(&files, output.path(), None)
+        .unwrap();
+
+    assert!(!output.path().join("summary.json").exists());
+    let markdown = fs::read_to_string(output.path().join("report.md")).unwrap();
+    assert!(markdown.starts_with("# "));
+    assert!(markdown.contains("missing.flac"));
+    let table: Vec<&str> = markdown
+        .lines()
+        .filter(|line| line.starts_with('|'))
+        .collect();
+    // 表头、分隔行与一个成功的文件
+    assert_eq!(table.len(), 3);
+    assert!(table[2].contains("track.flac"));
+    assert!(table[2].contains("| 9.0 | -12.0 | -7.0 |"));
+    assert!(!table[2].contains("**"));
+}
+
+#[test]
+fn test_run_without_intermediate_json() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::write(music.path().join("track.flac"), b"audio").unwrap();
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.keep_intermediate_json = false;
+    let report = initialized(config.clone())
+        .run(music.path(), output.path())
+        .unwrap();
+
+    assert_eq!(report.succeeded.len(), 1);
+    // 只写出JSON以外的报告，也不留下临时文件
+    let output_files = |dir: &Path| {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort_unstable();
+        names
+    };
+    assert_eq!(
+        output_files(output.path()),
+        ["audio_quality_report.csv", "summary.json"]
+    );
+
+    // 只有JSON一种格式时不写出任何文件，仍返回分析结果
+    let output = TempDir::new().unwrap();
+    config.output.formats = vec![OutputFormat::Json];
+    let report = initialized(config)
+        .run(music.path(), output.path())
+        .unwrap();
+    assert_eq!(report.succeeded.len(), 1);
+    assert!(output_files(output.path()).is_empty());
+}
+
+#[test]
+fn test_results_keep_input_order() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    // 名称含 slow 的文件分析得更慢，完成顺序与输入顺序不同
+    let inner = tools.path().join("inner");
+    fs::create_dir(&inner).unwrap();
+    let ffmpeg = tools.path().join("ffmpeg");
+    let script = format!(
+        "#!/bin/sh\ncase \"$*\" in *slow*) sleep 0.3 ;; esac\nexec {} \"$@\"\n",
+        fake_ffmpeg(&inner).display()
+    );
+    fs::write(&ffmpeg, script).unwrap();
+    fs::set_permissions(&ffmpeg, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let names = [
+        "slow-1.flac",
+        "fast-1.flac",
+        "missing.flac",
+        "slow-2.flac",
+        "fast-2.flac",
+    ];
+    let paths: Vec<PathBuf> = names.iter().map(|name| music.path().join(name)).collect();
+    for path in paths.iter().filter(|p| !p.ends_with("missing.flac")) {
+        fs::write(path, b"audio").unwrap();
+    }
+
+    let mut config = AnalyzerConfig {
+        show_progress: false,
+        num_threads: Some(4),
+        ..Default::default()
+    };
+    config.ffmpeg.binary_path = Some(ffmpeg);
+    let report = initialized(config).analyze_files_detailed(&paths).unwrap();
+
+    let succeeded: Vec<String> = report.succeeded.iter().map(|m| m.filename()).collect();
+    assert_eq!(
+        succeeded,
+        ["slow-1.flac", "fast-1.flac", "slow-2.flac", "fast-2.flac"]
+    );
+    assert_eq!(report.failed.len(), 1);
+    assert!(report.failed[0].0.ends_with("missing.flac"));
+}
+
+#[test]
+fn test_dry_run_lists_files_without_ffmpeg() {
+    let music = TempDir::new().unwrap();
+    fs::create_dir(music.path().join("album")).unwrap();
+    fs::write(music.path().join("album/01.flac"), b"one").unwrap();
+    fs::write(music.path().join("album/02.mp3"), b"second").unwrap();
+    fs::write(music.path().join("cover.jpg"), b"jpg").unwrap();
+
+    // 未初始化依赖项也可以使用
+    let analyzer = AudioAnalyzer::new(AnalyzerConfig::default()).unwrap();
+    let (mut files, total_bytes) = analyzer.dry_run(music.path()).unwrap();
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            music.path().join("album/01.flac"),
+            music.path().join("album/02.mp3")
+        ]
+    );
+    assert_eq!(total_bytes, 9);
+
+    let (files, _) = analyzer.dry_run(&music.path().join("cover.jpg")).unwrap();
+    assert!(files.is_empty());
+    let empty = TempDir::new().unwrap();
+    assert_eq!(analyzer.dry_run(empty.path()).unwrap(), (Vec::new(), 0));
+}
+
+#[test]
+fn test_empty_directory_is_not_an_error() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    fs::write(music.path().join("cover.jpg"), b"jpg").unwrap();
+    let analyzer = initialized(config_with_fake_ffmpeg(tools.path()));
+
+    assert!(analyzer.analyze_directory(music.path()).unwrap().is_empty());
+    let report = analyzer.run(music.path(), music.path()).unwrap();
+    assert_eq!(report.total(), 0);
+
+    // 无法读取的目录仍然报错
+    assert!(analyzer
+        .analyze_directory(music.path().join("missing"))
+        .is_err());
+}
+
+#[test]
+fn test_run_single_file_and_selected_formats() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let track = music.path().join("track.flac");
+    fs::write(&track, b"audio").unwrap();
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = vec![OutputFormat::Csv];
+    let report = initialized(config).run(&track, music.path()).unwrap();
+
+    assert_eq!(report.succeeded.len(), 1);
+    assert!(music.path().join("audio_quality_report.csv").exists());
+    assert!(!music.path().join("analysis_data.json").exists());
+}
+
+#[test]
+fn test_run_files_reports_missing_and_unsupported() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    let track = music.path().join("track.flac");
+    fs::write(&track, b"audio").unwrap();
+    fs::write(music.path().join("notes.txt"), b"text").unwrap();
+    let files = vec![
+        track,
+        music.path().join("missing.flac"),
+        music.path().join("notes.txt"),
+    ];
+
+    let report = initialized(config_with_fake_ffmpeg(tools.path()))
+        .run_files(&files, output.path(), None)
+        .unwrap();
+
+    assert_eq!(report.succeeded.len(), 1);
+    let failed: Vec<&PathBuf> = report.failed.iter().map(|(path, _)| path).collect();
+    assert_eq!(failed, [&files[1], &files[2]]);
+    assert!(output.path().join("audio_quality_report.csv").exists());
+}
+
+#[test]
+fn test_undersized_files_are_skipped_not_failed() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let files: Vec<PathBuf> = ["empty.flac", "stub.m4a", "track.flac"]
+        .iter()
+        .map(|name| music.path().join(name))
+        .collect();
+    fs::write(&files[0], b"").unwrap();
+    fs::write(&files[1], b"stub").unwrap();
+    fs::write(&files[2], b"full audio").unwrap();
+
+    // 未设置下限时只跳过空文件
+    let analyzer = initialized(config_with_fake_ffmpeg(tools.path()));
+    let report = analyzer.analyze_files_detailed(&files).unwrap();
+    assert_eq!(report.succeeded.len(), 2);
+    assert_eq!(report.undersized, [files[0].clone()]);
+    assert!(report.failed.is_empty());
+    assert!(analyzer
+        .analyze_file(&files[0])
+        .unwrap_err()
+        .localized(Locale::Zh)
+        .contains("0 字节"));
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.min_file_size_bytes = Some(8);
+    let report = initialized(config).analyze_files_detailed(&files).unwrap();
+    assert_eq!(report.succeeded.len(), 1);
+    assert_eq!(report.undersized, files[..2]);
+    assert!(report.is_all_succeeded());
+}
+
+#[test]
+fn test_resume_skips_unchanged_files() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    let files: Vec<PathBuf> = ["a.flac", "b.flac", "c.flac"]
+        .iter()
+        .map(|name| music.path().join(name))
+        .collect();
+    for file in &files {
+        fs::write(file, b"audio").unwrap();
+    }
+
+    // 模拟中断的上一次运行：只完成了前两个文件
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = vec![OutputFormat::Json];
+    initialized(config.clone())
+        .run_files(&files[..2], output.path(), None)
+        .unwrap();
+
+    // 标记已有结果，以便区分沿用的结果与重新分析的结果
+    let json_path = output.path().join("analysis_data.json");
+    let mut entries: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    for entry in entries.as_array_mut().unwrap() {
+        entry["lra"] = serde_json::json!(1.0);
+    }
+    fs::write(&json_path, entries.to_string()).unwrap();
+    // b.flac 在两次运行之间发生了变化
+    fs::write(&files[1], b"re-encoded audio").unwrap();
+
+    config.resume = true;
+    let report = initialized(config)
+        .run_files(&files, output.path(), None)
+        .unwrap();
+    assert_eq!(report.resumed, [files[0].clone()]);
+    assert_eq!(report.succeeded.len(), 3);
+
+    let entries: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    let lra_of = |name: &str| {
+        entries
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["filePath"].as_str().unwrap().ends_with(name))
+            .map(|entry| entry["lra"].as_f64().unwrap())
+    };
+    assert_eq!(entries.as_array().unwrap().len(), 3);
+    assert_eq!(lra_of("a.flac"), Some(1.0));
+    assert_eq!(lra_of("b.flac"), Some(9.0));
+    assert_eq!(lra_of("c.flac"), Some(9.0));
+}
+
+#[test]
+fn test_resume_reads_ndjson_of_interrupted_run() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    let files: Vec<PathBuf> = ["a.flac", "b.flac", "c.flac"]
+        .iter()
+        .map(|name| music.path().join(name))
+        .collect();
+    for file in &files {
+        fs::write(file, b"audio").unwrap();
+    }
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = vec![OutputFormat::Json, OutputFormat::Ndjson];
+    initialized(config.clone())
+        .run_files(&files[..2], output.path(), None)
+        .unwrap();
+
+    // 模拟在写出JSON之前中断：只留下逐个写出的NDJSON，其中 b.flac 缺少核心指标
+    let json_path = output.path().join("analysis_data.json");
+    let ndjson_path = output.path().join("analysis_data.ndjson");
+    fs::remove_file(&json_path).unwrap();
+    let lines: Vec<String> = fs::read_to_string(&ndjson_path)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let mut entry: serde_json::Value = serde_json::from_str(line).unwrap();
+            entry["lra"] = serde_json::json!(1.0);
+            if entry["filePath"].as_str().unwrap().ends_with("b.flac") {
+                entry["lra"] = serde_json::Value::Null;
+            }
+            entry.to_string()
+        })
+        .collect();
+    fs::write(&ndjson_path, lines.join("\n") + "\n").unwrap();
+
+    config.resume = true;
+    let report = initialized(config)
+        .run_files(&files, output.path(), None)
+        .unwrap();
+    assert_eq!(report.resumed, [files[0].clone()]);
+    assert_eq!(report.succeeded.len(), 3);
+
+    // 新的NDJSON同样包含沿用的结果
+    let ndjson = fs::read_to_string(&ndjson_path).unwrap();
+    assert_eq!(ndjson.lines().count(), 3);
+    let entries: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    let lra_of = |name: &str| {
+        entries
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["filePath"].as_str().unwrap().ends_with(name))
+            .map(|entry| entry["lra"].as_f64().unwrap())
+    };
+    assert_eq!(lra_of("a.flac"), Some(1.0));
+    assert_eq!(lra_of("b.flac"), Some(9.0));
+    assert_eq!(lra_of("c.flac"), Some(9.0));
+}
+
+#[test]
+fn test_path_style_relative_to_input() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::create_dir(music.path().join("album")).unwrap();
+    fs::write(music.path().join("album/01.flac"), b"one").unwrap();
+    fs::write(music.path().join("02.flac"), b"two").unwrap();
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = vec![OutputFormat::Json, OutputFormat::Csv];
+    config.output.path_style = PathStyle::RelativeToInput;
+    let report = initialized(config.clone())
+        .run(music.path(), output.path())
+        .unwrap();
+    // 返回的结果仍使用实际路径
+    assert!(report.succeeded.iter().all(|m| m.path().is_absolute()));
+
+    let json_path = output.path().join("analysis_data.json");
+    let entries: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    let mut paths: Vec<&str> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["filePath"].as_str().unwrap())
+        .collect();
+    paths.sort_unstable();
+    assert_eq!(paths, ["02.flac", "album/01.flac"]);
+    let csv = fs::read_to_string(output.path().join("audio_quality_report.csv")).unwrap();
+    assert!(csv.contains(",album/01.flac,"));
+    assert!(!csv.contains(&music.path().to_string_lossy().into_owned()));
+
+    // 断点续跑将已有结果还原为实际路径后匹配
+    config.resume = true;
+    let report = initialized(config)
+        .run(music.path(), output.path())
+        .unwrap();
+    assert_eq!(report.resumed.len(), 2);
+    assert!(report.succeeded.iter().all(|m| m.path().is_absolute()));
+    let entries: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    let mut paths: Vec<&str> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["filePath"].as_str().unwrap())
+        .collect();
+    paths.sort_unstable();
+    assert_eq!(paths, ["02.flac", "album/01.flac"]);
+}
+
+#[test]
+fn test_profile_rules_match_real_paths() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::create_dir(music.path().join("spoken")).unwrap();
+    fs::write(music.path().join("spoken/01.flac"), b"one").unwrap();
+    fs::write(music.path().join("02.flac"), b"two").unwrap();
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.formats = vec![OutputFormat::Csv];
+    config.output.path_style = PathStyle::RelativeToInput;
+    // 只与实际的完整路径匹配，报告中的相对路径不会匹配
+    config.profile_rules = vec![ProfileRule {
+        pattern: format!("{}/spoken/*", music.path().display()),
+        profile: QualityProfile::Podcast,
+    }];
+    let analyzer = initialized(config);
+    let report = analyzer.run(music.path(), output.path()).unwrap();
+
+    // LRA 9 LU 对音乐为优秀，超出了播客预设的理想范围
+    let csv = fs::read_to_string(output.path().join("audio_quality_report.csv")).unwrap();
+    let score_of = |name: &str| {
+        csv.lines()
+            .find(|line| line.contains(name))
+            .and_then(|line| line.split(',').next())
+            .map(str::to_string)
+            .unwrap()
+    };
+    assert_eq!(score_of(",02.flac,"), "100");
+    assert_ne!(score_of(",spoken/01.flac,"), "100");
+
+    // 排序与汇总使用同样的阈值
+    let worst = AnalysisSummary::worst_with_thresholds(
+        &report.succeeded,
+        1,
+        MetricKey::QualityScore,
+        analyzer.profile_matcher(),
+    );
+    assert!(worst[0].path().ends_with("spoken/01.flac"));
+}
+
+#[test]
+fn test_run_skips_unreadable_entries() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::write(music.path().join("a.flac"), b"audio").unwrap();
+    std::os::unix::fs::symlink(music.path().join("missing"), music.path().join("broken")).unwrap();
+
+    // 跟随符号链接时失效的链接无法读取，跳过后继续分析其余文件
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.follow_symlinks = true;
+    let analyzer = initialized(config);
+    let report = analyzer.run(music.path(), output.path()).unwrap();
+    assert_eq!(report.succeeded.len(), 1);
+    assert!(report.is_all_succeeded());
+
+    // 输入目录本身不存在时仍然返回错误
+    assert!(analyzer
+        .run(&music.path().join("missing"), output.path())
+        .is_err());
+}
+
+#[test]
+fn test_run_refuses_to_overwrite_before_analysis() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::write(music.path().join("a.flac"), b"audio").unwrap();
+    fs::write(output.path().join("analysis_data.json"), b"old").unwrap();
+
+    let mut config = config_with_fake_ffmpeg(tools.path());
+    config.output.overwrite = false;
+    let analyzer = initialized(config);
+
+    assert!(analyzer.run(music.path(), output.path()).is_err());
+    // 已有文件保持不变，其他报告也没有写出
+    assert_eq!(
+        fs::read_to_string(output.path().join("analysis_data.json")).unwrap(),
+        "old"
+    );
+    assert!(!output.path().join("audio_quality_report.csv").exists());
+}
+
+#[test]
+fn test_analyzer_shared_across_threads() {
+    let tools = TempDir::new().unwrap();
+    let music = TempDir::new().unwrap();
+    let analyzer = Arc::new(initialized(config_with_fake_ffmpeg(tools.path())));
+
+    let handles: Vec<_> = (0..4)
+        .map(|index| {
+            let path = music.path().join(format!("{index}.flac"));
+            fs::write(&path, b"audio").unwrap();
+            let analyzer = Arc::clone(&analyzer);
+            thread::spawn(move || analyzer.analyze_file(&path))
+        })
+        .collect();
+
+    for handle in handles {
+        let metrics = handle.join().unwrap().unwrap();
+        assert_eq!(metrics.peak_amplitude_db, Some(-7.0));
+    }
+}
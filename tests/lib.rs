@@ -9,4 +9,8 @@ mod unit {
     mod test_utils;
 }
 
-// 集成测试将在单独的文件中定义
+// 集成测试模块
+mod integration {
+    mod test_async;
+    mod test_pipeline;
+}
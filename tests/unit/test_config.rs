@@ -4,6 +4,7 @@
 
 use audio_analyzer_ultimate::config::{AnalyzerConfig, FfmpegConfig, OutputConfig};
 use audio_analyzer_ultimate::types::QualityThresholds;
+use audio_analyzer_ultimate::Locale;
 use tempfile::NamedTempFile;
 
 #[test]
@@ -107,6 +108,7 @@ fn test_output_config_defaults() {
     assert_eq!(output_config.csv_filename, "audio_quality_report.csv");
     assert!(output_config.include_timing);
     assert_eq!(output_config.min_quality_score, None);
+    assert_eq!(output_config.min_quality_verdict, None);
     assert_eq!(output_config.output_dir, None);
 }
 
@@ -117,6 +119,28 @@ fn test_ffmpeg_config_defaults() {
     assert_eq!(ffmpeg_config.log_level, "info");
     assert!(ffmpeg_config.hide_banner);
     assert_eq!(ffmpeg_config.timeout_seconds, Some(300));
+    assert!(ffmpeg_config.extra_input_args.is_empty());
+    assert!(ffmpeg_config.extra_output_args.is_empty());
+}
+
+#[test]
+fn test_extra_ffmpeg_args_from_config_file() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(
+        &path,
+        "[ffmpeg]\nlog_level = \"info\"\nhide_banner = true\n\
+         extra_input_args = [\"-err_detect\", \"ignore_err\"]\n",
+    )
+    .unwrap();
+
+    let config = AnalyzerConfig::from_file(&path).unwrap();
+    assert_eq!(
+        config.ffmpeg.extra_input_args,
+        vec!["-err_detect", "ignore_err"]
+    );
+    // 未给出的附加参数默认为空
+    assert!(config.ffmpeg.extra_output_args.is_empty());
 }
 
 #[test]
@@ -154,3 +178,14 @@ fn test_quality_thresholds_logical_order() {
     assert!(thresholds.peak_good_db < thresholds.peak_medium_db);
     assert!(thresholds.peak_medium_db < thresholds.peak_clipping_db);
 }
+
+#[test]
+fn test_locale_from_config_file() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "locale = \"en\"\n").unwrap();
+
+    let config = AnalyzerConfig::from_file(&path).unwrap();
+    assert_eq!(config.locale, Some(Locale::En));
+    assert_eq!(AnalyzerConfig::default().locale, None);
+}
@@ -2,7 +2,9 @@
 //!
 //! 测试音频分析相关数据结构的功能
 
-use audio_analyzer_ultimate::types::{AnalysisProgress, AudioMetrics, AudioStats};
+use audio_analyzer_ultimate::types::{
+    AnalysisProgress, AnalysisWindow, AudioMetrics, AudioStats, QualityThresholds, SpectralVerdict,
+};
 
 #[test]
 fn test_audio_metrics_creation() {
@@ -13,9 +15,10 @@ fn test_audio_metrics_creation() {
     assert_eq!(metrics.lra, None);
     assert_eq!(metrics.peak_amplitude_db, None);
     assert_eq!(metrics.overall_rms_db, None);
-    assert_eq!(metrics.rms_db_above_16k, None);
-    assert_eq!(metrics.rms_db_above_18k, None);
-    assert_eq!(metrics.rms_db_above_20k, None);
+    assert!(metrics.highpass_rms.is_empty());
+    assert_eq!(metrics.rms_db_above(16000), None);
+    assert_eq!(metrics.rms_db_above(18000), None);
+    assert_eq!(metrics.rms_db_above(20000), None);
     assert_eq!(metrics.processing_time_ms, 0);
 }
 
@@ -33,8 +36,56 @@ fn test_audio_metrics_completeness() {
     metrics.peak_amplitude_db = Some(-6.0);
     assert!(!metrics.is_complete());
 
+    metrics.highpass_rms.insert(18000, -70.0);
+    assert!(!metrics.is_complete());
+    assert_eq!(
+        metrics.missing_metrics(),
+        vec!["integratedLufs", "overallRmsDb"]
+    );
+
     // 设置所有必需数据
-    metrics.rms_db_above_18k = Some(-70.0);
+    metrics.integrated_lufs = Some(-14.0);
+    metrics.overall_rms_db = Some(-18.0);
+    assert!(metrics.is_complete());
+    assert!(metrics.missing_metrics().is_empty());
+}
+
+#[test]
+fn test_missing_metrics() {
+    let mut metrics = AudioMetrics::new("test.wav".to_string(), 1024);
+    assert_eq!(
+        metrics.missing_metrics(),
+        vec![
+            "lra",
+            "integratedLufs",
+            "peakAmplitudeDb",
+            "overallRmsDb",
+            "rmsDbAbove18k"
+        ]
+    );
+
+    // 原生 WAV 等只有电平指标的结果：缺少响度与频谱
+    metrics.peak_amplitude_db = Some(-1.0);
+    metrics.overall_rms_db = Some(-20.0);
+    // 其他频段不能代替 18kHz
+    metrics.highpass_rms.insert(16000, -60.0);
+    assert_eq!(
+        metrics.missing_metrics(),
+        vec!["lra", "integratedLufs", "rmsDbAbove18k"]
+    );
+
+    // 只缺整体RMS时同样不完整
+    metrics.lra = Some(6.5);
+    metrics.integrated_lufs = Some(-12.0);
+    metrics.highpass_rms.insert(18000, -75.0);
+    metrics.overall_rms_db = None;
+    assert_eq!(metrics.missing_metrics(), vec!["overallRmsDb"]);
+    assert!(!metrics.is_complete());
+
+    // 可选字段缺失不影响完整性
+    metrics.overall_rms_db = Some(-20.0);
+    assert_eq!(metrics.bit_depth, None);
+    assert_eq!(metrics.phase_correlation, None);
     assert!(metrics.is_complete());
 }
 
@@ -69,9 +120,9 @@ fn test_audio_metrics_serialization() {
     metrics.lra = Some(8.5);
     metrics.peak_amplitude_db = Some(-3.2);
     metrics.overall_rms_db = Some(-18.7);
-    metrics.rms_db_above_16k = Some(-65.3);
-    metrics.rms_db_above_18k = Some(-72.1);
-    metrics.rms_db_above_20k = Some(-85.4);
+    metrics.highpass_rms.insert(16000, -65.3);
+    metrics.highpass_rms.insert(18000, -72.1);
+    metrics.highpass_rms.insert(20000, -85.4);
     metrics.processing_time_ms = 1500;
 
     // 序列化为JSON
@@ -81,6 +132,9 @@ fn test_audio_metrics_serialization() {
     assert!(json.contains("lra"));
     assert!(json.contains("peakAmplitudeDb"));
     assert!(json.contains("processingTimeMs"));
+    assert!(json.contains("rmsDbAbove16k"));
+    assert!(json.contains("rmsDbAbove18k"));
+    assert!(json.contains("rmsDbAbove20k"));
 
     // 反序列化
     let deserialized: AudioMetrics = serde_json::from_str(&json).unwrap();
@@ -89,6 +143,182 @@ fn test_audio_metrics_serialization() {
     assert_eq!(deserialized.lra, metrics.lra);
     assert_eq!(deserialized.peak_amplitude_db, metrics.peak_amplitude_db);
     assert_eq!(deserialized.processing_time_ms, metrics.processing_time_ms);
+    assert_eq!(deserialized.highpass_rms, metrics.highpass_rms);
+}
+
+#[test]
+fn test_spectral_verdict() {
+    let thresholds = QualityThresholds::default();
+    let metrics_with = |rms_18k: f64, rms_20k: f64| {
+        let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+        metrics.highpass_rms.insert(18000, rms_18k);
+        metrics.highpass_rms.insert(20000, rms_20k);
+        metrics
+    };
+
+    // 高频完全缺失：典型的MP3转FLAC
+    let fake = metrics_with(-110.0, -144.0);
+    assert_eq!(fake.spectral_verdict(&thresholds), SpectralVerdict::Fake);
+
+    // 高频衰减明显
+    let processed = metrics_with(-82.0, -100.0);
+    assert_eq!(
+        processed.spectral_verdict(&thresholds),
+        SpectralVerdict::Processed
+    );
+
+    // 18kHz正常但20kHz处被截断
+    let cutoff_20k = metrics_with(-75.0, -95.0);
+    assert_eq!(
+        cutoff_20k.spectral_verdict(&thresholds),
+        SpectralVerdict::Processed
+    );
+
+    // 高频能量充足
+    let good = metrics_with(-62.0, -68.0);
+    assert_eq!(good.spectral_verdict(&thresholds), SpectralVerdict::Good);
+
+    let borderline_good = metrics_with(-75.0, -82.0);
+    assert_eq!(
+        borderline_good.spectral_verdict(&thresholds),
+        SpectralVerdict::Good
+    );
+
+    // 缺少18kHz数据
+    let unknown = AudioMetrics::new("test.flac".to_string(), 1024);
+    assert_eq!(
+        unknown.spectral_verdict(&thresholds),
+        SpectralVerdict::Unknown
+    );
+}
+
+#[test]
+fn test_gain_to_target() {
+    let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+    assert_eq!(metrics.gain_to_target(-14.0), None);
+
+    metrics.integrated_lufs = Some(-9.5);
+    assert_eq!(metrics.gain_to_target(-14.0), Some(-4.5));
+
+    // 很安静的文件需要大幅提升，不做截断
+    metrics.integrated_lufs = Some(-30.0);
+    assert_eq!(metrics.gain_to_target(-14.0), Some(16.0));
+}
+
+#[test]
+fn test_is_overcompressed() {
+    let thresholds = QualityThresholds::default();
+    let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+    assert!(!metrics.is_overcompressed(&thresholds));
+
+    // 响且平
+    metrics.lra = Some(4.0);
+    metrics.overall_rms_db = Some(-9.0);
+    assert!(metrics.is_overcompressed(&thresholds));
+
+    // 安静的低动态录音不算过度压缩
+    metrics.overall_rms_db = Some(-24.0);
+    assert!(!metrics.is_overcompressed(&thresholds));
+
+    // 动态充足的响亮作品同样不算
+    metrics.lra = Some(10.0);
+    metrics.overall_rms_db = Some(-9.0);
+    assert!(!metrics.is_overcompressed(&thresholds));
+}
+
+#[test]
+fn test_is_effectively_silent() {
+    let thresholds = QualityThresholds::default();
+    let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+    assert!(!metrics.is_effectively_silent(&thresholds));
+
+    // 带有较长静音尾巴的正常曲目
+    metrics.silent_fraction = Some(0.3);
+    assert!(!metrics.is_effectively_silent(&thresholds));
+
+    metrics.silent_fraction = Some(1.0);
+    assert!(metrics.is_effectively_silent(&thresholds));
+}
+
+#[test]
+fn test_is_clipping() {
+    let thresholds = QualityThresholds::default();
+    let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+    assert!(!metrics.is_clipping(&thresholds));
+
+    metrics.peak_amplitude_db = Some(-1.0);
+    assert!(!metrics.is_clipping(&thresholds));
+
+    metrics.peak_amplitude_db = Some(0.0);
+    assert!(metrics.is_clipping(&thresholds));
+    // 母带预设的阈值更严格
+    metrics.peak_amplitude_db = Some(-0.5);
+    assert!(metrics.is_clipping(&QualityThresholds::mastering()));
+}
+
+#[test]
+fn test_has_phase_issues() {
+    let mut thresholds = QualityThresholds::default();
+    let mut metrics = AudioMetrics::new("test.flac".to_string(), 1024);
+    // 单声道文件没有相位数据
+    assert!(!metrics.has_phase_issues(&thresholds));
+
+    metrics.phase_correlation = Some(0.6);
+    assert!(!metrics.has_phase_issues(&thresholds));
+
+    metrics.phase_correlation = Some(-0.3);
+    assert!(metrics.has_phase_issues(&thresholds));
+
+    thresholds.phase_correlation_min = -0.5;
+    assert!(!metrics.has_phase_issues(&thresholds));
+}
+
+#[test]
+fn test_looks_transcoded() {
+    let thresholds = QualityThresholds::default();
+    let mut metrics = AudioMetrics::new("track.mp3".to_string(), 1024);
+    metrics.codec = Some("mp3".to_string());
+    metrics.bitrate_kbps = Some(320);
+    metrics.highpass_rms.insert(18000, -95.0);
+    assert!(metrics.is_lossy());
+    assert!(metrics.looks_transcoded(&thresholds));
+
+    // 低码率文件截掉高频是正常的
+    metrics.bitrate_kbps = Some(128);
+    assert!(!metrics.looks_transcoded(&thresholds));
+
+    metrics.bitrate_kbps = Some(320);
+    metrics.highpass_rms.insert(18000, -60.0);
+    assert!(!metrics.looks_transcoded(&thresholds));
+
+    // 无损文件不做此项检查
+    metrics.codec = Some("flac".to_string());
+    metrics.highpass_rms.insert(18000, -95.0);
+    assert!(!metrics.is_lossy());
+    assert!(!metrics.looks_transcoded(&thresholds));
+}
+
+#[test]
+fn test_looks_upsampled() {
+    let thresholds = QualityThresholds::default();
+    let mut metrics = AudioMetrics::new("hires.flac".to_string(), 1024);
+    metrics.highpass_rms.insert(20000, -110.0);
+    // 没有采样率信息时无法判断
+    assert!(!metrics.looks_upsampled(&thresholds));
+
+    // 44.1k 文件 20kHz 以上本就没有内容
+    metrics.sample_rate_hz = Some(44100);
+    assert!(!metrics.looks_upsampled(&thresholds));
+
+    metrics.sample_rate_hz = Some(96000);
+    assert!(metrics.looks_upsampled(&thresholds));
+
+    metrics.highpass_rms.insert(20000, -72.0);
+    assert!(!metrics.looks_upsampled(&thresholds));
+
+    // 未分析 20kHz 频段
+    metrics.highpass_rms.remove(&20000);
+    assert!(!metrics.looks_upsampled(&thresholds));
 }
 
 #[test]
@@ -177,3 +407,21 @@ fn test_analysis_progress_fields() {
     assert_eq!(progress.completed_files, 4);
     assert_eq!(progress.percentage(), 40.0);
 }
+
+#[test]
+fn test_analysis_window_length() {
+    let window = AnalysisWindow {
+        start_secs: 60.0,
+        duration_secs: Some(90.0),
+    };
+    assert_eq!(window.analyzed_length(7200.0), 90.0);
+    // 片段超出文件末尾时只计算剩余部分
+    assert_eq!(window.analyzed_length(100.0), 40.0);
+    assert_eq!(window.analyzed_length(30.0), 0.0);
+
+    let to_end = AnalysisWindow {
+        start_secs: 60.0,
+        duration_secs: None,
+    };
+    assert_eq!(to_end.analyzed_length(300.0), 240.0);
+}
@@ -96,6 +96,21 @@ fn test_ensure_dir_exists() {
     assert!(test_dir.exists());
 }
 
+#[test]
+fn test_read_file_list() {
+    let list = "/music/a.flac\r\n\n  \n/music/with space.wav\nrelative/b.mp3";
+    let paths = fs_utils::read_file_list(list.as_bytes()).unwrap();
+    assert_eq!(
+        paths,
+        [
+            Path::new("/music/a.flac"),
+            Path::new("/music/with space.wav"),
+            Path::new("relative/b.mp3"),
+        ]
+    );
+    assert!(fs_utils::read_file_list("".as_bytes()).unwrap().is_empty());
+}
+
 #[test]
 fn test_format_file_size() {
     let test_cases = vec![
@@ -233,6 +248,424 @@ fn test_scan_audio_files() {
     assert!(file_names.contains(&"test3.wav".to_string()));
 }
 
+#[test]
+fn test_scan_audio_files_filtered() {
+    let temp_dir = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string(), "mp3".to_string()];
+    let root = temp_dir.path();
+
+    // album/@eaDir 为群晖生成的缩略图目录，lossy/ 为顶层的有损副本
+    let files = [
+        "album/track.flac",
+        "album/@eaDir/track.flac",
+        "album/@eaDir/nested/deep.flac",
+        "lossy/track.mp3",
+        "album/lossy/kept.mp3",
+    ];
+    for file in files {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "fake audio content").unwrap();
+    }
+
+    let exclude = vec![
+        glob::Pattern::new("@eaDir").unwrap(),
+        glob::Pattern::new("lossy/*").unwrap(),
+    ];
+    let mut found: Vec<String> = fs_utils::scan_audio_files_filtered(root, &extensions, &exclude)
+        .unwrap()
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    found.sort();
+
+    assert_eq!(found, vec!["album/lossy/kept.mp3", "album/track.flac"]);
+
+    // 不带排除模式时行为与 scan_audio_files 一致
+    assert_eq!(
+        fs_utils::scan_audio_files_filtered(root, &extensions, &[])
+            .unwrap()
+            .len(),
+        files.len()
+    );
+}
+
+#[test]
+fn test_scan_max_depth() {
+    let temp_dir = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string()];
+    let root = temp_dir.path();
+
+    std::fs::create_dir_all(root.join("artist/album")).unwrap();
+    std::fs::write(root.join("top.flac"), "fake").unwrap();
+    std::fs::write(root.join("artist/album/track.flac"), "fake").unwrap();
+
+    let options = fs_utils::ScanOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    };
+    let found = fs_utils::scan_audio_files_with_options(root, &extensions, &options).unwrap();
+    assert_eq!(found, vec![root.join("top.flac")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_follow_symlinks() {
+    let library = TempDir::new().unwrap();
+    let scan_root = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string()];
+
+    // 顶层只有指向专辑目录的符号链接，专辑内还有一个指向自身的循环链接
+    let album = library.path().join("album");
+    std::fs::create_dir(&album).unwrap();
+    std::fs::write(album.join("track.flac"), "fake").unwrap();
+    std::os::unix::fs::symlink(&album, scan_root.path().join("album")).unwrap();
+    std::os::unix::fs::symlink(&album, album.join("loop")).unwrap();
+
+    // 默认不跟随符号链接
+    assert!(fs_utils::scan_audio_files(scan_root.path(), &extensions)
+        .unwrap()
+        .is_empty());
+
+    let options = fs_utils::ScanOptions {
+        follow_symlinks: true,
+        ..Default::default()
+    };
+    let found =
+        fs_utils::scan_audio_files_with_options(scan_root.path(), &extensions, &options).unwrap();
+    assert_eq!(found, vec![scan_root.path().join("album/track.flac")]);
+}
+
+#[test]
+fn test_scan_audio_files_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string(), "mp3".to_string()];
+    let root = temp_dir.path();
+
+    std::fs::create_dir_all(root.join("album/@eaDir")).unwrap();
+    std::fs::write(root.join("album/01.flac"), "fake").unwrap();
+    std::fs::write(root.join("album/cover.jpg"), "fake").unwrap();
+    std::fs::write(root.join("album/notes.txt"), "fake").unwrap();
+    std::fs::write(root.join("album/@eaDir/thumb.jpg"), "fake").unwrap();
+    std::fs::write(root.join("02.mp3"), "fake").unwrap();
+
+    let options = fs_utils::ScanOptions {
+        exclude: vec![glob::Pattern::new("@eaDir").unwrap()],
+        ..Default::default()
+    };
+    let report = fs_utils::scan_audio_files_report(root, &extensions, &options);
+    let mut matched = report.matched.clone();
+    matched.sort();
+    assert_eq!(
+        matched,
+        vec![root.join("02.mp3"), root.join("album/01.flac")]
+    );
+    // 被排除的目录中的文件不计入跳过数
+    assert_eq!(report.skipped_unsupported, 2);
+    assert!(report.skipped_errors.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_report_keeps_error_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string()];
+    let root = temp_dir.path();
+
+    std::fs::write(root.join("track.flac"), "fake").unwrap();
+    let broken = root.join("broken");
+    std::os::unix::fs::symlink(root.join("missing"), &broken).unwrap();
+
+    // 跟随符号链接时失效的链接无法读取，记录其路径后继续扫描
+    let options = fs_utils::ScanOptions {
+        follow_symlinks: true,
+        ..Default::default()
+    };
+    let report = fs_utils::scan_audio_files_report(root, &extensions, &options);
+    assert_eq!(report.matched, vec![root.join("track.flac")]);
+    assert_eq!(report.skipped_errors.len(), 1);
+    let (path, error) = &report.skipped_errors[0];
+    assert_eq!(path, &broken);
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_parallel_scan_matches_sequential() {
+    let temp_dir = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string(), "mp3".to_string()];
+    let root = temp_dir.path();
+
+    for album in ["a", "b/c", "b/c/d", "lossy/e", "f/@eaDir"] {
+        std::fs::create_dir_all(root.join(album)).unwrap();
+        for name in ["01.flac", "02.MP3", "cover.jpg"] {
+            std::fs::write(root.join(album).join(name), "fake").unwrap();
+        }
+    }
+    std::fs::write(root.join("top.flac"), "fake").unwrap();
+
+    let sorted_scan = |options: &fs_utils::ScanOptions| {
+        let mut found =
+            fs_utils::scan_audio_files_with_options(root, &extensions, options).unwrap();
+        found.sort();
+        found
+    };
+    for sequential in [
+        fs_utils::ScanOptions::default(),
+        fs_utils::ScanOptions {
+            exclude: vec![
+                glob::Pattern::new("@eaDir").unwrap(),
+                glob::Pattern::new("lossy/*").unwrap(),
+            ],
+            ..Default::default()
+        },
+        fs_utils::ScanOptions {
+            max_depth: Some(3),
+            ..Default::default()
+        },
+    ] {
+        let parallel = fs_utils::ScanOptions {
+            parallel_scan: true,
+            ..sequential.clone()
+        };
+        let expected = sorted_scan(&sequential);
+        assert!(!expected.is_empty());
+        assert_eq!(sorted_scan(&parallel), expected);
+
+        let report = fs_utils::scan_audio_files_report(root, &extensions, &parallel);
+        let sequential_report = fs_utils::scan_audio_files_report(root, &extensions, &sequential);
+        assert_eq!(report.matched.len(), expected.len());
+        assert_eq!(
+            report.skipped_unsupported,
+            sequential_report.skipped_unsupported
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_parallel_scan_follow_symlinks() {
+    let library = TempDir::new().unwrap();
+    let scan_root = TempDir::new().unwrap();
+    let extensions = vec!["flac".to_string()];
+
+    let album = library.path().join("album");
+    std::fs::create_dir(&album).unwrap();
+    std::fs::write(album.join("track.flac"), "fake").unwrap();
+    std::os::unix::fs::symlink(&album, scan_root.path().join("album")).unwrap();
+    std::os::unix::fs::symlink(&album, album.join("loop")).unwrap();
+    std::os::unix::fs::symlink(
+        scan_root.path().join("missing"),
+        scan_root.path().join("broken"),
+    )
+    .unwrap();
+
+    // 不跟随符号链接时链接本身被忽略
+    let options = fs_utils::ScanOptions {
+        parallel_scan: true,
+        ..Default::default()
+    };
+    assert!(
+        fs_utils::scan_audio_files_with_options(scan_root.path(), &extensions, &options)
+            .unwrap()
+            .is_empty()
+    );
+
+    // 跟随时循环链接被跳过，失效的链接记录为无法读取
+    let options = fs_utils::ScanOptions {
+        follow_symlinks: true,
+        parallel_scan: true,
+        ..Default::default()
+    };
+    let report = fs_utils::scan_audio_files_report(scan_root.path(), &extensions, &options);
+    assert_eq!(
+        report.matched,
+        vec![scan_root.path().join("album/track.flac")]
+    );
+    assert_eq!(report.skipped_errors.len(), 1);
+    assert_eq!(report.skipped_errors[0].0, scan_root.path().join("broken"));
+}
+
+#[test]
+fn test_write_atomic() {
+    let temp_dir = TempDir::new().unwrap();
+    let target = temp_dir.path().join("report.csv");
+
+    fs_utils::write_atomic(&target, b"first").unwrap();
+    assert_eq!(std::fs::read(&target).unwrap(), b"first");
+
+    // 覆盖已有文件，且目录中不留下临时文件
+    fs_utils::write_atomic(&target, b"second").unwrap();
+    assert_eq!(std::fs::read(&target).unwrap(), b"second");
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_semaphore_limits_concurrency() {
+    use audio_analyzer_ultimate::utils::process_utils::Semaphore;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let semaphore = Semaphore::new(2);
+    let running = AtomicUsize::new(0);
+    let max_running = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                let _permit = semaphore.acquire();
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(10));
+                running.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_find_executable_in() {
+    use audio_analyzer_ultimate::utils::process_utils::find_executable_in;
+    use std::os::unix::fs::PermissionsExt;
+
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+
+    // 没有执行权限的同名文件被跳过
+    let not_executable = first.path().join("audio_analyzer");
+    std::fs::write(&not_executable, "").unwrap();
+    let executable = second.path().join("audio_analyzer");
+    std::fs::write(&executable, "").unwrap();
+    std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let search_path = std::env::join_paths([first.path(), second.path()]).unwrap();
+    assert_eq!(
+        find_executable_in("audio_analyzer", &search_path),
+        Some(executable)
+    );
+    assert_eq!(find_executable_in("missing", &search_path), None);
+}
+
+#[test]
+fn test_is_transient_error() {
+    use audio_analyzer_ultimate::utils::process_utils::{
+        is_transient_error, run_command_capture_stderr,
+    };
+    use audio_analyzer_ultimate::{AnalyzerError, FfmpegErrorKind};
+    use std::io::{Error, ErrorKind};
+    use std::process::Command;
+
+    let io = |kind: ErrorKind| AnalyzerError::Io(Error::from(kind));
+    assert!(is_transient_error(&io(ErrorKind::WouldBlock)));
+    assert!(is_transient_error(&io(ErrorKind::OutOfMemory)));
+    assert!(!is_transient_error(&io(ErrorKind::NotFound)));
+
+    // 被信号终止可以重试，非零退出码不行
+    let killed = run_command_capture_stderr({
+        let mut command = Command::new("sh");
+        command.args(["-c", "kill -9 $$"]);
+        command
+    })
+    .unwrap_err();
+    assert!(is_transient_error(&killed));
+    assert!(matches!(
+        killed,
+        AnalyzerError::FfmpegError {
+            kind: FfmpegErrorKind::Signal,
+            ..
+        }
+    ));
+    let failed = run_command_capture_stderr({
+        let mut command = Command::new("sh");
+        command.args(["-c", "exit 1"]);
+        command
+    })
+    .unwrap_err();
+    assert!(!is_transient_error(&failed));
+    assert!(matches!(
+        failed,
+        AnalyzerError::FfmpegError {
+            kind: FfmpegErrorKind::Exit(1),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_is_timeout_error() {
+    use audio_analyzer_ultimate::utils::process_utils::{
+        is_timeout_error, run_command_capture_stderr_timeout,
+    };
+    use audio_analyzer_ultimate::AnalyzerError;
+    use std::process::Command;
+
+    let mut command = Command::new("sh");
+    command.args(["-c", "echo started >&2; exec sleep 5"]);
+    let error =
+        run_command_capture_stderr_timeout(command, Some(Duration::from_millis(200))).unwrap_err();
+    assert!(is_timeout_error(&error));
+    let AnalyzerError::FfmpegError { stderr, .. } = error else {
+        unreachable!();
+    };
+    assert_eq!(stderr.as_deref(), Some("started\n"));
+
+    assert!(!is_timeout_error(&AnalyzerError::FfmpegError {
+        kind: audio_analyzer_ultimate::FfmpegErrorKind::Exit(1),
+        message: "进程异常退出，退出代码: 1".to_string(),
+        stderr: None,
+    }));
+}
+
+#[test]
+fn test_clone_command() {
+    use audio_analyzer_ultimate::utils::process_utils::clone_command;
+    use std::process::Command;
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-i", "a.flac"])
+        .env("LC_ALL", "C")
+        .env_remove("FFREPORT")
+        .current_dir("/tmp");
+    let clone = clone_command(&command);
+
+    assert_eq!(clone.get_program(), "ffmpeg");
+    assert!(clone.get_args().eq(["-i", "a.flac"]));
+    assert!(clone.get_envs().eq(command.get_envs()));
+    assert_eq!(clone.get_current_dir(), Some(Path::new("/tmp")));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_set_low_priority() {
+    use audio_analyzer_ultimate::utils::process_utils::{set_low_priority, LOW_PRIORITY_NICE};
+    use std::process::Command;
+
+    let niceness = |low_priority: bool| {
+        let mut command = Command::new("sh");
+        command.args(["-c", "ps -o ni= -p $$"]);
+        if low_priority {
+            set_low_priority(&mut command);
+        }
+        let output = command.output().unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim()
+            .parse::<i32>()
+            .unwrap()
+    };
+
+    let normal = niceness(false);
+    // nice 值上限为 19
+    assert_eq!(niceness(true), (normal + LOW_PRIORITY_NICE).min(19));
+}
+
 #[test]
 fn test_get_file_size() {
     let temp_dir = TempDir::new().unwrap();
@@ -243,4 +676,8 @@ fn test_get_file_size() {
 
     let size = fs_utils::get_file_size(&test_file).unwrap();
     assert_eq!(size, content.len() as u64);
+
+    // 无法读取的文件按 0 计算
+    let missing = temp_dir.path().join("missing.txt");
+    assert_eq!(fs_utils::total_file_size(&[test_file.clone(), missing]), 13);
 }